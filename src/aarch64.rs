@@ -1,70 +1,81 @@
 use crate::registers_info::Register;
 
-// let aa64isar0: u64;
-// unsafe { asm!("mrs $0, ID_AA64ISAR0_EL1" : "=r"(aa64isar0)); }
-
+/// Read a register of the current core with `MRS`. EL0 cannot access
+/// the ID registers, the kernel emulates the access, e.g., Linux with
+/// `HWCAP_CPUID`. Returns `None` for registers without an emulation,
+/// e.g., the external debug registers.
 #[cfg(target_arch = "aarch64")]
-pub(crate) fn read_register(register: Register) -> u64 {
+pub(crate) fn read_register(register: Register) -> Option<u64> {
     use std::arch::asm;
-    let tmp: u64;
 
-    match register {
-        Register::ID_AA64DFR0_EL1 => unsafe {
-            asm!("mrs {tmp}, ID_AA64DFR0_EL1", tmp = out(reg) tmp);
-        },
-        Register::ID_AA64ISAR0_EL1 => unsafe {
-            asm!("mrs {tmp}, ID_AA64ISAR0_EL1_EL1", tmp = out(reg) tmp);
-        },
-        Register::ID_AA64ISAR1_EL1 => unsafe {
-            asm!("mrs {tmp}, ID_AA64ISAR1_EL1", tmp = out(reg) tmp);
-        },
-        Register::ID_AA64MMFR0_EL1 => unsafe {
-            asm!("mrs {tmp}, ID_AA64MMFR0_EL1", tmp = out(reg) tmp);
-        },
-        Register::ID_AA64MMFR1_EL1 => unsafe {
-            asm!("mrs {tmp}, ID_AA64MMFR1_EL1", tmp = out(reg) tmp);
-        },
-        Register::ID_AA64PFR1_EL1 => unsafe {
-            asm!("mrs {tmp}, ID_AA64PFR1_EL1", tmp = out(reg) tmp);
-        },
-        Register::ID_AA64PFR0_EL1 => unsafe {
-            asm!("mrs {tmp}, ID_AA64PFR0_EL1", tmp = out(reg) tmp);
-        },
-        Register::ID_AA64MMFR2_EL1 => unsafe {
-            asm!("mrs {tmp}, ID_AA64MMFR2_EL1", tmp = out(reg) tmp);
-        },
-        Register::EDDEVID => unsafe {
-            asm!("mrs {tmp}, EDDEVID", tmp = out(reg) tmp);
-        },
-        Register::ID_AA64SMFR0_EL1 => unsafe {
-            asm!("mrs {tmp}, ID_AA64SMFR0_EL1", tmp = out(reg) tmp);
-        },
-        Register::ID_AA64ISAR2_EL1 => unsafe {
-            asm!("mrs {tmp}, ID_AA64ISAR2_EL1", tmp = out(reg) tmp);
-        },
-        Register::CNTID => unsafe {
-            asm!("mrs {tmp}, CNTID", tmp = out(reg) tmp);
-        },
-        Register::ID_AA64ZFR0_EL1 => unsafe {
-            asm!("mrs {tmp}, ID_AA64ZFR0_EL1", tmp = out(reg) tmp);
-        },
-        Register::TRCDEVARCH => unsafe {
-            asm!("mrs {tmp}, TRCDEVARCH", tmp = out(reg) tmp);
-        },
-        Register::CTR_EL0 => unsafe {
-            asm!("mrs {tmp}, CTR_EL0", tmp = out(reg) tmp);
-        },
-        Register::NoRegister => tmp = u64::MAX,
+    macro_rules! mrs {
+        ($name:literal) => {{
+            let value: u64;
+            // SAFETY: reading an ID register or CTR_EL0 has no side
+            // effects, and the callers check that the kernel emulates
+            // the access
+            unsafe {
+                asm!(
+                    concat!("mrs {value}, ", $name),
+                    value = out(reg) value,
+                    options(nomem, nostack, preserves_flags)
+                );
+            }
+            Some(value)
+        }};
     }
 
-    tmp
+    // the generic encodings are for registers that older assemblers
+    // do not know
+    match register {
+        Register::ID_AA64DFR0_EL1 => mrs!("ID_AA64DFR0_EL1"),
+        Register::ID_AA64DFR1_EL1 => mrs!("ID_AA64DFR1_EL1"),
+        Register::ID_AA64ISAR0_EL1 => mrs!("ID_AA64ISAR0_EL1"),
+        Register::ID_AA64ISAR1_EL1 => mrs!("ID_AA64ISAR1_EL1"),
+        Register::ID_AA64ISAR2_EL1 => mrs!("S3_0_C0_C6_2"),
+        Register::ID_AA64ISAR3_EL1 => mrs!("S3_0_C0_C6_3"),
+        Register::ID_AA64MMFR0_EL1 => mrs!("ID_AA64MMFR0_EL1"),
+        Register::ID_AA64MMFR1_EL1 => mrs!("ID_AA64MMFR1_EL1"),
+        Register::ID_AA64MMFR2_EL1 => mrs!("S3_0_C0_C7_2"),
+        Register::ID_AA64MMFR3_EL1 => mrs!("S3_0_C0_C7_3"),
+        Register::ID_AA64MMFR4_EL1 => mrs!("S3_0_C0_C7_4"),
+        Register::ID_AA64PFR0_EL1 => mrs!("ID_AA64PFR0_EL1"),
+        Register::ID_AA64PFR1_EL1 => mrs!("ID_AA64PFR1_EL1"),
+        Register::ID_AA64PFR2_EL1 => mrs!("S3_0_C0_C4_2"),
+        Register::ID_AA64ZFR0_EL1 => mrs!("S3_0_C0_C4_4"),
+        Register::ID_AA64SMFR0_EL1 => mrs!("S3_0_C0_C4_5"),
+        Register::ID_AA64FPFR0_EL1 => mrs!("S3_0_C0_C4_7"),
+        Register::CTR_EL0 => mrs!("CTR_EL0"),
+        // memory-mapped or only accessible from EL1
+        Register::EDDEVID | Register::CNTID | Register::TRCDEVARCH => None,
+        Register::NoRegister => None,
+    }
 }
 
 #[cfg(not(target_arch = "aarch64"))]
-pub(crate) fn read_register(_register: Register) -> u64 {
-    0
+pub(crate) fn read_register(_register: Register) -> Option<u64> {
+    None
 }
 
-// FIXME: audit switch statement and asm!s
+/// Read `DCZID_EL0`, which EL0 can always access
+#[cfg(target_arch = "aarch64")]
+pub(crate) fn read_dczid() -> Option<u64> {
+    use std::arch::asm;
 
-// __rsr64
+    let value: u64;
+    // SAFETY: DCZID_EL0 is readable at EL0 and reading it has no side
+    // effects
+    unsafe {
+        asm!(
+            "mrs {value}, DCZID_EL0",
+            value = out(reg) value,
+            options(nomem, nostack, preserves_flags)
+        );
+    }
+    Some(value)
+}
+
+#[cfg(not(target_arch = "aarch64"))]
+pub(crate) fn read_dczid() -> Option<u64> {
+    None
+}
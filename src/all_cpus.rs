@@ -0,0 +1,197 @@
+use crate::cpuinfo::CpuInfo;
+use crate::Feature;
+use std::collections::HashSet;
+
+/// The features of all online CPUs of a system, see [`check_features_all_cpus`].
+///
+/// Code that migrates between cores can only rely on the
+/// [intersection](SystemFeatures::intersection) of the features.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SystemFeatures {
+    per_cpu: Vec<(usize, HashSet<Feature>)>,
+    intersection: HashSet<Feature>,
+    union: HashSet<Feature>,
+}
+
+impl SystemFeatures {
+    /// Merge the features of individual CPUs
+    ///
+    /// ```rust
+    /// use aarch64_features::{Feature, SystemFeatures};
+    /// use std::collections::HashSet;
+    ///
+    /// let little = HashSet::from([Feature::FEAT_LSE]);
+    /// let big = HashSet::from([Feature::FEAT_LSE, Feature::FEAT_DotProd]);
+    ///
+    /// let system = SystemFeatures::from_per_cpu(vec![(0, little), (4, big)]);
+    ///
+    /// assert!(system.intersection().contains(&Feature::FEAT_LSE));
+    /// assert!(system.asymmetric().contains(&Feature::FEAT_DotProd));
+    /// ```
+    pub fn from_per_cpu(mut per_cpu: Vec<(usize, HashSet<Feature>)>) -> Self {
+        per_cpu.sort_by_key(|(cpu, _)| *cpu);
+
+        let mut union = HashSet::new();
+        let mut intersection: Option<HashSet<Feature>> = None;
+
+        for (_, features) in &per_cpu {
+            union.extend(features.iter().copied());
+            intersection = Some(match intersection {
+                None => features.clone(),
+                Some(common) => common.intersection(features).copied().collect(),
+            });
+        }
+
+        SystemFeatures {
+            per_cpu,
+            intersection: intersection.unwrap_or_default(),
+            union,
+        }
+    }
+
+    /// Merge the `Features` lines of the processors of a
+    /// `/proc/cpuinfo` file, e.g., from another machine. The features
+    /// are those of the hardware capabilities, see
+    /// [`cpuinfo`](crate::cpuinfo).
+    pub fn from_cpuinfo(cpus: &[CpuInfo]) -> Self {
+        Self::from_per_cpu(
+            cpus.iter()
+                .map(|cpu| (cpu.processor(), cpu.features().clone()))
+                .collect(),
+        )
+    }
+
+    /// Collect the features of the given CPUs with `collector`. CPUs
+    /// for which the collector returns `None` are skipped.
+    pub fn collect<F>(cpus: &[usize], collector: F) -> Self
+    where
+        F: Fn(usize) -> Option<HashSet<Feature>>,
+    {
+        let per_cpu = cpus
+            .iter()
+            .filter_map(|cpu| collector(*cpu).map(|features| (*cpu, features)))
+            .collect();
+
+        Self::from_per_cpu(per_cpu)
+    }
+
+    /// The features of each CPU, sorted by the CPU number
+    pub fn per_cpu(&self) -> &[(usize, HashSet<Feature>)] {
+        &self.per_cpu
+    }
+
+    /// The features that all CPUs have
+    pub fn intersection(&self) -> &HashSet<Feature> {
+        &self.intersection
+    }
+
+    /// The features that at least one CPU has
+    pub fn union(&self) -> &HashSet<Feature> {
+        &self.union
+    }
+
+    /// The features that some, but not all CPUs have
+    pub fn asymmetric(&self) -> HashSet<Feature> {
+        self.union.difference(&self.intersection).copied().collect()
+    }
+
+    /// Whether all CPUs have the same features
+    pub fn is_symmetric(&self) -> bool {
+        self.union == self.intersection
+    }
+}
+
+/// test for all aarch64 features on every online CPU.
+///
+/// On Linux, the features of each processor come from its `Features`
+/// line in `/proc/cpuinfo`, see [`SystemFeatures::from_cpuinfo`]. The
+/// `MRS` emulation of the kernel cannot be used, it reports sanitised
+/// system-wide values on every CPU. On other systems, the features of
+/// the current CPU are reported as CPU 0.
+pub fn check_features_all_cpus() -> SystemFeatures {
+    #[cfg(all(target_arch = "aarch64", target_os = "linux"))]
+    {
+        if let Ok(cpus) = crate::cpuinfo::read() {
+            if !cpus.is_empty() {
+                return SystemFeatures::from_cpuinfo(&cpus);
+            }
+        }
+    }
+
+    SystemFeatures::from_per_cpu(vec![(0, crate::check_features())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symmetric() {
+        let features = HashSet::from([Feature::FEAT_LSE, Feature::FEAT_LRCPC]);
+        let system = SystemFeatures::collect(&[3, 1, 2], |_| Some(features.clone()));
+
+        assert!(system.is_symmetric());
+        assert!(system.asymmetric().is_empty());
+        assert_eq!(system.intersection(), &features);
+        assert_eq!(system.union(), &features);
+        let cpus: Vec<usize> = system.per_cpu().iter().map(|(cpu, _)| *cpu).collect();
+        assert_eq!(cpus, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_asymmetric() {
+        let system = SystemFeatures::collect(&[0, 1, 4, 5], |cpu| match cpu {
+            0 | 1 => Some(HashSet::from([Feature::FEAT_LSE, Feature::FEAT_FP16])),
+            4 => Some(HashSet::from([
+                Feature::FEAT_LSE,
+                Feature::FEAT_FP16,
+                Feature::FEAT_DotProd,
+            ])),
+            // unknown
+            _ => None,
+        });
+
+        assert_eq!(system.per_cpu().len(), 3);
+        assert!(!system.is_symmetric());
+        assert_eq!(
+            system.intersection(),
+            &HashSet::from([Feature::FEAT_LSE, Feature::FEAT_FP16])
+        );
+        assert_eq!(system.union().len(), 3);
+        assert_eq!(system.asymmetric(), HashSet::from([Feature::FEAT_DotProd]));
+    }
+
+    #[test]
+    fn test_cpuinfo() {
+        // a kernel that reports the features of each processor
+        let cpus = crate::cpuinfo::parse(
+            "processor\t: 0
+Features\t: fp asimd atomics
+CPU implementer\t: 0x41
+CPU part\t: 0xd05
+
+processor\t: 4
+Features\t: fp asimd atomics asimddp
+CPU implementer\t: 0x41
+CPU part\t: 0xd0b
+",
+        );
+
+        let system = SystemFeatures::from_cpuinfo(&cpus);
+
+        let cpus: Vec<usize> = system.per_cpu().iter().map(|(cpu, _)| *cpu).collect();
+        assert_eq!(cpus, vec![0, 4]);
+        assert!(!system.is_symmetric());
+        assert!(system.intersection().contains(&Feature::FEAT_LSE));
+        assert_eq!(system.asymmetric(), HashSet::from([Feature::FEAT_DotProd]));
+    }
+
+    #[test]
+    fn test_no_cpus() {
+        let system = SystemFeatures::collect(&[0, 1], |_| None);
+
+        assert!(system.per_cpu().is_empty());
+        assert!(system.intersection().is_empty());
+        assert!(system.union().is_empty());
+    }
+}
@@ -0,0 +1,171 @@
+//! Helpers for build scripts of downstream crates. They emit one
+//! `cargo:rustc-cfg=aarch64_feat="<name>"` line per detected feature,
+//! where the name is [`Feature::lowercase_name`], and declare the cfg
+//! with `cargo:rustc-check-cfg` for every [`Feature`].
+//!
+//! The features come from a register dump in the format of
+//! [`RegisterSnapshot`] if the environment variable [`SNAPSHOT_ENV`](crate::build_support::SNAPSHOT_ENV)
+//! names a file. Otherwise the build host is probed with
+//! [`check_features`]. When cross-compiling without a dump, no
+//! features are reported.
+//!
+//! ```rust,no_run
+//! // build.rs
+//! fn main() {
+//!     aarch64_features::build_support::emit();
+//! }
+//! ```
+//!
+//! ```rust,ignore
+//! #[cfg(aarch64_feat = "lse")]
+//! fn fetch_add(value: &AtomicU64) -> u64 {
+//!     value.fetch_add(1, Ordering::Relaxed)
+//! }
+//! ```
+
+use crate::{check_features, features_from_snapshot, Feature, RegisterSnapshot};
+use std::collections::{BTreeSet, HashSet};
+use std::env;
+use std::path::Path;
+use strum::IntoEnumIterator;
+
+/// The environment variable with the path of a register dump
+pub const SNAPSHOT_ENV: &str = "AARCH64_FEATURES_SNAPSHOT";
+
+/// The name of the emitted cfg
+pub const CFG_NAME: &str = "aarch64_feat";
+
+/// Detect the features and print the cargo directives for them.
+/// Panics if the register dump of [`SNAPSHOT_ENV`] cannot be read.
+pub fn emit() {
+    println!("cargo:rerun-if-env-changed={SNAPSHOT_ENV}");
+    if let Some(path) = env::var_os(SNAPSHOT_ENV) {
+        println!("cargo:rerun-if-changed={}", Path::new(&path).display());
+    }
+
+    let features = match detect() {
+        Ok(Some(features)) => features,
+        Ok(None) => {
+            println!("cargo:warning=aarch64_features: cross-compiling without {SNAPSHOT_ENV}, no features detected");
+            HashSet::new()
+        }
+        Err(err) => panic!("aarch64_features: {err}"),
+    };
+
+    for directive in directives(&features) {
+        println!("{directive}");
+    }
+}
+
+/// Detect the features for the crate that is being built. Returns
+/// `None` when cross-compiling without a register dump, i.e., the
+/// build host is not the target.
+pub fn detect() -> Result<Option<HashSet<Feature>>, String> {
+    if let Some(path) = env::var_os(SNAPSHOT_ENV) {
+        return read_snapshot(Path::new(&path))
+            .map(|snapshot| Some(features_from_snapshot(&snapshot)));
+    }
+
+    let host = env::var("HOST").ok();
+    let target = env::var("TARGET").ok();
+    if is_cross_compiling(host.as_deref(), target.as_deref()) {
+        return Ok(None);
+    }
+
+    Ok(Some(check_features()))
+}
+
+/// The cargo directives for a set of features: the declaration of the
+/// cfg and one `rustc-cfg` line per feature, sorted by the name
+pub fn directives(features: &HashSet<Feature>) -> Vec<String> {
+    let names: BTreeSet<String> = features.iter().map(Feature::lowercase_name).collect();
+
+    let mut directives = vec![check_cfg_directive()];
+    directives.extend(
+        names
+            .iter()
+            .map(|name| format!("cargo:rustc-cfg={CFG_NAME}=\"{name}\"")),
+    );
+
+    directives
+}
+
+/// The `rustc-check-cfg` directive with the names of all features
+pub fn check_cfg_directive() -> String {
+    let names: BTreeSet<String> = Feature::iter().map(|feat| feat.lowercase_name()).collect();
+    let values: Vec<String> = names.iter().map(|name| format!("\"{name}\"")).collect();
+
+    format!(
+        "cargo:rustc-check-cfg=cfg({CFG_NAME}, values({}))",
+        values.join(", ")
+    )
+}
+
+fn read_snapshot(path: &Path) -> Result<RegisterSnapshot, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| format!("cannot read {}: {err}", path.display()))?;
+
+    text.parse()
+        .map_err(|err| format!("invalid register dump {}: {err}", path.display()))
+}
+
+/// Cargo sets `HOST` and `TARGET` for build scripts. Without them, the
+/// build host is assumed to be the target.
+fn is_cross_compiling(host: Option<&str>, target: Option<&str>) -> bool {
+    match (host, target) {
+        (Some(host), Some(target)) => host != target,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::Fixture;
+
+    #[test]
+    fn test_directives() {
+        let features = HashSet::from([Feature::FEAT_LSE, Feature::FEAT_LRCPC]);
+
+        let directives = directives(&features);
+
+        assert_eq!(directives.len(), 3);
+        assert!(directives[0].starts_with("cargo:rustc-check-cfg=cfg(aarch64_feat, values("));
+        assert_eq!(directives[1], "cargo:rustc-cfg=aarch64_feat=\"lrcpc\"");
+        assert_eq!(directives[2], "cargo:rustc-cfg=aarch64_feat=\"lse\"");
+    }
+
+    #[test]
+    fn test_check_cfg() {
+        let directive = check_cfg_directive();
+
+        assert!(directive.contains("\"lse\", "));
+        assert!(directive.contains("\"sve2p1\""));
+        assert!(directive.ends_with("))"));
+        assert_eq!(directive.matches("\"fp8\"").count(), 1);
+    }
+
+    #[test]
+    fn test_cross_compiling() {
+        assert!(is_cross_compiling(
+            Some("x86_64-unknown-linux-gnu"),
+            Some("aarch64-unknown-linux-gnu")
+        ));
+        assert!(!is_cross_compiling(
+            Some("aarch64-unknown-linux-gnu"),
+            Some("aarch64-unknown-linux-gnu")
+        ));
+        assert!(!is_cross_compiling(None, None));
+    }
+
+    #[test]
+    fn test_read_snapshot() {
+        let fixture = Fixture::new("snapshot");
+        fixture.write("", &[("snapshot", "ID_AA64ISAR0_EL1=0x0000000000200000\n")]);
+
+        let snapshot = read_snapshot(&fixture.root().join("snapshot")).unwrap();
+
+        assert!(features_from_snapshot(&snapshot).contains(&Feature::FEAT_LSE));
+        assert!(read_snapshot(Path::new("/nonexistent/aarch64_features")).is_err());
+    }
+}
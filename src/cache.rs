@@ -0,0 +1,496 @@
+//! Decoder for the cache geometry in `CTR_EL0` and `DCZID_EL0`, and
+//! reader for the cache hierarchy that the Linux kernel exports in
+//! sysfs, i.e., `/sys/devices/system/cpu/cpu*/cache/index*`.
+//!
+//! `CTR_EL0` reports the smallest cache lines of all caches that the
+//! core can see, which is the safe granule for cache maintenance.
+//! `DCZID_EL0` reports the block size of `DC ZVA`.
+//!
+//! ```rust
+//! use aarch64_features::cache::{CacheType, DataCacheZeroId, L1IcachePolicy};
+//!
+//! // 64-byte cache lines
+//! let ctr = CacheType::decode(0x8444_c004);
+//!
+//! assert_eq!(ctr.dcache_line_size(), 64);
+//! assert_eq!(ctr.icache_line_size(), 64);
+//! assert_eq!(ctr.l1_icache_policy(), L1IcachePolicy::Pipt);
+//!
+//! let dczid = DataCacheZeroId::decode(0b0_0100);
+//!
+//! assert_eq!(dczid.block_size(), 64);
+//! assert!(!dczid.prohibited());
+//! ```
+
+use crate::aarch64::{read_dczid, read_register};
+use crate::registers_info::Register;
+use crate::sysfs::{cpus, parse_cpu_list};
+use std::io;
+use std::path::Path;
+
+/// The largest granules if `CTR_EL0` does not report them
+const MAX_GRANULE: u64 = 2048;
+
+/// The indexing and tagging policy of the level 1 instruction cache,
+/// `CTR_EL0.L1Ip`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum L1IcachePolicy {
+    /// VMID-aware physical index, physical tag, see `FEAT_VPIPT`
+    Vpipt,
+    /// ASID-tagged virtual index, virtual tag
+    Aivivt,
+    /// Virtual index, physical tag
+    Vipt,
+    /// Physical index, physical tag
+    Pipt,
+}
+
+/// The decoded Cache Type Register, `CTR_EL0`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CacheType {
+    value: u64,
+}
+
+impl CacheType {
+    /// Decode a raw value of `CTR_EL0`
+    pub fn decode(value: u64) -> Self {
+        CacheType { value }
+    }
+
+    /// Read `CTR_EL0` of the current core. Returns `None` if it is not
+    /// AArch64.
+    pub fn read() -> Option<Self> {
+        read_register(Register::CTR_EL0).map(CacheType::decode)
+    }
+
+    /// The raw value
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// The smallest instruction cache line in bytes, `IminLine`
+    pub fn icache_line_size(&self) -> u64 {
+        words(self.field(0))
+    }
+
+    /// The indexing and tagging policy of the level 1 instruction
+    /// cache, `L1Ip`
+    pub fn l1_icache_policy(&self) -> L1IcachePolicy {
+        match self.field(14) & 0b11 {
+            0b00 => L1IcachePolicy::Vpipt,
+            0b01 => L1IcachePolicy::Aivivt,
+            0b10 => L1IcachePolicy::Vipt,
+            _ => L1IcachePolicy::Pipt,
+        }
+    }
+
+    /// The smallest data or unified cache line in bytes, `DminLine`
+    pub fn dcache_line_size(&self) -> u64 {
+        words(self.field(16))
+    }
+
+    /// The exclusives reservation granule in bytes, `ERG`. Returns
+    /// `None` if the core does not report it, then it is at most 2KB.
+    pub fn exclusives_reservation_granule(&self) -> Option<u64> {
+        granule(self.field(20))
+    }
+
+    /// The cache writeback granule in bytes, `CWG`. Returns `None` if
+    /// the core does not report it, then it is at most 2KB.
+    pub fn cache_writeback_granule(&self) -> Option<u64> {
+        granule(self.field(24))
+    }
+
+    /// The cache writeback granule in bytes or 2KB if the core does
+    /// not report it. Use it to pad data that is written by DMA.
+    pub fn max_writeback_granule(&self) -> u64 {
+        self.cache_writeback_granule().unwrap_or(MAX_GRANULE)
+    }
+
+    /// Whether cleaning the data cache to the point of unification is
+    /// not required for instruction to data coherence, `IDC`
+    pub fn idc(&self) -> bool {
+        self.value >> 28 & 0b1 == 0b1
+    }
+
+    /// Whether invalidating the instruction cache to the point of
+    /// unification is not required for instruction to data coherence,
+    /// `DIC`
+    pub fn dic(&self) -> bool {
+        self.value >> 29 & 0b1 == 0b1
+    }
+
+    /// the 4-bit field at `lsb`
+    fn field(&self, lsb: u64) -> u64 {
+        self.value >> lsb & 0b1111
+    }
+}
+
+/// The decoded Data Cache Zero ID register, `DCZID_EL0`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DataCacheZeroId {
+    value: u64,
+}
+
+impl DataCacheZeroId {
+    /// Decode a raw value of `DCZID_EL0`
+    pub fn decode(value: u64) -> Self {
+        DataCacheZeroId { value }
+    }
+
+    /// Read `DCZID_EL0` of the current core. Returns `None` if it is
+    /// not AArch64.
+    pub fn read() -> Option<Self> {
+        read_dczid().map(DataCacheZeroId::decode)
+    }
+
+    /// The raw value
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// The number of bytes that `DC ZVA` zeroes, `BS`
+    pub fn block_size(&self) -> u64 {
+        words(self.value & 0b1111)
+    }
+
+    /// Whether `DC ZVA` is prohibited, `DZP`
+    pub fn prohibited(&self) -> bool {
+        self.value >> 4 & 0b1 == 0b1
+    }
+}
+
+/// The kind of a cache in sysfs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CacheKind {
+    /// Data cache
+    Data,
+    /// Instruction cache
+    Instruction,
+    /// Unified cache
+    Unified,
+}
+
+/// One cache of one CPU in sysfs, i.e., `cpu<N>/cache/index<M>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheInfo {
+    cpu: usize,
+    level: u64,
+    kind: CacheKind,
+    size: Option<u64>,
+    ways: Option<u64>,
+    line_size: Option<u64>,
+    shared_cpus: Vec<usize>,
+}
+
+impl CacheInfo {
+    /// The CPU number
+    pub fn cpu(&self) -> usize {
+        self.cpu
+    }
+
+    /// The level, e.g., 1 for L1
+    pub fn level(&self) -> u64 {
+        self.level
+    }
+
+    /// Data, instruction, or unified
+    pub fn kind(&self) -> CacheKind {
+        self.kind
+    }
+
+    /// The size in bytes. Firmware does not always report it.
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    /// The number of ways
+    pub fn ways(&self) -> Option<u64> {
+        self.ways
+    }
+
+    /// The line size in bytes
+    pub fn line_size(&self) -> Option<u64> {
+        self.line_size
+    }
+
+    /// The CPUs that share the cache, including [`cpu`](Self::cpu)
+    pub fn shared_cpus(&self) -> &[usize] {
+        &self.shared_cpus
+    }
+}
+
+/// Read the caches of all CPUs below `root`, e.g.,
+/// [`SYSFS_CPU_ROOT`](crate::sysfs::SYSFS_CPU_ROOT), sorted by the CPU
+/// number and the index. A shared cache is reported once per CPU.
+pub fn read_caches(root: impl AsRef<Path>) -> io::Result<Vec<CacheInfo>> {
+    let mut caches = Vec::new();
+
+    for cpu in cpus(root.as_ref())? {
+        let dir = root.as_ref().join(format!("cpu{cpu}")).join("cache");
+
+        for index in indices(&dir)? {
+            let dir = dir.join(format!("index{index}"));
+            let Some(level) = read_value(&dir, "level")? else {
+                continue;
+            };
+            let Some(kind) = read_file(&dir, "type")? else {
+                continue;
+            };
+
+            let kind = match kind.as_str() {
+                "Data" => CacheKind::Data,
+                "Instruction" => CacheKind::Instruction,
+                "Unified" => CacheKind::Unified,
+                _ => return Err(invalid_data("invalid cache type")),
+            };
+            let size = match read_file(&dir, "size")? {
+                Some(size) => Some(parse_size(&size).ok_or_else(|| invalid_data("invalid size"))?),
+                None => None,
+            };
+            let shared_cpus = match read_file(&dir, "shared_cpu_list")? {
+                Some(list) => {
+                    parse_cpu_list(&list).ok_or_else(|| invalid_data("invalid cpu list"))?
+                }
+                None => vec![cpu],
+            };
+
+            caches.push(CacheInfo {
+                cpu,
+                level,
+                kind,
+                size,
+                ways: read_value(&dir, "ways_of_associativity")?,
+                line_size: read_value(&dir, "coherency_line_size")?,
+                shared_cpus,
+            });
+        }
+    }
+
+    Ok(caches)
+}
+
+/// Parse a cache size of sysfs, e.g., `64K` or `32M`, in bytes
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let (number, scale) = match text.strip_suffix('K') {
+        Some(number) => (number, 1024),
+        None => match text.strip_suffix('M') {
+            Some(number) => (number, 1024 * 1024),
+            None => (text, 1),
+        },
+    };
+
+    number.parse::<u64>().ok()?.checked_mul(scale)
+}
+
+/// `log2` of a number of 4-byte words in bytes
+fn words(log2: u64) -> u64 {
+    4 << log2
+}
+
+/// `ERG` and `CWG`, where 0 means not reported
+fn granule(log2: u64) -> Option<u64> {
+    (log2 != 0).then(|| words(log2))
+}
+
+/// the numbers of all `index<M>` directories, empty without caches
+fn indices(dir: &Path) -> io::Result<Vec<usize>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    let mut indices = Vec::new();
+    for entry in entries {
+        let name = entry?.file_name();
+        let Some(number) = name.to_str().and_then(|name| name.strip_prefix("index")) else {
+            continue;
+        };
+        if let Ok(index) = number.parse::<usize>() {
+            indices.push(index);
+        }
+    }
+
+    indices.sort_unstable();
+
+    Ok(indices)
+}
+
+/// Returns `None` if the file does not exist
+fn read_file(dir: &Path, name: &str) -> io::Result<Option<String>> {
+    match std::fs::read_to_string(dir.join(name)) {
+        Ok(text) => Ok(Some(text.trim().to_string())),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn read_value(dir: &Path, name: &str) -> io::Result<Option<u64>> {
+    match read_file(dir, name)? {
+        Some(text) => text
+            .parse()
+            .map(Some)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+        None => Ok(None),
+    }
+}
+
+fn invalid_data(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::Fixture;
+
+    fn write_cache(fixture: &Fixture, cpu: usize, index: usize, files: &[(&str, &str)]) {
+        fixture.write(format!("cpu{cpu}/cache/index{index}"), files);
+    }
+
+    #[test]
+    fn test_cache_type() {
+        // 64-byte lines and granules, PIPT
+        let ctr = CacheType::decode(0x8444_c004);
+        assert_eq!(ctr.icache_line_size(), 64);
+        assert_eq!(ctr.dcache_line_size(), 64);
+        assert_eq!(ctr.exclusives_reservation_granule(), Some(64));
+        assert_eq!(ctr.cache_writeback_granule(), Some(64));
+        assert_eq!(ctr.l1_icache_policy(), L1IcachePolicy::Pipt);
+        assert!(!ctr.idc());
+        assert!(!ctr.dic());
+
+        // 128-byte instruction cache lines, IDC and DIC
+        let ctr = CacheType::decode(0xb444_c005);
+        assert_eq!(ctr.icache_line_size(), 128);
+        assert_eq!(ctr.dcache_line_size(), 64);
+        assert!(ctr.idc());
+        assert!(ctr.dic());
+
+        // nothing reported
+        let ctr = CacheType::decode(0x8000_8000);
+        assert_eq!(ctr.icache_line_size(), 4);
+        assert_eq!(ctr.exclusives_reservation_granule(), None);
+        assert_eq!(ctr.cache_writeback_granule(), None);
+        assert_eq!(ctr.max_writeback_granule(), 2048);
+        assert_eq!(ctr.l1_icache_policy(), L1IcachePolicy::Vipt);
+    }
+
+    #[test]
+    fn test_l1_icache_policy() {
+        let policies = [
+            L1IcachePolicy::Vpipt,
+            L1IcachePolicy::Aivivt,
+            L1IcachePolicy::Vipt,
+            L1IcachePolicy::Pipt,
+        ];
+
+        for (l1ip, policy) in policies.into_iter().enumerate() {
+            let value = u64::try_from(l1ip).unwrap() << 14;
+            assert_eq!(CacheType::decode(value).l1_icache_policy(), policy);
+        }
+    }
+
+    #[test]
+    fn test_dczid() {
+        let dczid = DataCacheZeroId::decode(0b0_0100);
+        assert_eq!(dczid.block_size(), 64);
+        assert!(!dczid.prohibited());
+
+        let dczid = DataCacheZeroId::decode(0b1_0101);
+        assert_eq!(dczid.block_size(), 128);
+        assert!(dczid.prohibited());
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("64K\n"), Some(64 * 1024));
+        assert_eq!(parse_size("32M"), Some(32 * 1024 * 1024));
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("K"), None);
+        assert_eq!(parse_size("1G"), None);
+    }
+
+    #[test]
+    fn test_read_caches() {
+        let fixture = Fixture::new("hierarchy");
+        for cpu in [0, 1] {
+            let shared = format!("{cpu}\n");
+            write_cache(
+                &fixture,
+                cpu,
+                0,
+                &[
+                    ("level", "1\n"),
+                    ("type", "Data\n"),
+                    ("size", "64K\n"),
+                    ("ways_of_associativity", "4\n"),
+                    ("coherency_line_size", "64\n"),
+                    ("shared_cpu_list", &shared),
+                ],
+            );
+            write_cache(
+                &fixture,
+                cpu,
+                1,
+                &[
+                    ("level", "1\n"),
+                    ("type", "Instruction\n"),
+                    ("size", "64K\n"),
+                ],
+            );
+            write_cache(
+                &fixture,
+                cpu,
+                2,
+                &[
+                    ("level", "3\n"),
+                    ("type", "Unified\n"),
+                    ("size", "32M\n"),
+                    ("shared_cpu_list", "0-1\n"),
+                ],
+            );
+        }
+        std::fs::write(fixture.root().join("online"), "0-1\n").unwrap();
+
+        let caches = read_caches(fixture.root()).unwrap();
+        assert_eq!(caches.len(), 6);
+
+        assert_eq!(caches[0].cpu(), 0);
+        assert_eq!(caches[0].level(), 1);
+        assert_eq!(caches[0].kind(), CacheKind::Data);
+        assert_eq!(caches[0].size(), Some(64 * 1024));
+        assert_eq!(caches[0].ways(), Some(4));
+        assert_eq!(caches[0].line_size(), Some(64));
+        assert_eq!(caches[0].shared_cpus(), &[0]);
+
+        assert_eq!(caches[1].kind(), CacheKind::Instruction);
+        assert_eq!(caches[1].ways(), None);
+        assert_eq!(caches[1].shared_cpus(), &[0]);
+
+        assert_eq!(caches[5].cpu(), 1);
+        assert_eq!(caches[5].level(), 3);
+        assert_eq!(caches[5].kind(), CacheKind::Unified);
+        assert_eq!(caches[5].size(), Some(32 * 1024 * 1024));
+        assert_eq!(caches[5].shared_cpus(), &[0, 1]);
+    }
+
+    #[test]
+    fn test_invalid_cache() {
+        let fixture = Fixture::new("invalid");
+        write_cache(&fixture, 0, 0, &[("level", "1\n"), ("type", "Tag\n")]);
+
+        let err = read_caches(fixture.root()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_without_caches() {
+        let fixture = Fixture::new("empty");
+        std::fs::create_dir_all(fixture.root().join("cpu0")).unwrap();
+
+        assert_eq!(read_caches(fixture.root()).unwrap(), Vec::new());
+    }
+}
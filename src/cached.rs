@@ -0,0 +1,113 @@
+use crate::feature_set::{position, WORDS};
+use crate::target_features::statically_enabled;
+use crate::{check_features, Feature, FeatureSet};
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+
+const UNINITIALIZED: u8 = 0;
+const INITIALIZED: u8 = 1;
+
+/// the detected features, one bit per feature
+static DETECTED: [AtomicU64; WORDS] = [const { AtomicU64::new(0) }; WORDS];
+static STATE: AtomicU8 = AtomicU8::new(UNINITIALIZED);
+
+/// Test for a feature of the current process. The first call runs
+/// [`check_features`] and caches the result in a process-wide bitset,
+/// later calls are a load and a test. Features that are enabled at
+/// compile time, e.g., with `-C target-feature=+lse`, are reported
+/// without detection. See also [`has_feature!`](crate::has_feature).
+///
+/// ```rust
+/// use aarch64_features::{check_features, has, Feature};
+///
+/// assert_eq!(has(Feature::FEAT_SME), check_features().contains(&Feature::FEAT_SME));
+/// ```
+#[inline]
+pub fn has(feature: Feature) -> bool {
+    if statically_enabled(feature) {
+        return true;
+    }
+
+    if STATE.load(Ordering::Acquire) != INITIALIZED {
+        initialize();
+    }
+
+    let (word, bit) = position(feature);
+
+    DETECTED[word].load(Ordering::Relaxed) & bit != 0
+}
+
+impl FeatureSet {
+    /// The features of the current process from the cache of [`has`].
+    /// Unlike [`check_features`], the registers are read only once.
+    pub fn detected() -> FeatureSet {
+        if STATE.load(Ordering::Acquire) != INITIALIZED {
+            initialize();
+        }
+
+        let mut words = [0; WORDS];
+        for (word, detected) in words.iter_mut().zip(&DETECTED) {
+            *word = detected.load(Ordering::Relaxed);
+        }
+
+        FeatureSet::from_words(words)
+    }
+}
+
+/// Test for several features of the current process with [`has`]
+///
+/// ```rust
+/// use aarch64_features::has_feature;
+///
+/// if has_feature!(FEAT_LSE, FEAT_LRCPC) {
+///     println!("happy");
+/// }
+/// ```
+#[macro_export]
+macro_rules! has_feature {
+    ($($feature:ident),+ $(,)?) => {
+        $($crate::has($crate::Feature::$feature))&&+
+    };
+}
+
+/// Racing threads store the same bits, thus the detection may run more
+/// than once but never publishes a partial set.
+#[cold]
+fn initialize() {
+    let features: FeatureSet = check_features().into();
+
+    for (detected, word) in DETECTED.iter().zip(features.words()) {
+        detected.store(word, Ordering::Relaxed);
+    }
+
+    STATE.store(INITIALIZED, Ordering::Release);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_cached() {
+        let features = check_features();
+
+        for feature in Feature::iter() {
+            if !statically_enabled(feature) {
+                assert_eq!(has(feature), features.contains(&feature), "{feature:?}");
+            }
+        }
+        for feature in &features {
+            assert!(has(*feature), "{feature:?}");
+        }
+        assert_eq!(FeatureSet::detected(), FeatureSet::from(&features));
+    }
+
+    #[test]
+    fn test_macro() {
+        assert_eq!(
+            has_feature!(FEAT_LSE, FEAT_LRCPC),
+            has(Feature::FEAT_LSE) && has(Feature::FEAT_LRCPC)
+        );
+        assert_eq!(has_feature!(FEAT_SVE,), has(Feature::FEAT_SVE));
+    }
+}
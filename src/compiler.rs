@@ -0,0 +1,330 @@
+use crate::cpu_type::Core;
+use crate::{ArchProfile, Feature};
+use std::collections::HashSet;
+use strum::IntoEnumIterator;
+
+/// The C and C++ compilers with AArch64 backends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compiler {
+    /// GCC
+    Gcc,
+    /// Clang
+    Clang,
+}
+
+/// The value of `-march` for a set of features, e.g.,
+/// `armv8.2-a+fp16+dotprod+rcpc`. The base is the highest
+/// [`ArchProfile`] whose extensions, as the compilers turn them on, are
+/// all in the set. The modifiers add the extensions of the set that the
+/// base does not imply and that the compiler knows.
+///
+/// ```rust
+/// use aarch64_features::{march_string, Compiler, Feature};
+/// use std::collections::HashSet;
+///
+/// let features = HashSet::from([
+///     Feature::FEAT_AdvSIMD,
+///     Feature::FEAT_LSE,
+///     Feature::FEAT_RDM,
+///     Feature::FEAT_DPB,
+///     Feature::FEAT_FP16,
+/// ]);
+///
+/// assert_eq!(march_string(&features, Compiler::Gcc), "armv8.2-a+fp16");
+/// ```
+pub fn march_string(features: &HashSet<Feature>, compiler: Compiler) -> String {
+    let base = ArchProfile::iter()
+        .filter(|profile| base_features(*profile).is_subset(features))
+        .max()
+        .unwrap_or(ArchProfile::Armv8_0A);
+    let mandatory = base_features(base);
+
+    let mut march = String::from(base.name());
+
+    for extension in EXTENSIONS {
+        let Some(name) = extension.name(compiler) else {
+            continue;
+        };
+        let present = extension
+            .requires
+            .iter()
+            .all(|feat| features.contains(feat));
+        let implied = extension
+            .requires
+            .iter()
+            .all(|feat| mandatory.contains(feat));
+        if present && !implied {
+            march.push('+');
+            march.push_str(name);
+        }
+    }
+
+    march
+}
+
+/// The value of `-mcpu` for a core, e.g., `neoverse-v2`. Returns `None`
+/// if neither GCC nor Clang know the core.
+///
+/// ```rust
+/// use aarch64_features::cpu_type::Core;
+/// use aarch64_features::mcpu_name;
+///
+/// assert_eq!(mcpu_name(Core::NeoverseV2), Some("neoverse-v2"));
+/// assert_eq!(mcpu_name(Core::AppleM1Max), Some("apple-m1"));
+/// ```
+pub fn mcpu_name(core: Core) -> Option<&'static str> {
+    match core {
+        Core::NeoverseE1 => Some("neoverse-e1"),
+        Core::NeoverseN1 => Some("neoverse-n1"),
+        Core::NeoverseN2 => Some("neoverse-n2"),
+        Core::NeoverseN3 => Some("neoverse-n3"),
+        Core::NeoverseV1 => Some("neoverse-v1"),
+        Core::NeoverseV2 => Some("neoverse-v2"),
+        Core::NeoverseV3 => Some("neoverse-v3"),
+        Core::A64FX => Some("a64fx"),
+        Core::MONAKA => Some("fujitsu-monaka"),
+        Core::AppleM1 | Core::AppleM1Pro | Core::AppleM1Max => Some("apple-m1"),
+        Core::AppleM2 | Core::AppleM2Pro | Core::AppleM2Max => Some("apple-m2"),
+        Core::AppleM3 | Core::AppleM3Pro | Core::AppleM3Max => Some("apple-m3"),
+        Core::AppleM4 | Core::AppleM4Max => Some("apple-m4"),
+        Core::Ampere1 => Some("ampere1"),
+        Core::Ampere1A => Some("ampere1a"),
+        Core::Ampere1B => Some("ampere1b"),
+        Core::AppleA14 => Some("apple-a14"),
+        Core::AppleA15 => Some("apple-a15"),
+        Core::AppleA16 => Some("apple-a16"),
+        Core::AppleA18 | Core::AppleA18Pro => None,
+        Core::MicrosoftAzureCobalt100 => Some("cobalt-100"),
+        Core::NvidiaOlympus => Some("olympus"),
+    }
+}
+
+/// The features that GCC and Clang turn on for `-march=<profile>`
+fn base_features(profile: ArchProfile) -> HashSet<Feature> {
+    let mut features = profile.mandatory();
+
+    for ancestor in profile.ancestors() {
+        for (base, extensions) in BASE_EXTENSIONS {
+            if *base == ancestor {
+                features.extend(extensions.iter().copied());
+            }
+        }
+    }
+
+    features
+}
+
+/// The extensions that the compilers turn on for a base in addition to
+/// the mandatory features of the level, see `aarch64-arches.def` of GCC.
+/// FEAT_NMI, FEAT_LSUI, and FEAT_OCCMO are left out, they are not
+/// visible at EL0. GCC turns on `+fp16fml` for Armv8.4-A only with
+/// `+fp16`.
+#[rustfmt::skip]
+const BASE_EXTENSIONS: &[(ArchProfile, &[Feature])] = &[
+    (ArchProfile::Armv8_7A, &[
+        Feature::FEAT_LS64,
+        Feature::FEAT_LS64_V,
+        Feature::FEAT_LS64_ACCDATA,
+        Feature::FEAT_XS,
+    ]),
+    (ArchProfile::Armv9_4A, &[Feature::FEAT_SVE2p1]),
+];
+
+struct ExtensionDescription {
+    gcc: Option<&'static str>,
+    clang: Option<&'static str>,
+    requires: &'static [Feature],
+}
+
+impl ExtensionDescription {
+    fn name(&self, compiler: Compiler) -> Option<&'static str> {
+        match compiler {
+            Compiler::Gcc => self.gcc,
+            Compiler::Clang => self.clang,
+        }
+    }
+}
+
+macro_rules! declare_extensions {
+    ($(
+        ($gcc:expr, $clang:expr, [$($feature:ident),+]),
+    )+) => {
+        /// The `+ext` modifiers of `-march` and the features they require
+        const EXTENSIONS: &[ExtensionDescription] = &[
+            $(
+                ExtensionDescription {
+                    gcc: $gcc,
+                    clang: $clang,
+                    requires: &[$(Feature::$feature),+],
+                }
+            ),+
+        ];
+    }
+}
+
+#[rustfmt::skip]
+declare_extensions!(
+    // Armv8.0
+    (Some("aes"),          Some("aes"),          [FEAT_AES, FEAT_PMULL]),
+    (Some("sha2"),         Some("sha2"),         [FEAT_SHA1, FEAT_SHA256]),
+    (Some("sb"),           Some("sb"),           [FEAT_SB]),
+    (Some("ssbs"),         Some("ssbs"),         [FEAT_SSBS]),
+    (Some("predres"),      Some("predres"),      [FEAT_SPECRES]),
+    // Armv8.1
+    (Some("lse"),          Some("lse"),          [FEAT_LSE]),
+    (Some("rdma"),         Some("rdm"),          [FEAT_RDM]),
+    // Armv8.2
+    (Some("sha3"),         Some("sha3"),         [FEAT_SHA3, FEAT_SHA512]),
+    (Some("sm4"),          Some("sm4"),          [FEAT_SM3, FEAT_SM4]),
+    (Some("fp16"),         Some("fp16"),         [FEAT_FP16]),
+    (Some("fp16fml"),      Some("fp16fml"),      [FEAT_FHM]),
+    (Some("dotprod"),      Some("dotprod"),      [FEAT_DotProd]),
+    (Some("profile"),      Some("profile"),      [FEAT_SPE]),
+    (Some("sve"),          Some("sve"),          [FEAT_SVE]),
+    (Some("f32mm"),        Some("f32mm"),        [FEAT_F32MM]),
+    (Some("f64mm"),        Some("f64mm"),        [FEAT_F64MM]),
+    (Some("bf16"),         Some("bf16"),         [FEAT_BF16]),
+    (Some("i8mm"),         Some("i8mm"),         [FEAT_I8MM]),
+    // Armv8.3
+    (Some("rcpc"),         Some("rcpc"),         [FEAT_LRCPC]),
+    (Some("pauth"),        Some("pauth"),        [FEAT_PAuth]),
+    // Armv8.4
+    (Some("flagm"),        Some("flagm"),        [FEAT_FlagM]),
+    // Armv8.5
+    (Some("rng"),          Some("rng"),          [FEAT_RNG]),
+    (Some("memtag"),       Some("memtag"),       [FEAT_MTE, FEAT_MTE2]),
+    // Armv8.7
+    (Some("ls64"),         Some("ls64"),         [FEAT_LS64, FEAT_LS64_V, FEAT_LS64_ACCDATA]),
+    (None,                 Some("wfxt"),         [FEAT_WFxT]),
+    // Armv8.8
+    (Some("mops"),         Some("mops"),         [FEAT_MOPS]),
+    (None,                 Some("hbc"),          [FEAT_HBC]),
+    // Armv8.9
+    (Some("cssc"),         Some("cssc"),         [FEAT_CSSC]),
+    (Some("rcpc3"),        Some("rcpc3"),        [FEAT_LRCPC3]),
+    // Armv9.0
+    (Some("sve2"),         Some("sve2"),         [FEAT_SVE2]),
+    (Some("sve2-aes"),     Some("sve2-aes"),     [FEAT_SVE_AES, FEAT_SVE_PMULL128]),
+    (Some("sve2-bitperm"), Some("sve2-bitperm"), [FEAT_SVE_BitPerm]),
+    (Some("sve2-sha3"),    Some("sve2-sha3"),    [FEAT_SVE_SHA3]),
+    (Some("sve2-sm4"),     Some("sve2-sm4"),     [FEAT_SVE_SM4]),
+    // Armv9.2
+    (Some("sme"),          Some("sme"),          [FEAT_SME]),
+    (None,                 Some("sme-fa64"),     [FEAT_SME_FA64]),
+    (Some("sme-f64f64"),   Some("sme-f64f64"),   [FEAT_SME_F64F64]),
+    (Some("sme-i16i64"),   Some("sme-i16i64"),   [FEAT_SME_I16I64]),
+    // Armv9.3
+    (Some("sme2"),         Some("sme2"),         [FEAT_SME2]),
+    // Armv9.4
+    (Some("sve2p1"),       Some("sve2p1"),       [FEAT_SVE2p1]),
+    (Some("lse128"),       Some("lse128"),       [FEAT_LSE128]),
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neoverse_n1() {
+        let features = HashSet::from([
+            Feature::FEAT_AdvSIMD,
+            Feature::FEAT_AES,
+            Feature::FEAT_PMULL,
+            Feature::FEAT_SHA1,
+            Feature::FEAT_SHA256,
+            Feature::FEAT_LSE,
+            Feature::FEAT_RDM,
+            Feature::FEAT_FP16,
+            Feature::FEAT_DotProd,
+            Feature::FEAT_LRCPC,
+            Feature::FEAT_DPB,
+            Feature::FEAT_SSBS,
+        ]);
+
+        assert_eq!(
+            march_string(&features, Compiler::Gcc),
+            "armv8.2-a+aes+sha2+ssbs+fp16+dotprod+rcpc"
+        );
+        assert_eq!(
+            march_string(&features, Compiler::Clang),
+            "armv8.2-a+aes+sha2+ssbs+fp16+dotprod+rcpc"
+        );
+    }
+
+    #[test]
+    fn test_compiler_names() {
+        let features = HashSet::from([Feature::FEAT_RDM, Feature::FEAT_WFxT]);
+
+        assert_eq!(march_string(&features, Compiler::Gcc), "armv8-a+rdma");
+        assert_eq!(march_string(&features, Compiler::Clang), "armv8-a+rdm+wfxt");
+    }
+
+    #[test]
+    fn test_armv9() {
+        let base = ArchProfile::Armv9_0A.mandatory();
+        assert_eq!(march_string(&base, Compiler::Gcc), "armv9-a");
+
+        let mut features = base.clone();
+        features.extend([
+            Feature::FEAT_SVE_BitPerm,
+            Feature::FEAT_BF16,
+            Feature::FEAT_I8MM,
+        ]);
+        assert_eq!(
+            march_string(&features, Compiler::Clang),
+            "armv9-a+bf16+i8mm+sve2-bitperm"
+        );
+    }
+
+    #[test]
+    fn test_base_without_extensions() {
+        // Armv8.9-A and Armv9.0-A without FEAT_SVE2p1 and the Armv9.5-A
+        // extensions FEAT_CPA, FEAT_FAMINMAX, and FEAT_LUT
+        let mut features = base_features(ArchProfile::Armv8_9A);
+        features.extend([Feature::FEAT_SVE, Feature::FEAT_SVE2]);
+
+        assert_eq!(march_string(&features, Compiler::Gcc), "armv9.3-a+cssc");
+        assert_eq!(march_string(&features, Compiler::Clang), "armv9.3-a+cssc");
+
+        features.insert(Feature::FEAT_SVE2p1);
+        assert_eq!(march_string(&features, Compiler::Gcc), "armv9.4-a");
+
+        features.extend([Feature::FEAT_CPA, Feature::FEAT_FAMINMAX]);
+        assert_eq!(march_string(&features, Compiler::Gcc), "armv9.4-a");
+
+        features.insert(Feature::FEAT_LUT);
+        assert_eq!(march_string(&features, Compiler::Gcc), "armv9.5-a");
+    }
+
+    #[test]
+    fn test_bases() {
+        for profile in ArchProfile::iter() {
+            let features = base_features(profile);
+
+            assert_eq!(march_string(&features, Compiler::Gcc), profile.name());
+            assert_eq!(march_string(&features, Compiler::Clang), profile.name());
+        }
+    }
+
+    #[test]
+    fn test_armv8_7_without_ls64() {
+        let mut features = base_features(ArchProfile::Armv8_7A);
+        features.remove(&Feature::FEAT_LS64_ACCDATA);
+
+        assert_eq!(march_string(&features, Compiler::Gcc), "armv8.6-a");
+        assert_eq!(march_string(&features, Compiler::Clang), "armv8.6-a+wfxt");
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(march_string(&HashSet::new(), Compiler::Gcc), "armv8-a");
+    }
+
+    #[test]
+    fn test_mcpu_name() {
+        assert_eq!(mcpu_name(Core::NeoverseN1), Some("neoverse-n1"));
+        assert_eq!(mcpu_name(Core::AppleM3Pro), Some("apple-m3"));
+        assert_eq!(mcpu_name(Core::Ampere1B), Some("ampere1b"));
+        assert_eq!(mcpu_name(Core::AppleA18), None);
+    }
+}
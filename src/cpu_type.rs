@@ -11,6 +11,7 @@ use crate::midr::MidrEL1;
 
 #[non_exhaustive]
 #[derive(Debug, Hash, Eq, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Core kind
 pub enum Core {
     /// Arm Neoverse E1 core
@@ -122,7 +123,7 @@ const APPLE_CPU_PART_M2_AVALANCHE_PRO: u64 = 0x035;
 const APPLE_CPU_PART_M2_BLIZZARD_MAX: u64 = 0x038;
 const APPLE_CPU_PART_M2_AVALANCHE_MAX: u64 = 0x039;
 
-/// arm/cpuid.h
+// arm/cpuid.h
 
 /* H15 e-Core */
 const APPLE_SAWTOOTH_PART_NUM: u64 = 0x40; // maybe A16 energy-efficient core
@@ -142,7 +143,7 @@ const APPLE_ECORE_LOBOS_PART_NUM: u64 = 0x44;
 const APPLE_PCORE_LOBOS_PART_NUM: u64 = 0x45;
 
 /* M11 e-Core */
-const APPLE_SAWTOOTH_M11_PART_NUM: u64 = 0x46;
+// const APPLE_SAWTOOTH_M11_PART_NUM: u64 = 0x46;
 
 /* H15 Palma e-Core. */
 const APPLE_ECORE_PALMA_PART_NUM: u64 = 0x48;
@@ -150,9 +151,9 @@ const APPLE_ECORE_PALMA_PART_NUM: u64 = 0x48;
 const APPLE_PCORE_PALMA_PART_NUM: u64 = 0x49;
 
 /* H15 Coll e-Core. */
-const APPLE_ECORE_COLL_PART_NUM: u64 = 0x50; // maybe A17
-                                             /* H15 Coll p-Core. */
-const APPLE_PCORE_COLL_PART_NUM: u64 = 0x51;
+// const APPLE_ECORE_COLL_PART_NUM: u64 = 0x50; // maybe A17
+/* H15 Coll p-Core. */
+// const APPLE_PCORE_COLL_PART_NUM: u64 = 0x51;
 
 /* H16G Donan e-Core. */
 const APPLE_ECORE_DONAN_PART_NUM: u64 = 0x52; // M4 small
@@ -173,26 +174,26 @@ const APPLE_ECORE_TUPAI_PART_NUM: u64 = 0x6a; // A18
 const APPLE_PCORE_TUPAI_PART_NUM: u64 = 0x6b; // A18
 
 /* H16S Brava S e-Core. */
-const APPLE_ECORE_BRAVA_S: u64 = 0x54;
+// const APPLE_ECORE_BRAVA_S: u64 = 0x54;
 
 /* H16S Brava S p-Core. */
-const APPLE_PCORE_BRAVA_S: u64 = 0x55;
+// const APPLE_PCORE_BRAVA_S: u64 = 0x55;
 
 /* H16C Brava C e-Core. */
-const APPLE_ECORE_BRAVA_C: u64 = 0x58;
+// const APPLE_ECORE_BRAVA_C: u64 = 0x58;
 
 /* H16C Brava C p-Core. */
-const APPLE_PCORE_BRAVA_C: u64 = 0x59;
+// const APPLE_PCORE_BRAVA_C: u64 = 0x59;
 
 const ARM_NEOVERSE_E1_PART_NUM: u64 = 0xD4A;
-const ARM_NEOVERSE_N1_PART_NUM: u64 = 0xD0C;
-const ARM_NEOVERSE_N2_PART_NUM: u64 = 0xD49;
+pub(crate) const ARM_NEOVERSE_N1_PART_NUM: u64 = 0xD0C;
+pub(crate) const ARM_NEOVERSE_N2_PART_NUM: u64 = 0xD49;
 const ARM_NEOVERSE_N3_PART_NUM: u64 = 0xD8E;
 const ARM_NEOVERSE_V1_PART_NUM: u64 = 0xD40;
 const ARM_NEOVERSE_V2_PART_NUM: u64 = 0xD4F;
 const ARM_NEOVERSE_V3_PART_NUM: u64 = 0xD84;
 
-const AMPERE_1_PART_NUM: u64 = 0xac3;
+pub(crate) const AMPERE_1_PART_NUM: u64 = 0xac3;
 const AMPERE_1A_PART_NUM: u64 = 0xac4;
 const AMPERE_1B_PART_NUM: u64 = 0xac5;
 
@@ -207,7 +208,7 @@ const NVIDIA_OLYMPUS_PART_NUM: u64 = 0x010;
 mod tests {
 
     use super::*;
-    use crate::midr::{Midr, MidrBuilder};
+    use crate::midr::MidrBuilder;
 
     fn try_from(value: &MidrEL1) -> Option<Core> {
         match value {
@@ -215,7 +216,7 @@ mod tests {
             MidrEL1::Known(midr) => {
                 for core_description in CORES {
                     if midr.check_implementer(core_description.implementer)
-                        && core_description.variant.check_match(&value)
+                        && core_description.variant.check_match(value)
                     {
                         return Some(core_description.core);
                     }
@@ -229,14 +230,14 @@ mod tests {
     fn test_apple_m1() {
         let midr = MidrBuilder::new()
             .implementer(Implementer::Apple)
-            .part_num(APPLE_FIRESTORM_PART_NUM)
+            .part_num(APPLE_CPU_PART_M1_FIRESTORM)
             .build();
         let midr_el1 = MidrEL1::Known(midr.clone());
 
-        assert!(midr.check_part_num(APPLE_FIRESTORM_PART_NUM));
+        assert!(midr.check_part_num(APPLE_CPU_PART_M1_FIRESTORM));
         assert!(midr.check_implementer(Apple));
 
-        assert!(Or(APPLE_FIRESTORM_PART_NUM, APPLE_CPU_PART_M1_ICESTORM).check_match(&midr_el1));
+        assert!(Or(APPLE_CPU_PART_M1_FIRESTORM, APPLE_CPU_PART_M1_ICESTORM).check_match(&midr_el1));
 
         let core_option = try_from(&midr_el1);
         assert!(core_option.is_some());
@@ -247,6 +248,7 @@ mod tests {
             .implementer(Implementer::Apple)
             .part_num(APPLE_CPU_PART_M1_ICESTORM)
             .build();
+        let midr_el1 = MidrEL1::Known(midr);
 
         let core_option = try_from(&midr_el1);
         assert!(core_option.is_some());
@@ -254,6 +256,19 @@ mod tests {
 
         //assert!(is_apple_m1(&midr));
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let json = serde_json::to_string(&Core::NeoverseV2).unwrap();
+
+        assert_eq!(json, "\"NeoverseV2\"");
+        assert_eq!(
+            serde_json::from_str::<Core>(&json).unwrap(),
+            Core::NeoverseV2
+        );
+        assert!(serde_json::from_str::<Core>("\"Pentium\"").is_err());
+    }
 }
 
 // https://developer.arm.com/documentation/ddi0595/2020-12/AArch64-Registers/MIDR-EL1--Main-ID-Register
@@ -0,0 +1,264 @@
+//! Parser for the `/proc/cpuinfo` file of the Linux kernel.
+//!
+//! Some containers forbid the `MRS` emulation of the kernel and some
+//! kernels trap `MRS` on ID registers. The `Features` line of each
+//! processor is still available. The parser accepts arbitrary text,
+//! e.g., saved cpuinfo files from bug reports.
+//!
+//! ```rust
+//! use aarch64_features::cpuinfo::parse;
+//! use aarch64_features::Feature;
+//!
+//! let text = "processor\t: 0
+//! BogoMIPS\t: 243.75
+//! Features\t: fp asimd evtstrm aes pmull sha1 sha2 crc32 atomics cpuid
+//! CPU implementer\t: 0x41
+//! CPU architecture: 8
+//! CPU variant\t: 0x3
+//! CPU part\t: 0xd0c
+//! CPU revision\t: 1
+//! ";
+//!
+//! let cpus = parse(text);
+//!
+//! assert_eq!(cpus.len(), 1);
+//! assert!(cpus[0].features().contains(&Feature::FEAT_LSE));
+//! ```
+
+use crate::hwcap::feature_by_name;
+use crate::midr::{Architecture, Implementer, Midr, MidrBuilder};
+use crate::Feature;
+use std::collections::HashSet;
+
+/// The information of one processor in `/proc/cpuinfo`
+#[derive(Debug, Clone)]
+pub struct CpuInfo {
+    processor: usize,
+    features: HashSet<Feature>,
+    unknown_features: Vec<String>,
+    midr: Midr,
+}
+
+impl CpuInfo {
+    /// The number of the processor
+    pub fn processor(&self) -> usize {
+        self.processor
+    }
+
+    /// The features of the `Features` line
+    pub fn features(&self) -> &HashSet<Feature> {
+        &self.features
+    }
+
+    /// The names in the `Features` line that this crate does not know
+    pub fn unknown_features(&self) -> &[String] {
+        &self.unknown_features
+    }
+
+    /// The MIDR_EL1 register built from the `CPU implementer`, `CPU
+    /// variant`, `CPU part`, and `CPU revision` lines
+    pub fn midr(&self) -> &Midr {
+        &self.midr
+    }
+}
+
+/// Parse the text of a `/proc/cpuinfo` file. Entries without a
+/// `processor` line are ignored.
+pub fn parse(text: &str) -> Vec<CpuInfo> {
+    let mut cpus = Vec::new();
+    let mut current: Option<CpuInfoBuilder> = None;
+
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = key.trim();
+        let value = value.trim();
+
+        if key == "processor" {
+            if let Some(builder) = current.take() {
+                cpus.push(builder.build());
+            }
+            current = parse_number(value).map(CpuInfoBuilder::new);
+            continue;
+        }
+
+        let Some(builder) = current.as_mut() else {
+            continue;
+        };
+
+        match key {
+            "Features" => builder.features(value),
+            "CPU implementer" => builder.midr.implementer = parse_number(value),
+            "CPU variant" => builder.midr.variant = parse_number(value),
+            "CPU part" => builder.midr.part_num = parse_number(value),
+            "CPU revision" => builder.midr.revision = parse_number(value),
+            _ => {}
+        }
+    }
+
+    if let Some(builder) = current {
+        cpus.push(builder.build());
+    }
+
+    cpus
+}
+
+/// Read and parse `/proc/cpuinfo`
+pub fn read() -> std::io::Result<Vec<CpuInfo>> {
+    let text = std::fs::read_to_string("/proc/cpuinfo")?;
+
+    Ok(parse(&text))
+}
+
+/// accepts hexadecimal numbers with a `0x` prefix and decimal numbers
+fn parse_number<T: TryFrom<u64>>(value: &str) -> Option<T> {
+    let number = match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+        None => value.parse::<u64>().ok()?,
+    };
+
+    T::try_from(number).ok()
+}
+
+#[derive(Default)]
+struct MidrFields {
+    implementer: Option<u64>,
+    variant: Option<u64>,
+    part_num: Option<u64>,
+    revision: Option<u64>,
+}
+
+struct CpuInfoBuilder {
+    processor: usize,
+    features: HashSet<Feature>,
+    unknown_features: Vec<String>,
+    midr: MidrFields,
+}
+
+impl CpuInfoBuilder {
+    fn new(processor: usize) -> Self {
+        CpuInfoBuilder {
+            processor,
+            features: HashSet::new(),
+            unknown_features: Vec::new(),
+            midr: MidrFields::default(),
+        }
+    }
+
+    fn features(&mut self, value: &str) {
+        for name in value.split_whitespace() {
+            match feature_by_name(name) {
+                Some(Some(feature)) => {
+                    self.features.insert(feature);
+                }
+                Some(None) => {}
+                None => self.unknown_features.push(name.to_string()),
+            }
+        }
+    }
+
+    fn build(self) -> CpuInfo {
+        let implementer = self
+            .midr
+            .implementer
+            .and_then(|im| Implementer::try_from(im).ok())
+            .unwrap_or(Implementer::Unknown);
+
+        let midr = MidrBuilder::new()
+            .implementer(implementer)
+            .variant(self.midr.variant.unwrap_or(0))
+            .architecture(Architecture::IDRegisters)
+            .part_num(self.midr.part_num.unwrap_or(0))
+            .revision(self.midr.revision.unwrap_or(0))
+            .build();
+
+        CpuInfo {
+            processor: self.processor,
+            features: self.features,
+            unknown_features: self.unknown_features,
+            midr,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEOVERSE_N1: &str = "processor	: 0
+BogoMIPS	: 243.75
+Features	: fp asimd evtstrm aes pmull sha1 sha2 crc32 atomics fphp asimdhp cpuid asimdrdm lrcpc dcpop asimddp ssbs
+CPU implementer	: 0x41
+CPU architecture: 8
+CPU variant	: 0x3
+CPU part	: 0xd0c
+CPU revision	: 1
+
+processor	: 1
+BogoMIPS	: 243.75
+Features	: fp asimd evtstrm aes pmull sha1 sha2 crc32 atomics fphp asimdhp cpuid asimdrdm lrcpc dcpop asimddp ssbs
+CPU implementer	: 0x41
+CPU architecture: 8
+CPU variant	: 0x3
+CPU part	: 0xd0c
+CPU revision	: 1
+
+";
+
+    /// big.LITTLE with Cortex-A55 and Cortex-A76 and a future hwcap
+    const BIG_LITTLE: &str = "processor	: 0
+Features	: fp asimd atomics lrcpc newcap
+CPU implementer	: 0x41
+CPU variant	: 0x2
+CPU part	: 0xd05
+CPU revision	: 0
+
+processor	: 4
+Features	: fp asimd atomics lrcpc asimddp
+CPU implementer	: 0x41
+CPU variant	: 0x4
+CPU part	: 0xd0b
+CPU revision	: 1
+
+Hardware	: Qualcomm Technologies, Inc SM8150
+";
+
+    #[test]
+    fn test_neoverse_n1() {
+        let cpus = parse(NEOVERSE_N1);
+
+        assert_eq!(cpus.len(), 2);
+        for (idx, cpu) in cpus.iter().enumerate() {
+            assert_eq!(cpu.processor(), idx);
+            assert!(cpu.features().contains(&Feature::FEAT_LSE));
+            assert!(cpu.features().contains(&Feature::FEAT_DotProd));
+            assert!(!cpu.features().contains(&Feature::FEAT_SVE));
+            assert!(cpu.unknown_features().is_empty());
+            assert!(cpu.midr().check_implementer(Implementer::Arm));
+            assert!(cpu.midr().check_part_num(0xd0c));
+            assert!(cpu.midr().check_variant(0x3));
+        }
+    }
+
+    #[test]
+    fn test_big_little() {
+        let cpus = parse(BIG_LITTLE);
+
+        assert_eq!(cpus.len(), 2);
+        assert_eq!(cpus[0].processor(), 0);
+        assert_eq!(cpus[1].processor(), 4);
+        assert!(cpus[0].midr().check_part_num(0xd05));
+        assert!(cpus[1].midr().check_part_num(0xd0b));
+        assert!(!cpus[0].features().contains(&Feature::FEAT_DotProd));
+        assert!(cpus[1].features().contains(&Feature::FEAT_DotProd));
+        assert_eq!(cpus[0].unknown_features(), &["newcap".to_string()]);
+    }
+
+    #[test]
+    fn test_garbage() {
+        assert!(parse("").is_empty());
+        assert!(parse("Features\t: fp asimd\nno colon here\n").is_empty());
+        assert!(parse("processor\t: x\nFeatures\t: fp asimd\n").is_empty());
+    }
+}
@@ -0,0 +1,349 @@
+use crate::Feature;
+use std::collections::HashSet;
+use std::fmt;
+
+/// A feature in a set without one of its prerequisites, see [`validate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Inconsistency {
+    feature: Feature,
+    missing: Feature,
+}
+
+impl Inconsistency {
+    /// The feature that is in the set
+    pub fn feature(&self) -> Feature {
+        self.feature
+    }
+
+    /// The prerequisite of the feature that is not in the set
+    pub fn missing(&self) -> Feature {
+        self.missing
+    }
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} implies {:?}", self.feature, self.missing)
+    }
+}
+
+impl Feature {
+    /// The features that the Arm ARM requires for this feature, e.g.,
+    /// [FEAT_SVE2](Feature::FEAT_SVE2) implies [FEAT_SVE](Feature::FEAT_SVE).
+    /// Only the direct prerequisites are returned, see [`implied_closure`].
+    ///
+    /// ```rust
+    /// use aarch64_features::Feature;
+    ///
+    /// assert_eq!(Feature::FEAT_LSE128.implies(), &[Feature::FEAT_LSE]);
+    /// assert!(Feature::FEAT_LSE.implies().is_empty());
+    /// ```
+    pub fn implies(&self) -> &'static [Feature] {
+        FEATURE_DEPENDENCIES
+            .iter()
+            .find(|dependency| dependency.feature == *self)
+            .map_or(&[], |dependency| dependency.implies)
+    }
+}
+
+/// Extend a set of features with all features that they imply
+///
+/// ```rust
+/// use aarch64_features::{implied_closure, Feature};
+/// use std::collections::HashSet;
+///
+/// let features = implied_closure(&HashSet::from([Feature::FEAT_SVE2p1]));
+///
+/// assert!(features.contains(&Feature::FEAT_SVE2));
+/// assert!(features.contains(&Feature::FEAT_SVE));
+/// ```
+pub fn implied_closure(features: &HashSet<Feature>) -> HashSet<Feature> {
+    let mut closure = features.clone();
+    let mut worklist: Vec<Feature> = features.iter().copied().collect();
+
+    while let Some(feature) = worklist.pop() {
+        for implied in feature.implies() {
+            if closure.insert(*implied) {
+                worklist.push(*implied);
+            }
+        }
+    }
+
+    closure
+}
+
+/// Report the features of a set without one of their prerequisites,
+/// sorted by the feature. Hypervisors sometimes hide fields of the ID
+/// registers inconsistently.
+///
+/// The set should be unfiltered, e.g., from
+/// [`features_from_snapshot`](crate::features_from_snapshot). On Linux,
+/// [`check_features`](crate::check_features) drops the features that
+/// the kernel does not expose to userspace. The exported features
+/// include their prerequisites, so the filter adds no inconsistencies,
+/// but it may hide the features of one.
+///
+/// ```rust
+/// use aarch64_features::{validate, Feature};
+/// use std::collections::HashSet;
+///
+/// let inconsistencies = validate(&HashSet::from([Feature::FEAT_PAuth2]));
+///
+/// assert_eq!(inconsistencies.len(), 1);
+/// assert_eq!(inconsistencies[0].missing(), Feature::FEAT_PAuth);
+/// ```
+pub fn validate(features: &HashSet<Feature>) -> Vec<Inconsistency> {
+    let mut inconsistencies: Vec<Inconsistency> = features
+        .iter()
+        .flat_map(|feature| {
+            feature
+                .implies()
+                .iter()
+                .filter(|implied| !features.contains(implied))
+                .map(|missing| Inconsistency {
+                    feature: *feature,
+                    missing: *missing,
+                })
+        })
+        .collect();
+
+    inconsistencies.sort();
+
+    inconsistencies
+}
+
+struct FeatureDependency {
+    feature: Feature,
+    implies: &'static [Feature],
+}
+
+macro_rules! declare_dependencies {
+    ($(
+        ($feature:ident, [$($implied:ident),+ $(,)?]),
+    )+) => {
+        /// The direct prerequisites of the features from the ARMARM
+        const FEATURE_DEPENDENCIES: &[FeatureDependency] = &[
+            $(
+                FeatureDependency {
+                    feature: Feature::$feature,
+                    implies: &[$(Feature::$implied),+],
+                }
+            ),+
+        ];
+    }
+}
+
+#[rustfmt::skip]
+declare_dependencies!(
+    // Armv8.0
+    (FEAT_PMULL,        [FEAT_AES]),
+    (FEAT_SHA256,       [FEAT_SHA1]),
+    (FEAT_CSV2_2,       [FEAT_CSV2]),
+    (FEAT_CSV2_3,       [FEAT_CSV2_2]),
+    (FEAT_CSV2_1p2,     [FEAT_CSV2_1p1]),
+    // Armv8.1
+    (FEAT_LSE128,       [FEAT_LSE]),
+    (FEAT_PAN2,         [FEAT_PAN]),
+    (FEAT_PAN3,         [FEAT_PAN2]),
+    (FEAT_HAFT,         [FEAT_HAFDBS]),
+    (FEAT_HPDS2,        [FEAT_HPDS]),
+    (FEAT_PMUv3p4,      [FEAT_PMUv3p1]),
+    (FEAT_PMUv3p5,      [FEAT_PMUv3p4]),
+    (FEAT_PMUv3p7,      [FEAT_PMUv3p5]),
+    (FEAT_PMUv3p8,      [FEAT_PMUv3p7]),
+    (FEAT_PMUv3p9,      [FEAT_PMUv3p8]),
+    // Armv8.2
+    (FEAT_SHA512,       [FEAT_SHA256]),
+    (FEAT_SHA3,         [FEAT_SHA512]),
+    (FEAT_FHM,          [FEAT_FP16]),
+    (FEAT_DPB2,         [FEAT_DPB]),
+    (FEAT_LVA3,         [FEAT_LVA]),
+    (FEAT_EBF16,        [FEAT_BF16]),
+    (FEAT_RASv1p1,      [FEAT_RAS]),
+    (FEAT_RASv2,        [FEAT_RASv1p1]),
+    (FEAT_DoubleFault,  [FEAT_RASv1p1]),
+    (FEAT_Debugv8p4,    [FEAT_Debugv8p2]),
+    (FEAT_Debugv8p8,    [FEAT_Debugv8p4]),
+    (FEAT_SPEv1p1,      [FEAT_SPE]),
+    (FEAT_SPEv1p2,      [FEAT_SPEv1p1]),
+    (FEAT_SPEv1p3,      [FEAT_SPEv1p2]),
+    (FEAT_SPEv1p4,      [FEAT_SPEv1p3]),
+    (FEAT_F32MM,        [FEAT_SVE]),
+    (FEAT_F64MM,        [FEAT_SVE]),
+    // Armv8.3
+    (FEAT_LRCPC2,       [FEAT_LRCPC]),
+    (FEAT_LRCPC3,       [FEAT_LRCPC2]),
+    (FEAT_NV2,          [FEAT_NV]),
+    (FEAT_PAuth2,       [FEAT_PAuth]),
+    (FEAT_FPAC,         [FEAT_PAuth2]),
+    (FEAT_FPACCOMBINE,  [FEAT_FPAC]),
+    // Armv8.4
+    (FEAT_TLBIRANGE,    [FEAT_TLBIOS]),
+    // Armv8.5
+    (FEAT_FlagM2,       [FEAT_FlagM]),
+    (FEAT_MTE2,         [FEAT_MTE]),
+    (FEAT_MTE3,         [FEAT_MTE2]),
+    // Armv8.6
+    (FEAT_FGT2,         [FEAT_FGT]),
+    // Armv8.7
+    (FEAT_LS64_V,       [FEAT_LS64]),
+    (FEAT_LS64_ACCDATA, [FEAT_LS64_V]),
+    // Armv9.0
+    (FEAT_SVE2,         [FEAT_SVE]),
+    (FEAT_SVE2p1,       [FEAT_SVE2]),
+    (FEAT_SVE2p2,       [FEAT_SVE2p1]),
+    (FEAT_SVE_AES,      [FEAT_SVE2]),
+    (FEAT_SVE_PMULL128, [FEAT_SVE_AES]),
+    (FEAT_SVE_BitPerm,  [FEAT_SVE2]),
+    (FEAT_SVE_SHA3,     [FEAT_SVE2]),
+    (FEAT_SVE_SM4,      [FEAT_SVE2]),
+    (FEAT_TRBE,         [FEAT_ETE]),
+    (FEAT_TRBEv1p1,     [FEAT_TRBE]),
+    (FEAT_ETEv1p1,      [FEAT_ETE]),
+    (FEAT_ETEv1p2,      [FEAT_ETEv1p1]),
+    // Armv9.2
+    (FEAT_SME,          [FEAT_FP16, FEAT_BF16, FEAT_FCMA, FEAT_FHM]),
+    (FEAT_SME2,         [FEAT_SME]),
+    (FEAT_SME_FA64,     [FEAT_SME]),
+    (FEAT_SME_F64F64,   [FEAT_SME]),
+    (FEAT_SME_I16I64,   [FEAT_SME]),
+    (FEAT_BRBEv1p1,     [FEAT_BRBE]),
+    // Armv9.4
+    (FEAT_B16B16,       [FEAT_SVE2]),
+    (FEAT_SME2p1,       [FEAT_SME2]),
+    (FEAT_SME_B16B16,   [FEAT_SME2]),
+    (FEAT_SME_F16F16,   [FEAT_SME2]),
+    // Armv9.5
+    (FEAT_FP8FMA,       [FEAT_FP8]),
+    (FEAT_FP8DOT4,      [FEAT_FP8]),
+    (FEAT_FP8DOT2,      [FEAT_FP8]),
+    (FEAT_SME_LUTv2,    [FEAT_SME2]),
+    (FEAT_SME_F8F16,    [FEAT_SME2, FEAT_FP8]),
+    (FEAT_SME_F8F32,    [FEAT_SME2, FEAT_FP8]),
+    (FEAT_SSVE_FP8FMA,  [FEAT_SME]),
+    (FEAT_SSVE_FP8DOT4, [FEAT_SME]),
+    (FEAT_SSVE_FP8DOT2, [FEAT_SME]),
+    // Armv9.6
+    (FEAT_SVE_AES2,     [FEAT_SVE_AES]),
+    (FEAT_SVE_BFSCALE,  [FEAT_B16B16]),
+    (FEAT_SVE_F16F32MM, [FEAT_SVE]),
+    (FEAT_SME2p2,       [FEAT_SME2p1]),
+    (FEAT_SME_MOP4,     [FEAT_SME2]),
+    (FEAT_SME_TMOP,     [FEAT_SME2]),
+    (FEAT_SSVE_BitPerm, [FEAT_SME]),
+    (FEAT_SSVE_AES,     [FEAT_SME]),
+    (FEAT_SSVE_FEXPA,   [FEAT_SME]),
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArmVersion, Category};
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_implies() {
+        assert_eq!(Feature::FEAT_SVE2.implies(), &[Feature::FEAT_SVE]);
+        assert_eq!(Feature::FEAT_SME2.implies(), &[Feature::FEAT_SME]);
+        assert_eq!(Feature::FEAT_PAuth2.implies(), &[Feature::FEAT_PAuth]);
+        assert!(Feature::FEAT_SVE.implies().is_empty());
+    }
+
+    #[test]
+    fn test_closure() {
+        let features = implied_closure(&HashSet::from([
+            Feature::FEAT_SVE_PMULL128,
+            Feature::FEAT_PMUv3p9,
+        ]));
+
+        for feature in [
+            Feature::FEAT_SVE_PMULL128,
+            Feature::FEAT_SVE_AES,
+            Feature::FEAT_SVE2,
+            Feature::FEAT_SVE,
+            Feature::FEAT_PMUv3p1,
+        ] {
+            assert!(features.contains(&feature), "{feature:?}");
+        }
+        assert!(!features.contains(&Feature::FEAT_SVE2p1));
+
+        assert!(implied_closure(&HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_validate() {
+        let features = HashSet::from([
+            Feature::FEAT_SVE2,
+            Feature::FEAT_LSE128,
+            Feature::FEAT_LSE,
+            Feature::FEAT_MTE3,
+        ]);
+
+        let inconsistencies = validate(&features);
+
+        assert_eq!(inconsistencies.len(), 2);
+        assert!(inconsistencies.contains(&Inconsistency {
+            feature: Feature::FEAT_SVE2,
+            missing: Feature::FEAT_SVE,
+        }));
+        assert!(inconsistencies.contains(&Inconsistency {
+            feature: Feature::FEAT_MTE3,
+            missing: Feature::FEAT_MTE2,
+        }));
+        assert!(inconsistencies.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(
+            Inconsistency {
+                feature: Feature::FEAT_SVE2,
+                missing: Feature::FEAT_SVE,
+            }
+            .to_string(),
+            "FEAT_SVE2 implies FEAT_SVE"
+        );
+    }
+
+    #[test]
+    fn test_closure_is_consistent() {
+        for feature in Feature::iter() {
+            let closure = implied_closure(&HashSet::from([feature]));
+
+            assert!(validate(&closure).is_empty(), "{feature:?}");
+        }
+    }
+
+    #[test]
+    fn test_no_cycles() {
+        for feature in Feature::iter() {
+            let direct: HashSet<Feature> = feature.implies().iter().copied().collect();
+
+            assert!(
+                !implied_closure(&direct).contains(&feature),
+                "{feature:?} implies itself"
+            );
+        }
+    }
+
+    #[test]
+    fn test_armv9_vector_features() {
+        for feature in Feature::iter() {
+            let Some(version) = feature.arch_version() else {
+                continue;
+            };
+
+            if version >= ArmVersion::Armv9_0
+                && matches!(feature.category(), Some(Category::Sve | Category::Sme))
+            {
+                assert!(!feature.implies().is_empty(), "{feature:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_unique_entries() {
+        let features: HashSet<Feature> = FEATURE_DEPENDENCIES
+            .iter()
+            .map(|dependency| dependency.feature)
+            .collect();
+
+        assert_eq!(features.len(), FEATURE_DEPENDENCIES.len());
+    }
+}
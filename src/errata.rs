@@ -0,0 +1,335 @@
+//! Known errata of AArch64 cores, keyed on ranges of `MIDR_EL1`, i.e.,
+//! the implementer, the part number, and a range of revisions. The
+//! table follows `arch/arm64/kernel/cpu_errata.c` of the Linux kernel
+//! and the software developer errata notices of the vendors. It only
+//! lists errata that affect code generation or userspace visible
+//! behaviour.
+//!
+//! ```rust
+//! use aarch64_features::errata::Workaround;
+//! use aarch64_features::midr::Midr;
+//!
+//! // Neoverse N1 r3p1
+//! let midr = Midr::from(0x413f_d0c1);
+//!
+//! for erratum in midr.applicable_errata() {
+//!     println!("{erratum}");
+//! }
+//!
+//! assert!(midr
+//!     .applicable_errata()
+//!     .iter()
+//!     .any(|erratum| erratum.id() == "1542419"
+//!         && erratum.workaround() == Workaround::CacheMaintenance));
+//! ```
+
+use crate::cpu_type::{
+    Core, AMPERE_1_PART_NUM, ARM_NEOVERSE_N1_PART_NUM, ARM_NEOVERSE_N2_PART_NUM,
+};
+use crate::midr::{Implementer, Midr, MidrBuilder, MidrEL1};
+use std::fmt;
+
+const ARM_CORTEX_A53_PART_NUM: u64 = 0xD03;
+const ARM_CORTEX_A57_PART_NUM: u64 = 0xD07;
+const ARM_CORTEX_A76_PART_NUM: u64 = 0xD0B;
+const ARM_CORTEX_A77_PART_NUM: u64 = 0xD0D;
+const ARM_CORTEX_A510_PART_NUM: u64 = 0xD46;
+const ARM_CORTEX_A710_PART_NUM: u64 = 0xD47;
+
+/// The variant and the revision of `MIDR_EL1`, e.g., `r3p1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Revision {
+    variant: u64,
+    revision: u64,
+}
+
+impl Revision {
+    /// The revision `r<variant>p<revision>`
+    pub fn new(variant: u64, revision: u64) -> Self {
+        Revision { variant, revision }
+    }
+
+    /// The major revision, i.e., the variant
+    pub fn variant(&self) -> u64 {
+        self.variant
+    }
+
+    /// The minor revision
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+}
+
+impl fmt::Display for Revision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "r{}p{}", self.variant, self.revision)
+    }
+}
+
+/// What software has to do about an erratum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Workaround {
+    /// Compilers and linkers have to avoid instruction sequences
+    CodeGeneration,
+    /// Atomics, exclusives, or acquire and release need other sequences
+    Atomics,
+    /// Cache maintenance needs stronger or additional operations
+    CacheMaintenance,
+    /// TLB maintenance has to be repeated
+    Tlb,
+    /// Reads of the generic timer have to be trapped or repeated
+    Timer,
+    /// The hypervisor has to avoid or emulate a feature
+    Virtualization,
+    /// Debuggers have to avoid or emulate a feature
+    Debug,
+    /// Trace has to avoid or emulate a feature
+    Trace,
+}
+
+/// One erratum of one core
+#[derive(Debug)]
+pub struct Erratum {
+    id: &'static str,
+    core: &'static str,
+    implementer: Implementer,
+    part_num: u64,
+    first: Revision,
+    last: Revision,
+    workaround: Workaround,
+    description: &'static str,
+}
+
+impl Erratum {
+    /// The id of the vendor, e.g., `1463225`
+    pub fn id(&self) -> &'static str {
+        self.id
+    }
+
+    /// The name of the affected core, e.g., `Cortex-A76`
+    pub fn core_name(&self) -> &'static str {
+        self.core
+    }
+
+    /// The affected core if it is one of [`Core`]
+    pub fn core(&self) -> Option<Core> {
+        let midr = MidrBuilder::new()
+            .implementer(self.implementer)
+            .part_num(self.part_num)
+            .build();
+
+        Core::try_from(MidrEL1::Known(midr)).ok()
+    }
+
+    /// The first affected revision
+    pub fn first_revision(&self) -> Revision {
+        self.first
+    }
+
+    /// The last affected revision
+    pub fn last_revision(&self) -> Revision {
+        self.last
+    }
+
+    /// What software has to do about it
+    pub fn workaround(&self) -> Workaround {
+        self.workaround
+    }
+
+    /// A short description
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+
+    /// Whether the erratum affects `midr`
+    pub fn affects(&self, midr: &Midr) -> bool {
+        midr.check_implementer(self.implementer)
+            && midr.check_part_num(self.part_num)
+            && (self.first..=self.last).contains(&midr.revision())
+    }
+}
+
+impl fmt::Display for Erratum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} ({}-{}, {:?}): {}",
+            self.core, self.id, self.first, self.last, self.workaround, self.description
+        )
+    }
+}
+
+/// All known errata
+pub fn all_errata() -> &'static [Erratum] {
+    ERRATA
+}
+
+/// The errata that affect `midr`
+pub(crate) fn errata_of(midr: &Midr) -> Vec<&'static Erratum> {
+    ERRATA
+        .iter()
+        .filter(|erratum| erratum.affects(midr))
+        .collect()
+}
+
+macro_rules! declare_errata {
+    ($(
+        ($id:literal, $core:literal, $implementer:ident, $part_num:expr,
+         ($first_variant:literal, $first_revision:literal)..=($last_variant:literal, $last_revision:literal),
+         $workaround:ident, $description:literal),
+    )+) => {
+        const ERRATA: &[Erratum] = &[
+            $(
+                Erratum {
+                    id: $id,
+                    core: $core,
+                    implementer: Implementer::$implementer,
+                    part_num: $part_num,
+                    first: Revision { variant: $first_variant, revision: $first_revision },
+                    last: Revision { variant: $last_variant, revision: $last_revision },
+                    workaround: Workaround::$workaround,
+                    description: $description,
+                }
+            ),+
+        ];
+    }
+}
+
+#[rustfmt::skip]
+declare_errata!(
+    ("835769",      "Cortex-A53",  Arm,    ARM_CORTEX_A53_PART_NUM,   (0, 0)..=(0, 4),   CodeGeneration,   "A 64-bit multiply-accumulate after a load or store may produce a wrong result"),
+    ("843419",      "Cortex-A53",  Arm,    ARM_CORTEX_A53_PART_NUM,   (0, 0)..=(0, 4),   CodeGeneration,   "ADRP at the end of a 4KB page may compute a wrong address"),
+    ("826319",      "Cortex-A53",  Arm,    ARM_CORTEX_A53_PART_NUM,   (0, 0)..=(0, 2),   CacheMaintenance, "Data cache clean may deadlock unless it is upgraded to clean and invalidate"),
+    ("832075",      "Cortex-A57",  Arm,    ARM_CORTEX_A57_PART_NUM,   (0, 0)..=(1, 2),   Atomics,          "Load-acquire from Device memory may deadlock"),
+    ("834220",      "Cortex-A57",  Arm,    ARM_CORTEX_A57_PART_NUM,   (0, 0)..=(1, 2),   Virtualization,   "Stage 2 translation faults may be reported instead of stage 1 faults"),
+    ("1165522",     "Cortex-A76",  Arm,    ARM_CORTEX_A76_PART_NUM,   (0, 0)..=(2, 0),   Virtualization,   "Speculative AT instructions may corrupt the TLB"),
+    ("1188873",     "Cortex-A76",  Arm,    ARM_CORTEX_A76_PART_NUM,   (0, 0)..=(2, 0),   Timer,            "AArch32 reads of the generic timer may return a wrong value"),
+    ("1286807",     "Cortex-A76",  Arm,    ARM_CORTEX_A76_PART_NUM,   (0, 0)..=(3, 0),   Tlb,              "A TLB invalidation may not remove an entry that a concurrent access uses"),
+    ("1463225",     "Cortex-A76",  Arm,    ARM_CORTEX_A76_PART_NUM,   (0, 0)..=(3, 1),   Debug,            "Software step of an SVC may prevent interrupt recognition"),
+    ("1188873",     "Neoverse N1", Arm,    ARM_NEOVERSE_N1_PART_NUM,  (0, 0)..=(2, 0),   Timer,            "AArch32 reads of the generic timer may return a wrong value"),
+    ("1463225",     "Neoverse N1", Arm,    ARM_NEOVERSE_N1_PART_NUM,  (0, 0)..=(3, 1),   Debug,            "Software step of an SVC may prevent interrupt recognition"),
+    ("1542419",     "Neoverse N1", Arm,    ARM_NEOVERSE_N1_PART_NUM,  (3, 0)..=(4, 0),   CacheMaintenance, "Instruction fetches may see stale code after cache maintenance by another core"),
+    ("1508412",     "Cortex-A77",  Arm,    ARM_CORTEX_A77_PART_NUM,   (0, 0)..=(1, 0),   Atomics,          "A Device load and a store exclusive or PAR_EL1 read may deadlock"),
+    ("2441009",     "Cortex-A510", Arm,    ARM_CORTEX_A510_PART_NUM,  (0, 0)..=(1, 1),   Tlb,              "A broadcast TLB invalidation may complete before the entry is removed"),
+    ("2658417",     "Cortex-A510", Arm,    ARM_CORTEX_A510_PART_NUM,  (0, 0)..=(1, 1),   CodeGeneration,   "BFMMLA and VMMLA may produce wrong results"),
+    ("2119858",     "Cortex-A710", Arm,    ARM_CORTEX_A710_PART_NUM,  (0, 0)..=(2, 0),   Trace,            "TRBE in fill mode may overwrite trace data"),
+    ("2139208",     "Neoverse N2", Arm,    ARM_NEOVERSE_N2_PART_NUM,  (0, 0)..=(0, 2),   Trace,            "TRBE in fill mode may overwrite trace data"),
+    ("AC03_CPU_38", "AmpereOne",   Ampere, AMPERE_1_PART_NUM,         (0, 0)..=(15, 15), Virtualization,   "Hardware may update the access flag although FEAT_HAFDBS is not reported"),
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn midr(implementer: Implementer, part_num: u64, variant: u64, revision: u64) -> Midr {
+        MidrBuilder::new()
+            .implementer(implementer)
+            .part_num(part_num)
+            .variant(variant)
+            .revision(revision)
+            .build()
+    }
+
+    #[test]
+    fn test_applicable_errata() {
+        #[rustfmt::skip]
+        let table: &[(Implementer, u64, u64, u64, &[&str])] = &[
+            // Cortex-A53
+            (Implementer::Arm,       ARM_CORTEX_A53_PART_NUM,   0, 0, &["835769", "843419", "826319"]),
+            (Implementer::Arm,       ARM_CORTEX_A53_PART_NUM,   0, 2, &["835769", "843419", "826319"]),
+            (Implementer::Arm,       ARM_CORTEX_A53_PART_NUM,   0, 3, &["835769", "843419"]),
+            (Implementer::Arm,       ARM_CORTEX_A53_PART_NUM,   0, 4, &["835769", "843419"]),
+            (Implementer::Arm,       ARM_CORTEX_A53_PART_NUM,   1, 0, &[]),
+            // Cortex-A57
+            (Implementer::Arm,       ARM_CORTEX_A57_PART_NUM,   1, 2, &["832075", "834220"]),
+            (Implementer::Arm,       ARM_CORTEX_A57_PART_NUM,   1, 3, &[]),
+            // Cortex-A76
+            (Implementer::Arm,       ARM_CORTEX_A76_PART_NUM,   0, 0, &["1165522", "1188873", "1286807", "1463225"]),
+            (Implementer::Arm,       ARM_CORTEX_A76_PART_NUM,   2, 0, &["1165522", "1188873", "1286807", "1463225"]),
+            (Implementer::Arm,       ARM_CORTEX_A76_PART_NUM,   2, 1, &["1286807", "1463225"]),
+            (Implementer::Arm,       ARM_CORTEX_A76_PART_NUM,   3, 0, &["1286807", "1463225"]),
+            (Implementer::Arm,       ARM_CORTEX_A76_PART_NUM,   3, 1, &["1463225"]),
+            (Implementer::Arm,       ARM_CORTEX_A76_PART_NUM,   4, 0, &[]),
+            // Neoverse N1
+            (Implementer::Arm,       ARM_NEOVERSE_N1_PART_NUM,  1, 0, &["1188873", "1463225"]),
+            (Implementer::Arm,       ARM_NEOVERSE_N1_PART_NUM,  3, 0, &["1463225", "1542419"]),
+            (Implementer::Arm,       ARM_NEOVERSE_N1_PART_NUM,  3, 1, &["1463225", "1542419"]),
+            (Implementer::Arm,       ARM_NEOVERSE_N1_PART_NUM,  4, 0, &["1542419"]),
+            (Implementer::Arm,       ARM_NEOVERSE_N1_PART_NUM,  4, 1, &[]),
+            // Cortex-A77
+            (Implementer::Arm,       ARM_CORTEX_A77_PART_NUM,   1, 0, &["1508412"]),
+            (Implementer::Arm,       ARM_CORTEX_A77_PART_NUM,   1, 1, &[]),
+            // Cortex-A510
+            (Implementer::Arm,       ARM_CORTEX_A510_PART_NUM,  1, 1, &["2441009", "2658417"]),
+            (Implementer::Arm,       ARM_CORTEX_A510_PART_NUM,  1, 2, &[]),
+            // Cortex-A710 and Neoverse N2
+            (Implementer::Arm,       ARM_CORTEX_A710_PART_NUM,  2, 0, &["2119858"]),
+            (Implementer::Arm,       ARM_NEOVERSE_N2_PART_NUM,  0, 2, &["2139208"]),
+            (Implementer::Arm,       ARM_NEOVERSE_N2_PART_NUM,  0, 3, &[]),
+            // AmpereOne, all revisions
+            (Implementer::Ampere,    AMPERE_1_PART_NUM,         0, 0, &["AC03_CPU_38"]),
+            (Implementer::Ampere,    AMPERE_1_PART_NUM,         15, 15, &["AC03_CPU_38"]),
+            // the same part number of another implementer
+            (Implementer::Microsoft, ARM_NEOVERSE_N2_PART_NUM,  0, 0, &[]),
+            (Implementer::Apple,     ARM_CORTEX_A76_PART_NUM,   0, 0, &[]),
+        ];
+
+        for (implementer, part_num, variant, revision, ids) in table {
+            let midr = midr(*implementer, *part_num, *variant, *revision);
+            let errata: Vec<&str> = midr
+                .applicable_errata()
+                .iter()
+                .map(|erratum| erratum.id())
+                .collect();
+
+            assert_eq!(&errata, ids, "{midr}");
+        }
+    }
+
+    #[test]
+    fn test_raw_midr() {
+        // Neoverse N1 r3p1
+        let midr = Midr::from(0x413f_d0c1);
+        let errata = midr.applicable_errata();
+
+        assert_eq!(errata.len(), 2);
+        assert!(errata
+            .iter()
+            .all(|erratum| erratum.core() == Some(Core::NeoverseN1)));
+    }
+
+    #[test]
+    fn test_table() {
+        for erratum in all_errata() {
+            assert!(erratum.first <= erratum.last, "{erratum}");
+            assert!(erratum.last.variant <= 0xf && erratum.last.revision <= 0xf);
+            assert!(!erratum.description.is_empty());
+            assert!(!erratum.description.ends_with('.'), "{erratum}");
+        }
+
+        let n1 = all_errata()
+            .iter()
+            .find(|erratum| erratum.id() == "1542419");
+        assert_eq!(n1.and_then(Erratum::core), Some(Core::NeoverseN1));
+
+        let a76 = all_errata()
+            .iter()
+            .find(|erratum| erratum.id() == "1463225");
+        assert_eq!(a76.and_then(Erratum::core), None);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Revision::new(3, 1).to_string(), "r3p1");
+        assert!(Revision::new(2, 0) > Revision::new(1, 15));
+
+        let erratum = all_errata()
+            .iter()
+            .find(|erratum| erratum.id() == "843419")
+            .unwrap();
+        assert_eq!(
+            erratum.to_string(),
+            "Cortex-A53 843419 (r0p0-r0p4, CodeGeneration): ADRP at the end of a 4KB page may compute a wrong address"
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use crate::Feature;
+use std::fmt;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+
+/// The error of parsing an unknown feature name, see [`Feature::from_str`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFeatureError {
+    name: String,
+}
+
+impl ParseFeatureError {
+    /// The name that could not be parsed
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Display for ParseFeatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown feature: {}", self.name)
+    }
+}
+
+impl std::error::Error for ParseFeatureError {}
+
+/// The Arm spelling of the feature, e.g., `FEAT_LSE` or
+/// `FEAT_SVE_BitPerm`
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Parse the Arm spelling of a feature. The name is case-insensitive
+/// and the `FEAT_` prefix is optional.
+///
+/// ```rust
+/// use aarch64_features::Feature;
+///
+/// assert_eq!("FEAT_SVE_BitPerm".parse(), Ok(Feature::FEAT_SVE_BitPerm));
+/// assert_eq!("sve_bitperm".parse(), Ok(Feature::FEAT_SVE_BitPerm));
+/// assert!("FEAT_FOO".parse::<Feature>().is_err());
+/// ```
+impl FromStr for Feature {
+    type Err = ParseFeatureError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let trimmed = name.trim();
+
+        // an exact match wins over a match without the prefix, e.g., FP8
+        Feature::iter()
+            .find(|feature| feature.to_string().eq_ignore_ascii_case(trimmed))
+            .or_else(|| {
+                Feature::iter().find(|feature| {
+                    let spelling = feature.to_string();
+                    spelling
+                        .strip_prefix("FEAT_")
+                        .is_some_and(|spelling| spelling.eq_ignore_ascii_case(trimmed))
+                })
+            })
+            .ok_or_else(|| ParseFeatureError {
+                name: name.to_string(),
+            })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Feature {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Feature {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for feature in Feature::iter() {
+            assert_eq!(feature.to_string().parse(), Ok(feature));
+            assert_eq!(feature.to_string().to_lowercase().parse(), Ok(feature));
+        }
+    }
+
+    #[test]
+    fn test_spellings() {
+        assert_eq!(Feature::FEAT_LSE.to_string(), "FEAT_LSE");
+        assert_eq!("feat_lse".parse(), Ok(Feature::FEAT_LSE));
+        assert_eq!("LSE".parse(), Ok(Feature::FEAT_LSE));
+        assert_eq!(" lse\n".parse(), Ok(Feature::FEAT_LSE));
+        assert_eq!("FP8".parse(), Ok(Feature::FP8));
+        assert_eq!("feat_fp8".parse(), Ok(Feature::FEAT_FP8));
+
+        let err = "FEAT_".parse::<Feature>().unwrap_err();
+        assert_eq!(err.name(), "FEAT_");
+        assert_eq!(err.to_string(), "unknown feature: FEAT_");
+        assert!("".parse::<Feature>().is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let json = serde_json::to_string(&Feature::FEAT_SVE_BitPerm).unwrap();
+        assert_eq!(json, "\"FEAT_SVE_BitPerm\"");
+        assert_eq!(
+            serde_json::from_str::<Feature>(&json).unwrap(),
+            Feature::FEAT_SVE_BitPerm
+        );
+        assert_eq!(
+            serde_json::from_str::<Feature>("\"lse\"").unwrap(),
+            Feature::FEAT_LSE
+        );
+
+        let err = serde_json::from_str::<Feature>("\"FEAT_FOO\"").unwrap_err();
+        assert!(err.to_string().contains("unknown feature: FEAT_FOO"));
+        assert!(serde_json::from_str::<Feature>("42").is_err());
+    }
+}
@@ -0,0 +1,332 @@
+use crate::Feature;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::{BitAnd, BitOr, Sub};
+use strum::{EnumCount, IntoEnumIterator};
+
+/// The number of words of a [`FeatureSet`]
+pub(crate) const WORDS: usize = 5;
+
+const _: () = assert!(
+    WORDS == Feature::COUNT.div_ceil(64),
+    "WORDS does not match Feature::COUNT"
+);
+
+/// A set of features with one bit per [`Feature`]. It is a compact
+/// alternative to `HashSet<Feature>` that is cheap to copy, compare,
+/// and hash. It iterates in the order of [`Feature`].
+///
+/// ```rust
+/// use aarch64_features::{Feature, FeatureSet};
+///
+/// let armv8_1: FeatureSet = [Feature::FEAT_LSE, Feature::FEAT_RDM].into_iter().collect();
+/// let mut features = FeatureSet::new();
+/// features.insert(Feature::FEAT_LSE);
+///
+/// assert!(features.is_subset(&armv8_1));
+/// assert_eq!(
+///     armv8_1.difference(&features).iter().collect::<Vec<_>>(),
+///     vec![Feature::FEAT_RDM]
+/// );
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FeatureSet {
+    words: [u64; WORDS],
+}
+
+impl FeatureSet {
+    /// Create an empty set
+    pub const fn new() -> Self {
+        FeatureSet { words: [0; WORDS] }
+    }
+
+    /// Create a set from its words, see [`position`]
+    pub(crate) const fn from_words(words: [u64; WORDS]) -> Self {
+        FeatureSet { words }
+    }
+
+    /// The words of the set, see [`position`]
+    pub(crate) const fn words(&self) -> [u64; WORDS] {
+        self.words
+    }
+
+    /// Add a feature. Returns whether the feature was not in the set.
+    pub fn insert(&mut self, feature: Feature) -> bool {
+        let (word, bit) = position(feature);
+        let absent = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        absent
+    }
+
+    /// Remove a feature. Returns whether the feature was in the set.
+    pub fn remove(&mut self, feature: Feature) -> bool {
+        let (word, bit) = position(feature);
+        let present = self.words[word] & bit != 0;
+        self.words[word] &= !bit;
+        present
+    }
+
+    /// Whether the feature is in the set
+    pub fn contains(&self, feature: Feature) -> bool {
+        let (word, bit) = position(feature);
+        self.words[word] & bit != 0
+    }
+
+    /// The number of features in the set
+    pub fn len(&self) -> usize {
+        let len: u32 = self.words.iter().map(|word| word.count_ones()).sum();
+        usize::try_from(len).expect("a set has at most 64 * WORDS features")
+    }
+
+    /// Whether the set has no features
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Remove all features
+    pub fn clear(&mut self) {
+        self.words = [0; WORDS];
+    }
+
+    /// Iterate over the features in the order of [`Feature`]
+    pub fn iter(&self) -> impl Iterator<Item = Feature> + '_ {
+        Feature::iter().filter(|feature| self.contains(*feature))
+    }
+
+    /// The features in `self` or in `other`
+    pub fn union(&self, other: &FeatureSet) -> FeatureSet {
+        self.zip_with(other, |a, b| a | b)
+    }
+
+    /// The features in `self` and in `other`
+    pub fn intersection(&self, other: &FeatureSet) -> FeatureSet {
+        self.zip_with(other, |a, b| a & b)
+    }
+
+    /// The features in `self` but not in `other`
+    pub fn difference(&self, other: &FeatureSet) -> FeatureSet {
+        self.zip_with(other, |a, b| a & !b)
+    }
+
+    /// Whether all features of `self` are in `other`
+    pub fn is_subset(&self, other: &FeatureSet) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    /// Whether all features of `other` are in `self`
+    pub fn is_superset(&self, other: &FeatureSet) -> bool {
+        other.is_subset(self)
+    }
+
+    fn zip_with(&self, other: &FeatureSet, op: impl Fn(u64, u64) -> u64) -> FeatureSet {
+        let mut words = [0; WORDS];
+        for (idx, word) in words.iter_mut().enumerate() {
+            *word = op(self.words[idx], other.words[idx]);
+        }
+        FeatureSet { words }
+    }
+}
+
+/// The word and the bit of a feature in a [`FeatureSet`]
+#[allow(clippy::as_conversions)]
+pub(crate) fn position(feature: Feature) -> (usize, u64) {
+    let index = feature as usize;
+
+    (index / 64, 1 << (index % 64))
+}
+
+impl fmt::Debug for FeatureSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<Feature> for FeatureSet {
+    fn from_iter<I: IntoIterator<Item = Feature>>(iter: I) -> Self {
+        let mut set = FeatureSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<'a> FromIterator<&'a Feature> for FeatureSet {
+    fn from_iter<I: IntoIterator<Item = &'a Feature>>(iter: I) -> Self {
+        iter.into_iter().copied().collect()
+    }
+}
+
+impl Extend<Feature> for FeatureSet {
+    fn extend<I: IntoIterator<Item = Feature>>(&mut self, iter: I) {
+        for feature in iter {
+            self.insert(feature);
+        }
+    }
+}
+
+impl IntoIterator for FeatureSet {
+    type Item = Feature;
+    type IntoIter = std::vec::IntoIter<Feature>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter().collect::<Vec<_>>().into_iter()
+    }
+}
+
+impl From<&HashSet<Feature>> for FeatureSet {
+    fn from(features: &HashSet<Feature>) -> Self {
+        features.iter().collect()
+    }
+}
+
+impl From<HashSet<Feature>> for FeatureSet {
+    fn from(features: HashSet<Feature>) -> Self {
+        features.into_iter().collect()
+    }
+}
+
+impl From<&FeatureSet> for HashSet<Feature> {
+    fn from(features: &FeatureSet) -> Self {
+        features.iter().collect()
+    }
+}
+
+impl From<FeatureSet> for HashSet<Feature> {
+    fn from(features: FeatureSet) -> Self {
+        features.iter().collect()
+    }
+}
+
+impl BitOr for FeatureSet {
+    type Output = FeatureSet;
+
+    fn bitor(self, other: FeatureSet) -> FeatureSet {
+        self.union(&other)
+    }
+}
+
+impl BitAnd for FeatureSet {
+    type Output = FeatureSet;
+
+    fn bitand(self, other: FeatureSet) -> FeatureSet {
+        self.intersection(&other)
+    }
+}
+
+impl Sub for FeatureSet {
+    type Output = FeatureSet;
+
+    fn sub(self, other: FeatureSet) -> FeatureSet {
+        self.difference(&other)
+    }
+}
+
+/// A sequence of the names of the features, see [`Feature`]
+#[cfg(feature = "serde")]
+impl serde::Serialize for FeatureSet {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FeatureSet {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let features = Vec::<Feature>::deserialize(deserializer)?;
+
+        Ok(features.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_remove() {
+        let mut set = FeatureSet::new();
+        assert!(set.is_empty());
+
+        assert!(set.insert(Feature::FEAT_LSE));
+        assert!(!set.insert(Feature::FEAT_LSE));
+        assert!(set.insert(Feature::FP8));
+        assert!(set.contains(Feature::FEAT_LSE));
+        assert!(set.contains(Feature::FP8));
+        assert_eq!(set.len(), 2);
+
+        assert!(set.remove(Feature::FEAT_LSE));
+        assert!(!set.remove(Feature::FEAT_LSE));
+        assert_eq!(set.len(), 1);
+
+        set.clear();
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_all_features() {
+        let all: FeatureSet = Feature::iter().collect();
+
+        assert_eq!(all.len(), Feature::COUNT);
+        assert!(all.iter().eq(Feature::iter()));
+        assert_eq!(format!("{:?}", FeatureSet::new()), "{}");
+    }
+
+    #[test]
+    fn test_algebra() {
+        let a: FeatureSet = [Feature::FEAT_LSE, Feature::FEAT_SVE, Feature::FEAT_SME]
+            .into_iter()
+            .collect();
+        let b: FeatureSet = [Feature::FEAT_SVE, Feature::FEAT_SME2]
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            (a | b).iter().collect::<Vec<_>>(),
+            vec![
+                Feature::FEAT_LSE,
+                Feature::FEAT_SVE,
+                Feature::FEAT_SME,
+                Feature::FEAT_SME2
+            ]
+        );
+        assert_eq!(a & b, [Feature::FEAT_SVE].into_iter().collect());
+        assert_eq!(
+            a - b,
+            [Feature::FEAT_LSE, Feature::FEAT_SME].into_iter().collect()
+        );
+        assert!((a & b).is_subset(&a));
+        assert!(a.is_superset(&(a & b)));
+        assert!(!a.is_subset(&b));
+        assert!(FeatureSet::new().is_subset(&b));
+    }
+
+    #[test]
+    fn test_hash_set() {
+        let features = HashSet::from([Feature::FEAT_AES, Feature::FEAT_PMULL, Feature::FP8]);
+
+        let set = FeatureSet::from(&features);
+
+        assert_eq!(set.len(), 3);
+        assert_eq!(HashSet::from(set), features);
+        assert_eq!(HashSet::<Feature>::from(&FeatureSet::new()), HashSet::new());
+    }
+
+    #[test]
+    fn test_positions() {
+        let positions: HashSet<(usize, u64)> = Feature::iter().map(position).collect();
+
+        assert_eq!(positions.len(), Feature::COUNT);
+        assert!(positions.iter().all(|(word, _)| *word < WORDS));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let set: FeatureSet = [Feature::FEAT_SVE, Feature::FEAT_LSE].into_iter().collect();
+
+        let json = serde_json::to_string(&set).unwrap();
+
+        assert_eq!(json, r#"["FEAT_LSE","FEAT_SVE"]"#);
+        assert_eq!(serde_json::from_str::<FeatureSet>(&json).unwrap(), set);
+        assert!(serde_json::from_str::<FeatureSet>(r#"["FEAT_LSE","FEAT_FOO"]"#).is_err());
+    }
+}
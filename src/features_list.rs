@@ -1,48 +1,181 @@
 use crate::is_exposed_to_userspace;
 use crate::{
-    registers_info::{Register, RegistersInfo},
-    Feature,
+    registers_info::{Register, RegisterSnapshot},
+    ArmVersion, Category, Feature,
 };
 
 pub(super) fn get_feature_description(feature: &Feature) -> Option<String> {
-    match AARCH64_FEATURES.binary_search_by(|mat| mat.get_feature().cmp(feature)) {
-        Result::Ok(idx) => Some(AARCH64_FEATURES[idx].get_description().to_string()),
-        _ => None,
+    find_description(feature).map(|desc| desc.get_description().to_string())
+}
+
+/// `AARCH64_FEATURES` is sorted by the architecture version, not by the feature
+fn find_description(feature: &Feature) -> Option<&'static FeatureDescription> {
+    AARCH64_FEATURES
+        .iter()
+        .find(|desc| desc.get_feature() == *feature)
+}
+
+impl Feature {
+    /// The version of the architecture that introduced the feature.
+    /// Features that were added to a version later, e.g., FEAT_CSV2_3,
+    /// report the version they were added to. Returns `None` for
+    /// features that this crate cannot detect.
+    ///
+    /// ```rust
+    /// use aarch64_features::{ArmVersion, Feature};
+    ///
+    /// assert_eq!(Feature::FEAT_LSE.arch_version(), Some(ArmVersion::Armv8_1));
+    /// ```
+    pub fn arch_version(&self) -> Option<ArmVersion> {
+        find_description(self).map(|desc| desc.version)
+    }
+
+    /// The category of the feature. Returns `None` for features that
+    /// this crate cannot detect.
+    ///
+    /// ```rust
+    /// use aarch64_features::{Category, Feature};
+    ///
+    /// assert_eq!(Feature::FEAT_SHA3.category(), Some(Category::Crypto));
+    /// ```
+    pub fn category(&self) -> Option<Category> {
+        find_description(self).map(|desc| desc.category)
+    }
+
+    /// A short description of the feature from the ARMARM, see also
+    /// [`get_description`](crate::get_description)
+    ///
+    /// ```rust
+    /// use aarch64_features::Feature;
+    ///
+    /// assert_eq!(Feature::FEAT_LSE.description(), Some("Large System Extensions"));
+    /// ```
+    pub fn description(&self) -> Option<&'static str> {
+        find_description(self).map(|desc| desc.description)
+    }
+
+    /// The name of the feature without the `FEAT_` prefix in lowercase,
+    /// e.g., `lse` or `sve2p1`
+    ///
+    /// ```rust
+    /// use aarch64_features::Feature;
+    ///
+    /// assert_eq!(Feature::FEAT_LSE.lowercase_name(), "lse");
+    /// assert_eq!(Feature::FEAT_SME_F64F64.lowercase_name(), "sme_f64f64");
+    /// ```
+    pub fn lowercase_name(&self) -> String {
+        let name = format!("{self:?}");
+
+        name.strip_prefix("FEAT_").unwrap_or(&name).to_lowercase()
     }
 }
 
+/// All features of a category, sorted like [`Feature`]
+///
+/// ```rust
+/// use aarch64_features::{features_in_category, Category, Feature};
+///
+/// assert!(features_in_category(Category::Crypto).contains(&Feature::FEAT_AES));
+/// ```
+pub fn features_in_category(category: Category) -> Vec<Feature> {
+    sorted_features(|desc| desc.category == category)
+}
+
+/// All features that the given version introduced, sorted like [`Feature`]
+///
+/// ```rust
+/// use aarch64_features::{features_introduced_in, ArmVersion, Feature};
+///
+/// assert!(features_introduced_in(ArmVersion::Armv8_1).contains(&Feature::FEAT_LSE));
+/// ```
+pub fn features_introduced_in(version: ArmVersion) -> Vec<Feature> {
+    sorted_features(|desc| desc.version == version)
+}
+
+fn sorted_features(filter: impl Fn(&FeatureDescription) -> bool) -> Vec<Feature> {
+    let mut features: Vec<Feature> = AARCH64_FEATURES
+        .iter()
+        .filter(|desc| filter(desc))
+        .map(|desc| desc.get_feature())
+        .collect();
+
+    features.sort();
+    features.dedup();
+
+    features
+}
+
 pub(super) fn get_features() -> HashSet<Feature> {
-    let mut features = HashSet::new();
+    let snapshot = RegisterSnapshot::read();
 
-    let cpu_info = RegistersInfo::new();
+    get_features_from_snapshot(&snapshot)
+        .into_iter()
+        .filter(|feat| is_exposed_to_userspace(*feat))
+        .collect()
+}
+
+pub(super) fn get_features_from_snapshot(snapshot: &RegisterSnapshot) -> HashSet<Feature> {
+    let mut features = HashSet::new();
 
     for feature in AARCH64_FEATURES {
-        if let Some(feat) = feature.matches(&cpu_info) {
-            if is_exposed_to_userspace(feat) {
-                features.insert(feat);
-            }
+        if let Some(feat) = feature.matches(snapshot) {
+            features.insert(feat);
         }
     }
 
     features
 }
 
+/// The features that the bits `msb:lsb` of a register with the given
+/// value imply, see [`Field::features`](crate::Field::features)
+pub(crate) fn features_of_field(
+    register: Register,
+    msb: u64,
+    lsb: u64,
+    value: u64,
+) -> Vec<Feature> {
+    AARCH64_FEATURES
+        .iter()
+        .filter(|desc| desc.register == register)
+        .filter(|desc| {
+            desc.matcher.check_field(msb, lsb, value) || desc.matcher2.check_field(msb, lsb, value)
+        })
+        .map(|desc| desc.get_feature())
+        .collect()
+}
+
+/// The registers and the bits `(msb, lsb)` of all matchers
+#[cfg(test)]
+pub(crate) fn matched_fields() -> Vec<(Register, u64, u64)> {
+    let mut fields = Vec::new();
+
+    for desc in AARCH64_FEATURES {
+        for matcher in [&desc.matcher, &desc.matcher2] {
+            if let RegisterMatches::RegisterMatch(range, _) = matcher {
+                fields.push((desc.register, *range.end(), *range.start()));
+            }
+        }
+    }
+
+    fields
+}
+
 #[allow(unused)]
 #[derive(Hash, Eq, PartialEq)]
 struct FeatureDescription {
     feature: Feature,
-    version: crate::ARMVersion,
+    version: ArmVersion,
     //register: &'static str,
     register: Register,
     matcher: RegisterMatches,
     matcher2: RegisterMatches,
-    category: crate::Category,
+    category: Category,
     description: &'static str,
 }
 
 impl FeatureDescription {
-    pub(super) fn matches(&self, cpu_info: &RegistersInfo) -> Option<Feature> {
-        let register = cpu_info[self.register];
+    pub(super) fn matches(&self, snapshot: &RegisterSnapshot) -> Option<Feature> {
+        let register = snapshot.get(self.register)?;
 
         if self.matcher.check_match(register) || self.matcher2.check_match(register) {
             return Some(self.feature);
@@ -62,24 +195,53 @@ impl FeatureDescription {
 
 use std::{collections::HashSet, ops::RangeInclusive};
 
+/// Matchers for the fields of the ID registers.
+///
+/// Following the principles of the ID scheme in the ARMARM, a higher
+/// field value describes a superset of the functionality of a lower
+/// value. Features should be matched with [`AtLeast`](ValueMatcher::AtLeast)
+/// or, for signed fields, with [`SignedAtLeast`](ValueMatcher::SignedAtLeast).
+/// The exact matchers are reserved for fields that do not follow the scheme.
 #[allow(unused)]
 #[derive(Hash, Eq, PartialEq)]
 enum ValueMatcher {
     Value(u64),
     Or(u64, u64),
     Any(u64, u64, u64),
+    /// unsigned field: `field >= value`
+    AtLeast(u64),
+    /// unsigned field where all ones is IMPLEMENTATION DEFINED, e.g.,
+    /// ID_AA64DFR0_EL1.PMUVer: `low <= field <= high`
+    Between(u64, u64),
+    /// signed field where all ones, i.e., `-1`, means not implemented,
+    /// e.g., ID_AA64PFR0_EL1.FP: `field >= value`
+    SignedAtLeast(i64),
 }
 
 impl ValueMatcher {
-    fn check_match(&self, bits: u64) -> bool {
+    fn check_match(&self, bits: u64, width: u64) -> bool {
         match self {
             ValueMatcher::Value(val) => bits == *val,
             ValueMatcher::Or(a, b) => bits == *a || bits == *b,
             ValueMatcher::Any(a, b, c) => bits == *a || bits == *b || bits == *c,
+            ValueMatcher::AtLeast(val) => bits >= *val,
+            ValueMatcher::Between(low, high) => (*low..=*high).contains(&bits),
+            ValueMatcher::SignedAtLeast(val) => sign_extend(bits, width) >= *val,
         }
     }
 }
 
+/// interpret the `width` low bits of `bits` as two's complement
+fn sign_extend(bits: u64, width: u64) -> i64 {
+    let value = i64::try_from(bits).unwrap_or(i64::MAX);
+
+    if bits >> (width - 1) & 1 == 1 {
+        value - (1 << width)
+    } else {
+        value
+    }
+}
+
 #[derive(Hash, Eq, PartialEq)]
 enum RegisterMatches {
     Fill,
@@ -92,7 +254,18 @@ impl RegisterMatches {
             RegisterMatches::Fill => false,
             RegisterMatches::RegisterMatch(range, value) => {
                 let bits = self.extract_bits(range, register);
-                value.check_match(bits)
+                value.check_match(bits, range.end() - range.start() + 1)
+            }
+        }
+    }
+
+    /// whether the matcher is on the bits `msb:lsb` and matches the
+    /// value of the field
+    fn check_field(&self, msb: u64, lsb: u64, value: u64) -> bool {
+        match self {
+            RegisterMatches::Fill => false,
+            RegisterMatches::RegisterMatch(range, matcher) => {
+                *range == (lsb..=msb) && matcher.check_match(value, msb - lsb + 1)
             }
         }
     }
@@ -116,10 +289,10 @@ macro_rules! declare_features {
         ($feature:ident, $version:ident, $register:ident, $matcher:expr, $matcher2:expr, $category:expr, $description:expr),
     )+) => {
         /// The features of AARCH64 from the ARMARM
-        use crate::features_list::ValueMatcher::{Value, Or, Any};
+        use crate::features_list::ValueMatcher::{Value, Any, AtLeast, Between, SignedAtLeast};
         use crate::features_list::RegisterMatches::{RegisterMatch, Fill};
         use crate::Category::*;
-        use crate::ARMVersion::*;
+        use crate::ArmVersion::*;
         use crate::registers_info::Register::*;
         const AARCH64_FEATURES: &[FeatureDescription] = &[
             $(
@@ -139,173 +312,262 @@ macro_rules! declare_features {
 
 #[rustfmt::skip]
 declare_features!(
-    (FEAT_AdvSIMD,     Armv8_0, ID_AA64PFR0_EL1,  RegisterMatch(20..=23,  Or(0b000 ,0b0001)),   Fill,                                      Float,     "AdvSIMD"),
+    (FEAT_AdvSIMD,     Armv8_0, ID_AA64PFR0_EL1,  RegisterMatch(20..=23,  SignedAtLeast(0)),    Fill,                                      Float,     "AdvSIMD"),
     // Armv8.0
-    (FEAT_SB,          Armv8_0, ID_AA64ISAR1_EL1, RegisterMatch(36..=39,  Value(0b0001)),       Fill,                                      Unknown,   "Speculation Barrier"),
-    (FEAT_SSBS,        Armv8_0, ID_AA64PFR1_EL1,  RegisterMatch( 4..= 7,  Or(0b0001, 0b0010)),  Fill,                                      Unknown,   "Speculative Store Bypass Safe"),
-    (FEAT_CSV2,        Armv8_0, ID_AA64PFR0_EL1,  RegisterMatch(56..=59,  Value(0b0001)),       Fill,                                      Caches,    "Cache Speculation Variant 2"),
-    (FEAT_CSV2_2,      Armv8_0, ID_AA64PFR0_EL1,  RegisterMatch(56..=59,  Value(0b0010)),       Fill,                                      Caches,    "Cache Speculation Variant 2"),
-    (FEAT_CSV2_1p1,    Armv8_0, ID_AA64PFR1_EL1,  RegisterMatch(32..=35,  Value(0b0001)),       Fill,                                      Caches,    "Cache Speculation Variant 2"),
-    (FEAT_CSV2_1p2,    Armv8_0, ID_AA64PFR1_EL1,  RegisterMatch(32..=35,  Value(0b0010)),       Fill,                                      Caches,    "Cache Speculation Variant 2"),
-    (FEAT_CSV3,        Armv8_0, ID_AA64PFR0_EL1,  RegisterMatch(60..=63,  Value(0b0001)),       Fill,                                      Caches,    "Cache Speculation Variant 3"),
-    (FEAT_SPECRES,     Armv8_0, ID_AA64ISAR1_EL1, RegisterMatch(40..=43,  Value(0b0001)),       Fill,                                      Unknown,   "Speculation restriction instructions"),
-    (FEAT_DoubleLock,  Armv8_0, ID_AA64DFR0_EL1,  RegisterMatch(36..=39,  Value(0b0000)),       Fill,                                      Unknown,   "Double Lock"),
-    (FEAT_DGH,         Armv8_0, ID_AA64ISAR1_EL1, RegisterMatch(48..=51,  Value(0b0001)),       Fill,                                      Unknown,   "Data Gathering Hint"),
-    (FEAT_ETS,         Armv8_0, ID_AA64MMFR1_EL1, RegisterMatch(36..=39,  Value(0b0001)),       Fill,                                      Unknown,   "Enhanced Translation Synchronization"),
-    (FEAT_nTLBPA,      Armv8_0, ID_AA64MMFR1_EL1, RegisterMatch(48..=51,  Value(0b0001)),       Fill,                                      Unknown,   "Intermediate caching of translation table walks"),
-    (FEAT_AES,         Armv8_0, ID_AA64ISAR0_EL1, RegisterMatch( 4..= 7,  Value(0b0001)),       Fill,                                      Crypto,    "Advanced SIMD AES instructions"),
-    (FEAT_PMULL,       Armv8_0, ID_AA64ISAR0_EL1, RegisterMatch( 4..= 7,  Value(0b0010)),       Fill,                                      Unknown,   "Advanced SIMD PMULL instructions"),
-    (FEAT_SHA1,        Armv8_0, ID_AA64ISAR0_EL1, RegisterMatch( 8..=11,  Value(0b0001)),       Fill,                                      Crypto,    "Advanced SIMD SHA1 instructions"),
-    (FEAT_SHA256,      Armv8_0, ID_AA64ISAR0_EL1, RegisterMatch(12..=15,  Value(0b0001)),       Fill,                                      Crypto,    "Advanced SIMD SHA256 instructions"),
+    (FEAT_SB,          Armv8_0, ID_AA64ISAR1_EL1, RegisterMatch(36..=39,  AtLeast(0b0001)),     Fill,                                      Security,  "Speculation Barrier"),
+    (FEAT_SSBS,        Armv8_0, ID_AA64PFR1_EL1,  RegisterMatch( 4..= 7,  AtLeast(0b0001)),     Fill,                                      Security,  "Speculative Store Bypass Safe"),
+    (FEAT_CSV2,        Armv8_0, ID_AA64PFR0_EL1,  RegisterMatch(56..=59,  AtLeast(0b0001)),     Fill,                                      Caches,    "Cache Speculation Variant 2"),
+    (FEAT_CSV2_2,      Armv8_0, ID_AA64PFR0_EL1,  RegisterMatch(56..=59,  AtLeast(0b0010)),     Fill,                                      Caches,    "Cache Speculation Variant 2"),
+    (FEAT_CSV2_3,      Armv8_0, ID_AA64PFR0_EL1,  RegisterMatch(56..=59,  AtLeast(0b0011)),     Fill,                                      Caches,    "Cache Speculation Variant 2"),
+    (FEAT_CSV2_1p1,    Armv8_0, ID_AA64PFR1_EL1,  RegisterMatch(32..=35,  AtLeast(0b0001)),     Fill,                                      Caches,    "Cache Speculation Variant 2"),
+    (FEAT_CSV2_1p2,    Armv8_0, ID_AA64PFR1_EL1,  RegisterMatch(32..=35,  AtLeast(0b0010)),     Fill,                                      Caches,    "Cache Speculation Variant 2"),
+    (FEAT_CSV3,        Armv8_0, ID_AA64PFR0_EL1,  RegisterMatch(60..=63,  AtLeast(0b0001)),     Fill,                                      Caches,    "Cache Speculation Variant 3"),
+    (FEAT_SPECRES,     Armv8_0, ID_AA64ISAR1_EL1, RegisterMatch(40..=43,  AtLeast(0b0001)),     Fill,                                      Security,  "Speculation restriction instructions"),
+    (FEAT_DoubleLock,  Armv8_0, ID_AA64DFR0_EL1,  RegisterMatch(36..=39,  SignedAtLeast(0)),    Fill,                                      Debug,     "Double Lock"),
+    (FEAT_DGH,         Armv8_0, ID_AA64ISAR1_EL1, RegisterMatch(48..=51,  AtLeast(0b0001)),     Fill,                                      Memory,    "Data Gathering Hint"),
+    (FEAT_ETS,         Armv8_0, ID_AA64MMFR1_EL1, RegisterMatch(36..=39,  AtLeast(0b0001)),     Fill,                                      Memory,    "Enhanced Translation Synchronization"),
+    (FEAT_nTLBPA,      Armv8_0, ID_AA64MMFR1_EL1, RegisterMatch(48..=51,  AtLeast(0b0001)),     Fill,                                      Memory,    "Intermediate caching of translation table walks"),
+    (FEAT_AES,         Armv8_0, ID_AA64ISAR0_EL1, RegisterMatch( 4..= 7,  AtLeast(0b0001)),     Fill,                                      Crypto,    "Advanced SIMD AES instructions"),
+    (FEAT_PMULL,       Armv8_0, ID_AA64ISAR0_EL1, RegisterMatch( 4..= 7,  AtLeast(0b0010)),     Fill,                                      Crypto,    "Advanced SIMD PMULL instructions"),
+    (FEAT_SHA1,        Armv8_0, ID_AA64ISAR0_EL1, RegisterMatch( 8..=11,  AtLeast(0b0001)),     Fill,                                      Crypto,    "Advanced SIMD SHA1 instructions"),
+    (FEAT_SHA256,      Armv8_0, ID_AA64ISAR0_EL1, RegisterMatch(12..=15,  AtLeast(0b0001)),     Fill,                                      Crypto,    "Advanced SIMD SHA256 instructions"),
     // Armv8.1
-    (FEAT_LSE,         Armv8_1, ID_AA64ISAR0_EL1, RegisterMatch(20..=23,  Value(0b0010)),       Fill,                                      Atomics,   "Large System Extensions"),
-    (FEAT_RDM,         Armv8_1, ID_AA64ISAR0_EL1, RegisterMatch(28..=31,  Value(0b0001)),       Fill,                                      Float,     "Advanced SIMD rounding double multiply accumulate instructions"),
-    (FEAT_LOR,         Armv8_1, ID_AA64MMFR1_EL1, RegisterMatch(16..=19,  Value(0b0001)),       Fill,                                      Atomics,   "Limited ordering regions"),
-    (FEAT_HAFDBS,      Armv8_1, ID_AA64MMFR1_EL1, RegisterMatch(12..=15,  Value(0b0001)),       Fill,                                      Unknown,   "Hardware management of the Access flag and dirty state"),
-    (FEAT_PAN,         Armv8_1, ID_AA64MMFR1_EL1, RegisterMatch(20..=23,  Value(0b0001)),       Fill,                                      Unknown,   "Privileged access never"),
-    (FEAT_VMID16,      Armv8_1, ID_AA64MMFR1_EL1, RegisterMatch( 4..= 7,  Value(0b0010)),       Fill,                                      Unknown,   "16-bit VMID"),
-    (FEAT_VHE,         Armv8_1, ID_AA64DFR0_EL1,  RegisterMatch( 8..=11,  Value(0b0001)),       Fill,                                      Virtual,   "Virtualization Host Extensions"),
-    (FEAT_PMUv3p1,     Armv8_1, ID_AA64DFR0_EL1,  RegisterMatch( 8..=11,  Value(0b0010)),       Fill,                                      Unknown,   "PMU Extensions v3.1"),
-    (FEAT_PAN3,        Armv8_1, ID_AA64MMFR1_EL1, RegisterMatch(20..=23,  Value(0b0011)),       Fill,                                      Unknown,   "Support for SCTLR_ELx.EPAN"),
+    (FEAT_LSE,         Armv8_1, ID_AA64ISAR0_EL1, RegisterMatch(20..=23,  AtLeast(0b0010)),     Fill,                                      Atomics,   "Large System Extensions"),
+    (FEAT_LSE128,      Armv9_4, ID_AA64ISAR0_EL1, RegisterMatch(20..=23,  AtLeast(0b0011)),     Fill,                                      Atomics,   "128-bit Atomics"),
+    (FEAT_RDM,         Armv8_1, ID_AA64ISAR0_EL1, RegisterMatch(28..=31,  AtLeast(0b0001)),     Fill,                                      Float,     "Advanced SIMD rounding double multiply accumulate instructions"),
+    (FEAT_LOR,         Armv8_1, ID_AA64MMFR1_EL1, RegisterMatch(16..=19,  AtLeast(0b0001)),     Fill,                                      Atomics,   "Limited ordering regions"),
+    (FEAT_HAFDBS,      Armv8_1, ID_AA64MMFR1_EL1, RegisterMatch( 0..= 3,  AtLeast(0b0001)),     Fill,                                      Memory,    "Hardware management of the Access flag and dirty state"),
+    (FEAT_HAFT,        Armv8_9, ID_AA64MMFR1_EL1, RegisterMatch( 0..= 3,  AtLeast(0b0011)),     Fill,                                      Memory,    "Hardware managed Access Flag for Table descriptors"),
+    (FEAT_PAN,         Armv8_1, ID_AA64MMFR1_EL1, RegisterMatch(20..=23,  AtLeast(0b0001)),     Fill,                                      Security,  "Privileged access never"),
+    (FEAT_VMID16,      Armv8_1, ID_AA64MMFR1_EL1, RegisterMatch( 4..= 7,  AtLeast(0b0010)),     Fill,                                      Virtual,   "16-bit VMID"),
+    (FEAT_VHE,         Armv8_1, ID_AA64MMFR1_EL1, RegisterMatch( 8..=11,  AtLeast(0b0001)),     Fill,                                      Virtual,   "Virtualization Host Extensions"),
+    (FEAT_PMUv3p1,     Armv8_1, ID_AA64DFR0_EL1,  RegisterMatch( 8..=11,  Between(0b0100, 0b1110)), Fill,                                  Profiling, "PMU Extensions v3.1"),
+    (FEAT_PAN3,        Armv8_1, ID_AA64MMFR1_EL1, RegisterMatch(20..=23,  AtLeast(0b0011)),     Fill,                                      Security,  "Support for SCTLR_ELx.EPAN"),
     // Armv8.2
-    (FEAT_SHA512,      Armv8_2, ID_AA64ISAR0_EL1, RegisterMatch(12..=15,  Value(0b0010)),       Fill,                                      Crypto,    "Advanced SIMD SHA512 instructions"),
-    (FEAT_SHA3,        Armv8_2, ID_AA64ISAR0_EL1, RegisterMatch(32..=35,  Value(0b0001)),       Fill,                                      Crypto,    "Advanced SIMD SHA3 instructions"),
-    (FEAT_SM3,         Armv8_2, ID_AA64ISAR0_EL1, RegisterMatch(36..=39,  Value(0b0001)),       Fill,                                      Crypto,    "Advanced SIMD SM3 instructions"),
-    (FEAT_SM4,         Armv8_2, ID_AA64ISAR0_EL1, RegisterMatch(40..=43,  Value(0b0001)),       Fill,                                      Crypto,    "Advanced SIMD SM4 instructions"),
-    (FEAT_PAN2,        Armv8_2, ID_AA64MMFR1_EL1, RegisterMatch(20..=23,  Value(0b0010)),       Fill,                                      Unknown,   "AT S1E1R and AT S1E1W instruction variants affected by PSTATE.PAN"),
-    (FEAT_FP16,        Armv8_2, ID_AA64PFR0_EL1,  RegisterMatch(16..=19,  Value(0b0001)),       Fill,                                      Float,     "Half-precision floating-point data processing"),
-    (FEAT_DotProd,     Armv8_2, ID_AA64ISAR0_EL1, RegisterMatch(44..=47,  Value(0b0001)),       Fill,                                      Float,     "Advanced SIMD dot product instructions"),
-    (FEAT_FHM,         Armv8_2, ID_AA64ISAR0_EL1, RegisterMatch(48..=51,  Value(0b0001)),       Fill,                                      Float,     "Floating-point half-precision multiplication instructions"),
-    (FEAT_UAO,         Armv8_2, ID_AA64MMFR2_EL1, RegisterMatch( 4..= 7,  Value(0b0001)),       Fill,                                      Unknown,   "Unprivileged Access Override control"),
-    (FEAT_DPB,         Armv8_2, ID_AA64ISAR1_EL1, RegisterMatch( 0..= 3,  Value(0b0001)),       Fill,                                      Caches,    "DC CVAP instruction"),
-    (FEAT_VPIPT,       Armv8_2, CTR_EL0,          RegisterMatch(14..=15,  Value(0b0000)),       Fill,                                      Unknown,   "VMID-aware PIPT instruction cache"),
-    (FEAT_HPDS2,       Armv8_2, ID_AA64MMFR1_EL1, RegisterMatch(12..=15,  Value(0b0010)),       Fill,                                      Unknown,   "Translation table page-based hardware attributes"),
-    (FEAT_LPA,         Armv8_2, ID_AA64MMFR0_EL1, RegisterMatch( 0..= 3,  Value(0b0110)),       Fill,                                      Unknown,   "Large PA and IPA support"),
-    (FEAT_LVA,         Armv8_2, ID_AA64MMFR2_EL1, RegisterMatch(16..=19,  Value(0b0001)),       Fill,                                      Unknown,   "Large VA support"),
-    (FEAT_TTCNP,       Armv8_2, ID_AA64MMFR2_EL1, RegisterMatch( 0..= 3,  Value(0b0001)),       Fill,                                      Unknown,   "Translation table Common not private translations"),
-    (FEAT_XNX,         Armv8_2, ID_AA64MMFR1_EL1, RegisterMatch(28..=31,  Value(0b0001)),       Fill,                                      Unknown,   "Translation table stage 2 Unprivileged Execute-never"),
-    (FEAT_Debugv8p2,   Armv8_2, ID_AA64DFR0_EL1,  RegisterMatch( 0..= 3,  Value(0b1000)),       Fill,                                      Unknown,   "Debug v8.2"),
-    (FEAT_PCSRv8p2,    Armv8_2, EDDEVID,          RegisterMatch( 0..= 3,  Value(0b0000)),       Fill,                                      Unknown,   "PC Sample-based profiling"),
-    (FEAT_IESB,        Armv8_2, ID_AA64MMFR2_EL1, RegisterMatch(12..=15,  Value(0b0001)),       Fill,                                      Unknown,   "Implicit Error Synchronization event"),
+    (FEAT_SHA512,      Armv8_2, ID_AA64ISAR0_EL1, RegisterMatch(12..=15,  AtLeast(0b0010)),     Fill,                                      Crypto,    "Advanced SIMD SHA512 instructions"),
+    (FEAT_SHA3,        Armv8_2, ID_AA64ISAR0_EL1, RegisterMatch(32..=35,  AtLeast(0b0001)),     Fill,                                      Crypto,    "Advanced SIMD SHA3 instructions"),
+    (FEAT_SM3,         Armv8_2, ID_AA64ISAR0_EL1, RegisterMatch(36..=39,  AtLeast(0b0001)),     Fill,                                      Crypto,    "Advanced SIMD SM3 instructions"),
+    (FEAT_SM4,         Armv8_2, ID_AA64ISAR0_EL1, RegisterMatch(40..=43,  AtLeast(0b0001)),     Fill,                                      Crypto,    "Advanced SIMD SM4 instructions"),
+    (FEAT_PAN2,        Armv8_2, ID_AA64MMFR1_EL1, RegisterMatch(20..=23,  AtLeast(0b0010)),     Fill,                                      Security,  "AT S1E1R and AT S1E1W instruction variants affected by PSTATE.PAN"),
+    (FEAT_FP16,        Armv8_2, ID_AA64PFR0_EL1,  RegisterMatch(16..=19,  SignedAtLeast(1)),    Fill,                                      Float,     "Half-precision floating-point data processing"),
+    (FEAT_DotProd,     Armv8_2, ID_AA64ISAR0_EL1, RegisterMatch(44..=47,  AtLeast(0b0001)),     Fill,                                      Float,     "Advanced SIMD dot product instructions"),
+    (FEAT_FHM,         Armv8_2, ID_AA64ISAR0_EL1, RegisterMatch(48..=51,  AtLeast(0b0001)),     Fill,                                      Float,     "Floating-point half-precision multiplication instructions"),
+    (FEAT_UAO,         Armv8_2, ID_AA64MMFR2_EL1, RegisterMatch( 4..= 7,  AtLeast(0b0001)),     Fill,                                      Security,  "Unprivileged Access Override control"),
+    (FEAT_DPB,         Armv8_2, ID_AA64ISAR1_EL1, RegisterMatch( 0..= 3,  AtLeast(0b0001)),     Fill,                                      Caches,    "DC CVAP instruction"),
+    (FEAT_VPIPT,       Armv8_2, CTR_EL0,          RegisterMatch(14..=15,  Value(0b0000)),       Fill,                                      Caches,    "VMID-aware PIPT instruction cache"),
+    (FEAT_HPDS2,       Armv8_2, ID_AA64MMFR1_EL1, RegisterMatch(12..=15,  AtLeast(0b0010)),     Fill,                                      Memory,    "Translation table page-based hardware attributes"),
+    (FEAT_LPA,         Armv8_2, ID_AA64MMFR0_EL1, RegisterMatch( 0..= 3,  AtLeast(0b0110)),     Fill,                                      Memory,    "Large PA and IPA support"),
+    (FEAT_LVA,         Armv8_2, ID_AA64MMFR2_EL1, RegisterMatch(16..=19,  AtLeast(0b0001)),     Fill,                                      Memory,    "Large VA support"),
+    (FEAT_LVA3,        Armv9_4, ID_AA64MMFR2_EL1, RegisterMatch(16..=19,  AtLeast(0b0010)),     Fill,                                      Memory,    "56-bit VA"),
+    (FEAT_TTCNP,       Armv8_2, ID_AA64MMFR2_EL1, RegisterMatch( 0..= 3,  AtLeast(0b0001)),     Fill,                                      Memory,    "Translation table Common not private translations"),
+    (FEAT_XNX,         Armv8_2, ID_AA64MMFR1_EL1, RegisterMatch(28..=31,  AtLeast(0b0001)),     Fill,                                      Memory,    "Translation table stage 2 Unprivileged Execute-never"),
+    (FEAT_HPDS,        Armv8_1, ID_AA64MMFR1_EL1, RegisterMatch(12..=15,  AtLeast(0b0001)),     Fill,                                      Memory,    "Hierarchical permission disables"),
+    (FEAT_Debugv8p2,   Armv8_2, ID_AA64DFR0_EL1,  RegisterMatch( 0..= 3,  AtLeast(0b1000)),     Fill,                                      Debug,     "Debug v8.2"),
+    (FEAT_PCSRv8p2,    Armv8_2, EDDEVID,          RegisterMatch( 0..= 3,  Value(0b0000)),       Fill,                                      Profiling, "PC Sample-based profiling"),
+    (FEAT_IESB,        Armv8_2, ID_AA64MMFR2_EL1, RegisterMatch(12..=15,  AtLeast(0b0001)),     Fill,                                      Ras,       "Implicit Error Synchronization event"),
+    (FEAT_RAS,         Armv8_2, ID_AA64PFR0_EL1,  RegisterMatch(28..=31,  AtLeast(0b0001)),     Fill,                                      Ras,       "Reliability, Availability, and Serviceability Extension"),
+    (FEAT_SPE,         Armv8_2, ID_AA64DFR0_EL1,  RegisterMatch(32..=35,  AtLeast(0b0001)),     Fill,                                      Profiling, "Statistical Profiling Extension"),
     // added later
-    (FEAT_EVT,         Armv8_2, ID_AA64MMFR2_EL1, RegisterMatch(56..=59,  Value(0b0010)),       Fill,                                      Unknown,   "Enhanced Virtualization Traps"),
-    (FEAT_DPB2,        Armv8_2, ID_AA64ISAR1_EL1, RegisterMatch( 0..= 3,  Value(0b0010)),       Fill,                                      Caches,    "DC CVADP instruction"),
-    (FEAT_BF16,        Armv8_2, ID_AA64ISAR1_EL1, RegisterMatch(44..=47,  Value(0b0001)),       Fill,                                      Float,     "BFloat16 instructions"),
-    (FEAT_I8MM,        Armv8_2, ID_AA64ISAR1_EL1, RegisterMatch(52..=55,  Value(0b0001)),       Fill,                                      Float,     "Int8 matrix multiplication instructions"),
+    (FEAT_EVT,         Armv8_2, ID_AA64MMFR2_EL1, RegisterMatch(56..=59,  AtLeast(0b0010)),     Fill,                                      Virtual,   "Enhanced Virtualization Traps"),
+    (FEAT_DPB2,        Armv8_2, ID_AA64ISAR1_EL1, RegisterMatch( 0..= 3,  AtLeast(0b0010)),     Fill,                                      Caches,    "DC CVADP instruction"),
+    (FEAT_BF16,        Armv8_2, ID_AA64ISAR1_EL1, RegisterMatch(44..=47,  AtLeast(0b0001)),     Fill,                                      Float,     "BFloat16 instructions"),
+    (FEAT_I8MM,        Armv8_2, ID_AA64ISAR1_EL1, RegisterMatch(52..=55,  AtLeast(0b0001)),     Fill,                                      Float,     "Int8 matrix multiplication instructions"),
     // Armv8.3
-    (FEAT_FCMA,        Armv8_3, ID_AA64ISAR1_EL1, RegisterMatch(16..=19,  Value(0b0001)),       Fill,                                      Float,     "Floating-point complex number instructions"),
-    (FEAT_JSCVT,       Armv8_3, ID_AA64ISAR1_EL1, RegisterMatch(12..=15,  Value(0b0001)),       Fill,                                      Float,     "JavaScript conversion instructions"),
-    (FEAT_LRCPC,       Armv8_3, ID_AA64ISAR1_EL1, RegisterMatch(20..=23,  Value(0b0001)),       Fill,                                      Atomics,   "Load-acquire RCpc instructions"),
-    (FEAT_NV,          Armv8_3, ID_AA64MMFR2_EL1, RegisterMatch(24..=27,  Value(0b0001)),       Fill,                                      Virtual,   "Nested virtualization support"),
-    (FEAT_CCIDX,       Armv8_3, ID_AA64MMFR2_EL1, RegisterMatch(20..=23,  Value(0b0001)),       Fill,                                      Caches,    "Extended cache index"),
-    (FEAT_PAuth,       Armv8_3, ID_AA64ISAR1_EL1, RegisterMatch( 4..= 7,  Or(0b0001, 0b0010)),  RegisterMatch(8..=11, Or(0b0001, 0b0010)), Security,  "Pointer authentication"),
+    (FEAT_FCMA,        Armv8_3, ID_AA64ISAR1_EL1, RegisterMatch(16..=19,  AtLeast(0b0001)),     Fill,                                      Float,     "Floating-point complex number instructions"),
+    (FEAT_JSCVT,       Armv8_3, ID_AA64ISAR1_EL1, RegisterMatch(12..=15,  AtLeast(0b0001)),     Fill,                                      Float,     "JavaScript conversion instructions"),
+    (FEAT_LRCPC,       Armv8_3, ID_AA64ISAR1_EL1, RegisterMatch(20..=23,  AtLeast(0b0001)),     Fill,                                      Atomics,   "Load-acquire RCpc instructions"),
+    (FEAT_NV,          Armv8_3, ID_AA64MMFR2_EL1, RegisterMatch(24..=27,  AtLeast(0b0001)),     Fill,                                      Virtual,   "Nested virtualization support"),
+    (FEAT_CCIDX,       Armv8_3, ID_AA64MMFR2_EL1, RegisterMatch(20..=23,  AtLeast(0b0001)),     Fill,                                      Caches,    "Extended cache index"),
+    (FEAT_PAuth,       Armv8_3, ID_AA64ISAR1_EL1, RegisterMatch( 4..= 7,  AtLeast(0b0001)),     RegisterMatch(8..=11, AtLeast(0b0001)),    Security,  "Pointer authentication"),
     // added later
-    (FEAT_SPEv1p1,     Armv8_3, ID_AA64DFR0_EL1,  RegisterMatch(32..=35,  Value(0b0010)),       Fill,                                      Profiling, "Armv8.3 Statistical Profiling Extensions"),
-    (FEAT_DoPD,        Armv8_3, EDDEVID,          RegisterMatch(  4..=7,  Value(0b0001)),       Fill,                                      Debug,     "Debug over Powerdown"),
-    (FEAT_PAuth2,      Armv8_3, ID_AA64ISAR1_EL1, RegisterMatch(  4..=7,  Value(0b0011)),       RegisterMatch(8..=11, Value(0b0011)),      Security,  "Enhancements to pointer authentication"),
-    (FEAT_FPAC,        Armv8_3, ID_AA64ISAR1_EL1, RegisterMatch(  4..=7,  Or(0b0100, 0b0101)),  RegisterMatch(8..=11, Or(0b0100, 0b0101)), Security,  "Faulting on AUT* instructions"),
+    (FEAT_SPEv1p1,     Armv8_3, ID_AA64DFR0_EL1,  RegisterMatch(32..=35,  AtLeast(0b0010)),     Fill,                                      Profiling, "Armv8.3 Statistical Profiling Extensions"),
+    (FEAT_DoPD,        Armv8_3, EDDEVID,          RegisterMatch(  4..=7,  AtLeast(0b0001)),     Fill,                                      Debug,     "Debug over Powerdown"),
+    (FEAT_PAuth2,      Armv8_3, ID_AA64ISAR1_EL1, RegisterMatch(  4..=7,  AtLeast(0b0011)),     RegisterMatch(8..=11, AtLeast(0b0011)),    Security,  "Enhancements to pointer authentication"),
+    (FEAT_FPAC,        Armv8_3, ID_AA64ISAR1_EL1, RegisterMatch(  4..=7,  AtLeast(0b0100)),     RegisterMatch(8..=11, AtLeast(0b0100)),    Security,  "Faulting on AUT* instructions"),
+    (FEAT_FPACCOMBINE, Armv8_3, ID_AA64ISAR1_EL1, RegisterMatch(  4..=7,  AtLeast(0b0101)),     RegisterMatch(8..=11, AtLeast(0b0101)),    Security,  "Faulting on combined pointer authentication instructions"),
+    (FEAT_PACQARMA3,   Armv8_3, ID_AA64ISAR2_EL1, RegisterMatch(12..=15,  AtLeast(0b0001)),     Fill,                                      Security,  "Pointer authentication with the QARMA3 algorithm"),
     // Armv8.4
-    (FEAT_DIT,         Armv8_4, ID_AA64PFR0_EL1,  RegisterMatch(48..=51,  Value(0b0010)),       Fill,                                      Unknown,   "Data Independent Timing instructions"),
-    (FEAT_FlagM,       Armv8_4, ID_AA64ISAR0_EL1, RegisterMatch(52..=55,  Value(0b0001)),       Fill,                                      Unknown,   "Flag manipulation instructions v2"),
-    (FEAT_LRCPC2,      Armv8_4, ID_AA64ISAR1_EL1, RegisterMatch(20..=23,  Value(0b0010)),       Fill,                                      Atomics,   "Load-Acquire RCpc instructions v2"),
-    (FEAT_LSE2,        Armv8_4, ID_AA64MMFR2_EL1, RegisterMatch(32..=35,  Value(0b0001)),       Fill,                                      Atomics,   "Large System Extensions v2"),
-    (FEAT_TLBIOS,      Armv8_4, ID_AA64ISAR0_EL1, RegisterMatch(56..=59,  Or(0b0001, 0b0010)),  Fill,                                      Unknown,   "TLB invalidate instructions in Outer Shareable domain"),
-    (FEAT_TLBIRANGE,   Armv8_4, ID_AA64ISAR0_EL1, RegisterMatch(56..=59,  Value(0b0010)),       Fill,                                      Unknown,   "TLB invalidate range instructions"),
-    (FEAT_TTL,         Armv8_4, ID_AA64MMFR2_EL1, RegisterMatch(48..=51,  Value(0b0001)),       Fill,                                      Unknown,   "Translation Table Level"),
-    (FEAT_S2FWB,       Armv8_4, ID_AA64MMFR2_EL1, RegisterMatch(40..=43,  Value(0b0001)),       Fill,                                      Unknown,   "Stage 2 forced Write-Back"),
-    (FEAT_TTST,        Armv8_4, ID_AA64MMFR2_EL1, RegisterMatch(28..=31,  Value(0b0001)),       Fill,                                      Unknown,   "Small translation tables"),
-    (FEAT_BBM,         Armv8_4, ID_AA64MMFR2_EL1, RegisterMatch(52..=55,  Any(0b0000, 0b0001, 0b0010)),        Fill,                       Unknown,   "Translation table break-before-make levels"),
-    (FEAT_SEL2,        Armv8_4, ID_AA64PFR0_EL1,  RegisterMatch(36..=39,  Value(0b0001)),       Fill,                                      Unknown,   "Secure EL2"),
-    (FEAT_NV2,         Armv8_4, ID_AA64MMFR2_EL1, RegisterMatch(24..=27,  Value(0b0010)),       Fill,                                      Virtual,   "Enhanced nested virtualization support"),
-    (FEAT_IDST,        Armv8_4, ID_AA64MMFR2_EL1, RegisterMatch(36..=39,  Value(0b0001)),       Fill,                                      Unknown,   "ID space trap handling"),
-    (FEAT_CNTSC,       Armv8_4, CNTID,            RegisterMatch( 0..= 3,  Value(0b0001)),       Fill,                                      Unknown,   "Generic Counter Scaling"),
-    (FEAT_Debugv8p4,   Armv8_4, ID_AA64DFR0_EL1,  RegisterMatch( 0..= 3,  Value(0b1001)),       Fill,                                      Unknown,   "Debug v8.4"),
-    (FEAT_TRF,         Armv8_4, ID_AA64DFR0_EL1,  RegisterMatch(40..=43,  Value(0b0001)),       Fill,                                      Unknown,   "Self-hosted Trace Extensions"),
-    (FEAT_PMUv3p4,     Armv8_4, ID_AA64DFR0_EL1,  RegisterMatch( 8..=11,  Value(0b0101)),       Fill,                                      Unknown,   "PMU Extensions v3.4"),
-    (FEAT_RASv1p1,     Armv8_4, ID_AA64PFR0_EL1,  RegisterMatch(28..=31,  Value(0b0010)),       Fill,                                      Unknown,   "RAS Extension v1.1"),
-    (FEAT_DoubleFault, Armv8_4, ID_AA64PFR0_EL1,  RegisterMatch(28..=31,  Value(0b0010)),       Fill,                                      Unknown,   "Double Fault Extension"),
+    (FEAT_DIT,         Armv8_4, ID_AA64PFR0_EL1,  RegisterMatch(48..=51,  AtLeast(0b0001)),     Fill,                                      Security,  "Data Independent Timing instructions"),
+    (FEAT_FlagM,       Armv8_4, ID_AA64ISAR0_EL1, RegisterMatch(52..=55,  AtLeast(0b0001)),     Fill,                                      Integer,   "Flag manipulation instructions v2"),
+    (FEAT_LRCPC2,      Armv8_4, ID_AA64ISAR1_EL1, RegisterMatch(20..=23,  AtLeast(0b0010)),     Fill,                                      Atomics,   "Load-Acquire RCpc instructions v2"),
+    (FEAT_LRCPC3,      Armv8_9, ID_AA64ISAR1_EL1, RegisterMatch(20..=23,  AtLeast(0b0011)),     Fill,                                      Atomics,   "Load-Acquire RCpc instructions v3"),
+    (FEAT_LSE2,        Armv8_4, ID_AA64MMFR2_EL1, RegisterMatch(32..=35,  AtLeast(0b0001)),     Fill,                                      Atomics,   "Large System Extensions v2"),
+    (FEAT_TLBIOS,      Armv8_4, ID_AA64ISAR0_EL1, RegisterMatch(56..=59,  AtLeast(0b0001)),     Fill,                                      Memory,    "TLB invalidate instructions in Outer Shareable domain"),
+    (FEAT_TLBIRANGE,   Armv8_4, ID_AA64ISAR0_EL1, RegisterMatch(56..=59,  AtLeast(0b0010)),     Fill,                                      Memory,    "TLB invalidate range instructions"),
+    (FEAT_TTL,         Armv8_4, ID_AA64MMFR2_EL1, RegisterMatch(48..=51,  AtLeast(0b0001)),     Fill,                                      Memory,    "Translation Table Level"),
+    (FEAT_S2FWB,       Armv8_4, ID_AA64MMFR2_EL1, RegisterMatch(40..=43,  AtLeast(0b0001)),     Fill,                                      Memory,    "Stage 2 forced Write-Back"),
+    (FEAT_TTST,        Armv8_4, ID_AA64MMFR2_EL1, RegisterMatch(28..=31,  AtLeast(0b0001)),     Fill,                                      Memory,    "Small translation tables"),
+    (FEAT_BBM,         Armv8_4, ID_AA64MMFR2_EL1, RegisterMatch(52..=55,  Any(0b0000, 0b0001, 0b0010)),        Fill,                       Memory,    "Translation table break-before-make levels"),
+    (FEAT_SEL2,        Armv8_4, ID_AA64PFR0_EL1,  RegisterMatch(36..=39,  AtLeast(0b0001)),     Fill,                                      Virtual,   "Secure EL2"),
+    (FEAT_NV2,         Armv8_4, ID_AA64MMFR2_EL1, RegisterMatch(24..=27,  AtLeast(0b0010)),     Fill,                                      Virtual,   "Enhanced nested virtualization support"),
+    (FEAT_IDST,        Armv8_4, ID_AA64MMFR2_EL1, RegisterMatch(36..=39,  AtLeast(0b0001)),     Fill,                                      Virtual,   "ID space trap handling"),
+    (FEAT_CNTSC,       Armv8_4, CNTID,            RegisterMatch( 0..= 3,  AtLeast(0b0001)),     Fill,                                      System,    "Generic Counter Scaling"),
+    (FEAT_Debugv8p4,   Armv8_4, ID_AA64DFR0_EL1,  RegisterMatch( 0..= 3,  AtLeast(0b1001)),     Fill,                                      Debug,     "Debug v8.4"),
+    (FEAT_TRF,         Armv8_4, ID_AA64DFR0_EL1,  RegisterMatch(40..=43,  AtLeast(0b0001)),     Fill,                                      Debug,     "Self-hosted Trace Extensions"),
+    (FEAT_PMUv3p4,     Armv8_4, ID_AA64DFR0_EL1,  RegisterMatch( 8..=11,  Between(0b0101, 0b1110)), Fill,                                  Profiling, "PMU Extensions v3.4"),
+    (FEAT_RASv1p1,     Armv8_4, ID_AA64PFR0_EL1,  RegisterMatch(28..=31,  AtLeast(0b0010)),     Fill,                                      Ras,       "RAS Extension v1.1"),
+    (FEAT_DoubleFault, Armv8_4, ID_AA64PFR0_EL1,  RegisterMatch(28..=31,  AtLeast(0b0010)),     Fill,                                      Ras,       "Double Fault Extension"),
     // Armv8.5
-    (FEAT_FlagM2,      Armv8_5,  ID_AA64ISAR0_EL1, RegisterMatch(52..=55, Value(0b0010)),       Fill,                                      Unknown,   "Enhancements to flag manipulation instructions"),
-    (FEAT_FRINTTS,     Armv8_5,  ID_AA64ISAR1_EL1, RegisterMatch(32..=35 ,Value(0b0001)),       Fill,                                      Float,     "Floating-point to integer instructions"),
-    (FEAT_ExS,         Armv8_5,  ID_AA64MMFR0_EL1, RegisterMatch(44..=47 ,Value(0b0001)),       Fill,                                      Unknown,   "Context synchronization and exception handling"),
-   // (FEAT_GTG,         Armv8_5,  ID_AA64MMFR0_EL1, RegisterMatch(        ,Value()), Fill,                                                  Unknown,   "Guest translation granule size"),
-    (FEAT_BTI,         Armv8_5,  ID_AA64PFR1_EL1,  RegisterMatch( 0..= 3, Value(0b0001)),       Fill,                                      Security,  "Branch Target Identification"),
-    (FEAT_E0PD,        Armv8_5,  ID_AA64MMFR2_EL1, RegisterMatch(60..=63, Value(0b0001)),       Fill,                                      Unknown,   "Preventing EL0 access to halves of address maps"),
-    (FEAT_RNG,         Armv8_5,  ID_AA64ISAR0_EL1, RegisterMatch(60..=63, Value(0b0001)),       Fill,                                      Unknown,   "Random number generator"),
-    (FEAT_MTE,         Armv8_5,  ID_AA64PFR1_EL1,  RegisterMatch( 8..=11, Value(0b0001)),       Fill,                                      Security,  "Memory Tagging Extension"),
-    (FEAT_MTE2,        Armv8_5,  ID_AA64PFR1_EL1,  RegisterMatch( 8..=11, Value(0b0010)),       Fill,                                      Security,  "Memory Tagging Extension"),
-    (FEAT_PMUv3p5,     Armv8_5,  ID_AA64DFR0_EL1,  RegisterMatch( 8..=11, Value(0b0110)),       Fill,                                      Unknown,   "PMU Extensions v3.5"),
+    (FEAT_FlagM2,      Armv8_5,  ID_AA64ISAR0_EL1, RegisterMatch(52..=55, AtLeast(0b0010)),     Fill,                                      Integer,   "Enhancements to flag manipulation instructions"),
+    (FEAT_FRINTTS,     Armv8_5,  ID_AA64ISAR1_EL1, RegisterMatch(32..=35 ,AtLeast(0b0001)),     Fill,                                      Float,     "Floating-point to integer instructions"),
+    (FEAT_ExS,         Armv8_5,  ID_AA64MMFR0_EL1, RegisterMatch(44..=47 ,AtLeast(0b0001)),     Fill,                                      System,    "Context synchronization and exception handling"),
+   // (FEAT_GTG,         Armv8_5,  ID_AA64MMFR0_EL1, RegisterMatch(        ,Value()), Fill,                                                  Memory,    "Guest translation granule size"),
+    (FEAT_BTI,         Armv8_5,  ID_AA64PFR1_EL1,  RegisterMatch( 0..= 3, AtLeast(0b0001)),     Fill,                                      Security,  "Branch Target Identification"),
+    (FEAT_E0PD,        Armv8_5,  ID_AA64MMFR2_EL1, RegisterMatch(60..=63, AtLeast(0b0001)),     Fill,                                      Security,  "Preventing EL0 access to halves of address maps"),
+    (FEAT_RNG,         Armv8_5,  ID_AA64ISAR0_EL1, RegisterMatch(60..=63, AtLeast(0b0001)),     Fill,                                      Crypto,    "Random number generator"),
+    (FEAT_MTE,         Armv8_5,  ID_AA64PFR1_EL1,  RegisterMatch( 8..=11, AtLeast(0b0001)),     Fill,                                      Security,  "Memory Tagging Extension"),
+    (FEAT_MTE2,        Armv8_5,  ID_AA64PFR1_EL1,  RegisterMatch( 8..=11, AtLeast(0b0010)),     Fill,                                      Security,  "Memory Tagging Extension"),
+    (FEAT_PMUv3p5,     Armv8_5,  ID_AA64DFR0_EL1,  RegisterMatch( 8..=11, Between(0b0110, 0b1110)), Fill,                                  Profiling, "PMU Extensions v3.5"),
     // added later
-    (FEAT_MTE3,        Armv8_5,  ID_AA64PFR1_EL1,  RegisterMatch( 8..=11, Value(0b0011)),       Fill,                                      Unknown,   "MTE Asymmetric Fault Handling"),
-    (FEAT_RNG_TRAP,    Armv8_5,  ID_AA64PFR1_EL1,  RegisterMatch(28..=31, Value(0b0001)),       Fill,                                      Unknown,   "Trapping support for RNDR/RNDRRS"),
+    (FEAT_MTE3,        Armv8_5,  ID_AA64PFR1_EL1,  RegisterMatch( 8..=11, AtLeast(0b0011)),     Fill,                                      Security,  "MTE Asymmetric Fault Handling"),
+    (FEAT_RNG_TRAP,    Armv8_5,  ID_AA64PFR1_EL1,  RegisterMatch(28..=31, AtLeast(0b0001)),     Fill,                                      Crypto,    "Trapping support for RNDR/RNDRRS"),
     // Armv8.6
-    (FEAT_ECV,         Armv8_6,  ID_AA64MMFR0_EL1, RegisterMatch(60..=63, Or(0b0001, 0b0010)),  Fill,                                      Virtual,   "Enhanced Counter Virtualization"),
-    (FEAT_FGT,         Armv8_6,  ID_AA64MMFR0_EL1, RegisterMatch(56..=59, Value(0b0001)),       Fill,                                      Unknown,   "Fine Grain Traps"),
-    (FEAT_TWED,        Armv8_6,  ID_AA64MMFR1_EL1, RegisterMatch(32..=35, Value(0b0001)),       Fill,                                      Unknown,   "Delayed Trapping of WFE"),
-    (FEAT_AMUv1p1,     Armv8_6,  ID_AA64PFR0_EL1,  RegisterMatch(44..=47, Value(0b0010)),       Fill,                                      Virtual,   "AMU Extensions v1.1"),
-    (FEAT_MTPMU,       Armv8_6,  ID_AA64DFR0_EL1,  RegisterMatch(48..=51, Value(0b0001)),       Fill,                                      Unknown,   "Multi-threaded PMU Extensions"),
+    (FEAT_ECV,         Armv8_6,  ID_AA64MMFR0_EL1, RegisterMatch(60..=63, AtLeast(0b0001)),     Fill,                                      Virtual,   "Enhanced Counter Virtualization"),
+    (FEAT_FGT,         Armv8_6,  ID_AA64MMFR0_EL1, RegisterMatch(56..=59, AtLeast(0b0001)),     Fill,                                      Virtual,   "Fine Grain Traps"),
+    (FEAT_TWED,        Armv8_6,  ID_AA64MMFR1_EL1, RegisterMatch(32..=35, AtLeast(0b0001)),     Fill,                                      Virtual,   "Delayed Trapping of WFE"),
+    (FEAT_AMUv1p1,     Armv8_6,  ID_AA64PFR0_EL1,  RegisterMatch(44..=47, AtLeast(0b0010)),     Fill,                                      Profiling, "AMU Extensions v1.1"),
+    (FEAT_MTPMU,       Armv8_6,  ID_AA64DFR0_EL1,  RegisterMatch(48..=51, SignedAtLeast(1)),    Fill,                                      Profiling, "Multi-threaded PMU Extensions"),
     // Armv8.7
-    (FEAT_AFP,          Armv8_7, ID_AA64MMFR1_EL1, RegisterMatch(44..=47, Value(0b0001)),       Fill,                                      Float,     "Alternate floating-point behavior"),
-    (FEAT_RPRES,        Armv8_7, ID_AA64ISAR2_EL1, RegisterMatch( 4..=7,  Value(0b0001)),       Fill,                                      Float,     "Increased precision of Reciprocal Estimate and Reciprocal Square Root Estimate"),
-    (FEAT_LS64,         Armv8_7, ID_AA64ISAR1_EL1, RegisterMatch(60..=63, Value(0b0001)),       Fill,                                      Unknown,   "Support for 64 byte loads/stores"),
-    (FEAT_LS64_V,       Armv8_7, ID_AA64ISAR1_EL1, RegisterMatch(60..=63, Value(0b0010)),       Fill,                                      Unknown,   "Support for 64 byte loads/stores"),
-    (FEAT_LS64_ACCDATA, Armv8_7, ID_AA64ISAR1_EL1, RegisterMatch(60..=63, Value(0b0011)),       Fill,                                      Unknown,   "Support for 64 byte loads/stores"),
-    (FEAT_WFxT,         Armv8_7, ID_AA64ISAR2_EL1, RegisterMatch( 0..=3,  Value(0b0010)),       Fill,                                      Unknown,   "WFE and WFI instructions with timeout"),
+    (FEAT_AFP,          Armv8_7, ID_AA64MMFR1_EL1, RegisterMatch(44..=47, AtLeast(0b0001)),     Fill,                                      Float,     "Alternate floating-point behavior"),
+    (FEAT_RPRES,        Armv8_7, ID_AA64ISAR2_EL1, RegisterMatch( 4..=7,  AtLeast(0b0001)),     Fill,                                      Float,     "Increased precision of Reciprocal Estimate and Reciprocal Square Root Estimate"),
+    (FEAT_LS64,         Armv8_7, ID_AA64ISAR1_EL1, RegisterMatch(60..=63, AtLeast(0b0001)),     Fill,                                      Memory,    "Support for 64 byte loads/stores"),
+    (FEAT_LS64_V,       Armv8_7, ID_AA64ISAR1_EL1, RegisterMatch(60..=63, AtLeast(0b0010)),     Fill,                                      Memory,    "Support for 64 byte loads/stores"),
+    (FEAT_LS64_ACCDATA, Armv8_7, ID_AA64ISAR1_EL1, RegisterMatch(60..=63, AtLeast(0b0011)),     Fill,                                      Memory,    "Support for 64 byte loads/stores"),
+    (FEAT_WFxT,         Armv8_7, ID_AA64ISAR2_EL1, RegisterMatch( 0..=3,  AtLeast(0b0010)),     Fill,                                      System,    "WFE and WFI instructions with timeout"),
     //(FEAT_WFxT2,        Armv8_7, ID_AA64ISAR2_EL1, RegisterMatch(), Fill, Float, "WFE and WFI instructions with timeout"),
-    (FEAT_HCX,          Armv8_7, ID_AA64MMFR1_EL1, RegisterMatch(40..=43, Value(0b0001)),       Fill,                                      Unknown,   "Support for the HCRX_EL2 register"),
+    (FEAT_HCX,          Armv8_7, ID_AA64MMFR1_EL1, RegisterMatch(40..=43, AtLeast(0b0001)),     Fill,                                      Virtual,   "Support for the HCRX_EL2 register"),
     //(FEAT_LPA2,         Armv8_7, ID_AA64MMFR0_EL1, RegisterMatch(), Fill, Float, "Larger physical address for 4KB and 16KB translation granules"),
-    (FEAT_XS,           Armv8_7, ID_AA64ISAR1_EL1, RegisterMatch(56..=59, Value(0b0001)),       Fill,                                      Unknown,   "XS attribute"),
-    (FEAT_PMUv3p7,      Armv8_7, ID_AA64DFR0_EL1,  RegisterMatch( 8..=11, Value(0b0111)),       Fill,                                      Unknown,   "Armv8.7 PMU extensions"),
-    (FEAT_SPEv1p2,      Armv8_7, ID_AA64DFR0_EL1,  RegisterMatch(32..=35, Value(0b0011)),       Fill,                                      Unknown,   "Armv8.7 SPE extensions"),
+    (FEAT_XS,           Armv8_7, ID_AA64ISAR1_EL1, RegisterMatch(56..=59, AtLeast(0b0001)),     Fill,                                      Memory,    "XS attribute"),
+    (FEAT_PMUv3p7,      Armv8_7, ID_AA64DFR0_EL1,  RegisterMatch( 8..=11, Between(0b0111, 0b1110)), Fill,                                  Profiling, "Armv8.7 PMU extensions"),
+    (FEAT_SPEv1p2,      Armv8_7, ID_AA64DFR0_EL1,  RegisterMatch(32..=35, AtLeast(0b0011)),     Fill,                                      Profiling, "Armv8.7 SPE extensions"),
     // Armv8.8
-    (FEAT_MOPS,        Armv8_8, ID_AA64ISAR2_EL1,  RegisterMatch(16..=19, Value(0b0001)),       Fill,                                      Memory,    "Standardization of memory operations"),
-    (FEAT_HBC,         Armv8_8, ID_AA64ISAR2_EL1,  RegisterMatch(20..=23, Value(0b0001)),       Fill,                                      Unknown,   "Hinted conditional branches"),
-    (FEAT_NMI,         Armv8_8, ID_AA64PFR1_EL1,   RegisterMatch(36..=39, Value(0b0001)),       Fill,                                      Unknown,   "Non-maskable Interrupts"),
-    (FEAT_TIDCP1,      Armv8_8, ID_AA64MMFR1_EL1,  RegisterMatch(52..=55, Value(0b0001)),       Fill,                                      Unknown,   "EL0 use of IMPLEMENTATION DEFINED functionality"),
-    (FEAT_CMOW,        Armv8_8, ID_AA64MMFR1_EL1,  RegisterMatch(56..=59, Value(0b0001)),       Fill,                                      Unknown,   "Control for cache maintenance permission"),
-    (FEAT_PMUv3p8,     Armv8_8, ID_AA64DFR0_EL1,   RegisterMatch( 8..=11, Value(0b1000)),       Fill,                                      Unknown,   "Armv8.8 PMU extensions"),
-    (FEAT_HPMN0,       Armv8_8, ID_AA64DFR0_EL1,   RegisterMatch(60..=63, Value(0b0001)),       Fill,                                      Unknown,   "Setting of MDCR_EL2.HPMN to zero"),
-    //(FEAT_PMUv3_TH,    Armv8_8, PMMIR_EL1,         RegisterMatch(20..=23, Value()), Fill, Unknown, "Event counting threshold"),
-    (FEAT_SPEv1p3,     Armv8_8, ID_AA64DFR0_EL1,   RegisterMatch(32..=35, Value(0b0100)),       Fill,                                      Unknown,   "Armv8.8 Statistical Profiling Extensions"),
-    (FEAT_Debugv8p8,   Armv8_8, ID_AA64DFR0_EL1,   RegisterMatch( 0..=3,  Value(0b1010)),       Fill,                                      Unknown,   "Debug v8.8"),
+    (FEAT_MOPS,        Armv8_8, ID_AA64ISAR2_EL1,  RegisterMatch(16..=19, AtLeast(0b0001)),     Fill,                                      Memory,    "Standardization of memory operations"),
+    (FEAT_HBC,         Armv8_8, ID_AA64ISAR2_EL1,  RegisterMatch(20..=23, AtLeast(0b0001)),     Fill,                                      Branch,    "Hinted conditional branches"),
+    (FEAT_NMI,         Armv8_8, ID_AA64PFR1_EL1,   RegisterMatch(36..=39, AtLeast(0b0001)),     Fill,                                      System,    "Non-maskable Interrupts"),
+    (FEAT_TIDCP1,      Armv8_8, ID_AA64MMFR1_EL1,  RegisterMatch(52..=55, AtLeast(0b0001)),     Fill,                                      System,    "EL0 use of IMPLEMENTATION DEFINED functionality"),
+    (FEAT_CMOW,        Armv8_8, ID_AA64MMFR1_EL1,  RegisterMatch(56..=59, AtLeast(0b0001)),     Fill,                                      Caches,    "Control for cache maintenance permission"),
+    (FEAT_PMUv3p8,     Armv8_8, ID_AA64DFR0_EL1,   RegisterMatch( 8..=11, Between(0b1000, 0b1110)), Fill,                                  Profiling, "Armv8.8 PMU extensions"),
+    (FEAT_HPMN0,       Armv8_8, ID_AA64DFR0_EL1,   RegisterMatch(60..=63, AtLeast(0b0001)),     Fill,                                      Profiling, "Setting of MDCR_EL2.HPMN to zero"),
+    //(FEAT_PMUv3_TH,    Armv8_8, PMMIR_EL1,         RegisterMatch(20..=23, Value()), Fill, Profiling, "Event counting threshold"),
+    (FEAT_SPEv1p3,     Armv8_8, ID_AA64DFR0_EL1,   RegisterMatch(32..=35, AtLeast(0b0100)),     Fill,                                      Profiling, "Armv8.8 Statistical Profiling Extensions"),
+    (FEAT_Debugv8p8,   Armv8_8, ID_AA64DFR0_EL1,   RegisterMatch( 0..=3,  AtLeast(0b1010)),     Fill,                                      Debug,     "Debug v8.8"),
+
+    // Armv8.9
+    (FEAT_CSSC,        Armv8_9, ID_AA64ISAR2_EL1,  RegisterMatch(52..=55, AtLeast(0b0001)),     Fill,                                      Integer,   "Common Short Sequence Compression instructions"),
+    (FEAT_FGT2,        Armv8_9, ID_AA64MMFR0_EL1,  RegisterMatch(56..=59, AtLeast(0b0010)),     Fill,                                      Virtual,   "Fine-grained traps 2"),
+    (FEAT_PMUv3p9,     Armv8_9, ID_AA64DFR0_EL1,   RegisterMatch( 8..=11, Between(0b1001, 0b1110)), Fill,                                  Profiling, "Armv8.9 PMU extensions"),
+    (FEAT_RASv2,       Armv8_9, ID_AA64PFR0_EL1,   RegisterMatch(28..=31, AtLeast(0b0011)),     Fill,                                      Ras,       "RAS Extension v2"),
+    (FEAT_SPEv1p4,     Armv8_9, ID_AA64DFR0_EL1,   RegisterMatch(32..=35, AtLeast(0b0101)),     Fill,                                      Profiling, "Armv8.9 Statistical Profiling Extensions"),
 
     // Armv9.0
-    (FEAT_SVE2,        Armv9_0, ID_AA64ZFR0_EL1,   RegisterMatch( 0..= 3, Value(0b0001)),       Fill,                                      Float,     "Scalable Vector Extension version 2"),
-    (FEAT_SVE_AES,     Armv9_0, ID_AA64ZFR0_EL1,   RegisterMatch( 4..= 7, Value(0b0001)),       Fill,                                      Float,     "Scalable Vector AES instructions"),
-    (FEAT_SVE_BitPerm, Armv9_0, ID_AA64ZFR0_EL1,   RegisterMatch(16..=19, Value(0b0001)),       Fill,                                      Unknown,   "Scalable Vector Bit Permutes instructions"),
-    (FEAT_SVE_PMULL128,Armv9_0, ID_AA64ZFR0_EL1,   RegisterMatch( 4..= 7, Value(0b0010)),       Fill,                                      Unknown,   "Scalable Vector PMULL instructions"),
-    (FEAT_SVE_SHA3,    Armv9_0, ID_AA64ZFR0_EL1,   RegisterMatch(32..=35, Value(0b0001)),       Fill,                                      Unknown,   "Scalable Vector SHA3 instructions"),
-    (FEAT_SVE_SM4,     Armv9_0, ID_AA64ZFR0_EL1,   RegisterMatch(40..=43, Value(0b0001)),       Fill,                                      Unknown,   "Scalable Vector SM4 instructions"),
-    (FEAT_ETE,         Armv9_0, ID_AA64DFR0_EL1,   RegisterMatch( 4..= 7, Value(0b0001)),       Fill,                                      Unknown,   "Embedded Trace Extension"),
-    (FEAT_TRBE,        Armv9_0, ID_AA64DFR0_EL1,   RegisterMatch(44..=47, Value(0b0001)),       Fill,                                      Unknown,   "Trace Buffer Extension"),
+    (FEAT_SVE2,        Armv9_0, ID_AA64ZFR0_EL1,   RegisterMatch( 0..= 3, AtLeast(0b0001)),     Fill,                                      Sve,       "Scalable Vector Extension version 2"),
+    (FEAT_SVE2p1,      Armv9_4, ID_AA64ZFR0_EL1,   RegisterMatch( 0..= 3, AtLeast(0b0010)),     Fill,                                      Sve,       "Scalable Vector Extension version 2.1"),
+    (FEAT_SVE2p2,      Armv9_6, ID_AA64ZFR0_EL1,   RegisterMatch( 0..= 3, AtLeast(0b0011)),     Fill,                                      Sve,       "Scalable Vector Extension version 2.2"),
+    (FEAT_SVE_AES,     Armv9_0, ID_AA64ZFR0_EL1,   RegisterMatch( 4..= 7, AtLeast(0b0001)),     Fill,                                      Sve,       "Scalable Vector AES instructions"),
+    (FEAT_SVE_BitPerm, Armv9_0, ID_AA64ZFR0_EL1,   RegisterMatch(16..=19, AtLeast(0b0001)),     Fill,                                      Sve,       "Scalable Vector Bit Permutes instructions"),
+    (FEAT_SVE_PMULL128, Armv9_0, ID_AA64ZFR0_EL1,  RegisterMatch( 4..= 7, AtLeast(0b0010)),     Fill,                                      Sve,       "Scalable Vector PMULL instructions"),
+    (FEAT_SVE_SHA3,    Armv9_0, ID_AA64ZFR0_EL1,   RegisterMatch(32..=35, AtLeast(0b0001)),     Fill,                                      Sve,       "Scalable Vector SHA3 instructions"),
+    (FEAT_SVE_SM4,     Armv9_0, ID_AA64ZFR0_EL1,   RegisterMatch(40..=43, AtLeast(0b0001)),     Fill,                                      Sve,       "Scalable Vector SM4 instructions"),
+    (FEAT_ETE,         Armv9_0, ID_AA64DFR0_EL1,   RegisterMatch( 4..= 7, AtLeast(0b0001)),     Fill,                                      Debug,     "Embedded Trace Extension"),
+    (FEAT_TRBE,        Armv9_0, ID_AA64DFR0_EL1,   RegisterMatch(44..=47, AtLeast(0b0001)),     Fill,                                      Debug,     "Trace Buffer Extension"),
+    (FEAT_TRBEv1p1,    Armv9_3, ID_AA64DFR0_EL1,   RegisterMatch(44..=47, AtLeast(0b0010)),     Fill,                                      Debug,     "Trace Buffer Extension v1.1"),
 
     // Armv9.1
-    (FEAT_ETEv1p1,     Armv9_1, TRCDEVARCH,        RegisterMatch(16..=19, Value(0b0001)),       Fill,                                      Unknown,   "Embedded Trace Extension"),
+    (FEAT_ETEv1p1,     Armv9_1, TRCDEVARCH,        RegisterMatch(16..=19, AtLeast(0b0001)),     Fill,                                      Debug,     "Embedded Trace Extension"),
 
     // Armv9.2
-    (FEAT_BRBE,        Armv9_2, ID_AA64DFR0_EL1,   RegisterMatch(52..=55, Value(0b0001)),       Fill,                                      Jump,      "Branch Record Buffer Extension"),
-    (FEAT_ETEv1p2,     Armv9_2, TRCDEVARCH,        RegisterMatch(16..=19, Value(0b0010)),       Fill,                                      Unknown,   "Embedded Trace Extension"),
-    //(FEAT_RME,         Armv9_2, ,        RegisterMatch(16..=19, Value(0b0010)),       Fill,                                      Unknown,   "Realm Management Extension"),
-    (FEAT_SME,         Armv9_2, ID_AA64PFR1_EL1,   RegisterMatch(24..=27, Value(0b0001)),       Fill,                                      Sme,       "Scalable Matrix Extension"),
-    (FEAT_SME_FA64,    Armv9_2, ID_AA64SMFR0_EL1,  RegisterMatch(63..=63, Value(0b1)),          Fill,                                      Sme,       "Full Streaming SVE mode instructions"),
-    (FEAT_EBF16,       Armv9_2, ID_AA64ISAR1_EL1,  RegisterMatch(44..=47, Value(0b0010)),       Fill,                                      Float,     "AArch64 Extended BFloat16 instructions"),
-    (FEAT_SME_F64F64,  Armv9_2, ID_AA64SMFR0_EL1,  RegisterMatch(48..=48, Value(0b1)),          Fill,                                      Sme,       "Double-precision floating-point outer product instructions"),
-    (FEAT_SME_I16I64,  Armv9_2, ID_AA64SMFR0_EL1,  RegisterMatch(52..=55, Value(0b1111)),       Fill,                                      Sme,       "16-bit to 64-bit integer widening outer product instructions"),
+    (FEAT_BRBE,        Armv9_2, ID_AA64DFR0_EL1,   RegisterMatch(52..=55, AtLeast(0b0001)),     Fill,                                      Jump,      "Branch Record Buffer Extension"),
+    (FEAT_ETEv1p2,     Armv9_2, TRCDEVARCH,        RegisterMatch(16..=19, AtLeast(0b0010)),     Fill,                                      Debug,     "Embedded Trace Extension"),
+    //(FEAT_RME,         Armv9_2, ,        RegisterMatch(16..=19, Value(0b0010)),       Fill,                                      Security,  "Realm Management Extension"),
+    (FEAT_SME,         Armv9_2, ID_AA64PFR1_EL1,   RegisterMatch(24..=27, AtLeast(0b0001)),     Fill,                                      Sme,       "Scalable Matrix Extension"),
+    (FEAT_SME2,        Armv9_2, ID_AA64PFR1_EL1,   RegisterMatch(24..=27, AtLeast(0b0010)),     Fill,                                      Sme,       "Scalable Matrix Extension version 2"),
+    (FEAT_SME_FA64,    Armv9_2, ID_AA64SMFR0_EL1,  RegisterMatch(63..=63, AtLeast(0b1)),        Fill,                                      Sme,       "Full Streaming SVE mode instructions"),
+    (FEAT_EBF16,       Armv9_2, ID_AA64ISAR1_EL1,  RegisterMatch(44..=47, AtLeast(0b0010)),     Fill,                                      Float,     "AArch64 Extended BFloat16 instructions"),
+    (FEAT_SME_F64F64,  Armv9_2, ID_AA64SMFR0_EL1,  RegisterMatch(48..=48, AtLeast(0b1)),        Fill,                                      Sme,       "Double-precision floating-point outer product instructions"),
+    (FEAT_SME_I16I64,  Armv9_2, ID_AA64SMFR0_EL1,  RegisterMatch(52..=55, AtLeast(0b1111)),     Fill,                                      Sme,       "16-bit to 64-bit integer widening outer product instructions"),
 
     // Armv9.3
-    (FEAT_BRBEv1p1,      Armv9_3,  ID_AA64DFR0_EL1,  RegisterMatch(52..=55, Value(0b0010)),       Fill,                                      Jump,      "Branch Record Buffer Extension"),
+    (FEAT_BRBEv1p1,      Armv9_3,  ID_AA64DFR0_EL1,  RegisterMatch(52..=55, AtLeast(0b0010)),     Fill,                                      Jump,      "Branch Record Buffer Extension"),
     // missing: found by testing
-    (FEAT_F32MM,         Armv8_2,  ID_AA64ZFR0_EL1,  RegisterMatch(52..=55, Value(0b0001)),       Fill,                                      Float,     "Single-precision Matrix Multiplication"),
-    (FEAT_F64MM,         Armv8_2,  ID_AA64ZFR0_EL1,  RegisterMatch(56..=59, Value(0b0001)),       Fill,                                      Float,     "Single-precision Matrix Multiplication"),
-    (FEAT_SVE,           Armv8_2,  ID_AA64PFR0_EL1,  RegisterMatch(32..=35, Value(0b0001)),       Fill,                                      Float,     "Scalable Vector Extension"),
-    (FEAT_CP15SDISABLE2, Armv8_0,  NoRegister     ,  Fill                                 ,       Fill,                                      Unknown,   "CP15SDISABLE2"),
-    (FEAT_PCSRv8,        Armv8_0,  EDDEVID        ,  RegisterMatch(0..=3,   Value(0b0011)),       Fill,                                      Unknown,   "PC Sample-based Profiling Extension"),
+    (FEAT_F32MM,         Armv8_2,  ID_AA64ZFR0_EL1,  RegisterMatch(52..=55, AtLeast(0b0001)),     Fill,                                      Sve,       "Single-precision Matrix Multiplication"),
+    (FEAT_F64MM,         Armv8_2,  ID_AA64ZFR0_EL1,  RegisterMatch(56..=59, AtLeast(0b0001)),     Fill,                                      Sve,       "Single-precision Matrix Multiplication"),
+    (FEAT_SVE,           Armv8_2,  ID_AA64PFR0_EL1,  RegisterMatch(32..=35, AtLeast(0b0001)),     Fill,                                      Sve,       "Scalable Vector Extension"),
+    (FEAT_CP15SDISABLE2, Armv8_0,  NoRegister,       Fill,                                        Fill,                                      System,    "CP15SDISABLE2"),
+    (FEAT_PCSRv8,        Armv8_0,  EDDEVID,          RegisterMatch(0..=3,   AtLeast(0b0011)),     Fill,                                      Profiling, "PC Sample-based Profiling Extension"),
+
+    // Armv9.5
+    (FEAT_CPA,             Armv9_5,  ID_AA64ISAR3_EL1,  RegisterMatch( 0..= 3, AtLeast(0b0001)),     Fill,                                      Integer,   "Instruction-only Checked Pointer Arithmetic"),
+    (FEAT_FAMINMAX,        Armv9_5,  ID_AA64ISAR3_EL1,  RegisterMatch( 4..= 7, AtLeast(0b0001)),     Fill,                                      Float,     "Floating-point maximum and minimum absolute value instructions"),
+    (FEAT_LUT,             Armv9_5,  ID_AA64ISAR2_EL1,  RegisterMatch(56..=59, AtLeast(0b0001)),     Fill,                                      Integer,   "Lookup table instructions with 2-bit and 4-bit indices"),
 
     // Armv9.6
-    (FEAT_CMPBR,           Armv9_6,  ID_AA64ISAR2_EL1,  RegisterMatch(55..=25, Value(0b0010)),       Fill,                                      Branch,     "Compare and Branch instructions"),
-    (FEAT_LSFE,           Armv9_6,  ID_AA64ISAR3_EL1,  RegisterMatch(19..=16, Value(0b0001)),       Fill,                                      Atomics,     "Large System Float Extension"),
-    (FEAT_FPRCVT,          Armv9_6,  ID_AA64ISAR3_EL1,  RegisterMatch(31..=28, Value(0b0001)),       Fill,                                      Float,     "Floating-Point to/from Integer in Scalar FP register"),
-    (FEAT_SME_TMOP,          Armv9_6,  ID_AA64SMFR0_EL1,  RegisterMatch(16..=16, Value(01)),       Fill,                                      Float,     "Structured sparsity outer product instructions"),
+    (FEAT_CMPBR,           Armv9_6,  ID_AA64ISAR2_EL1,  RegisterMatch(52..=55, AtLeast(0b0010)),     Fill,                                      Branch,     "Compare and Branch instructions"),
+    (FEAT_LSFE,           Armv9_6,  ID_AA64ISAR3_EL1,  RegisterMatch(16..=19, AtLeast(0b0001)),     Fill,                                      Atomics,     "Large System Float Extension"),
+    (FEAT_FPRCVT,          Armv9_6,  ID_AA64ISAR3_EL1,  RegisterMatch(28..=31, AtLeast(0b0001)),     Fill,                                      Float,     "Floating-Point to/from Integer in Scalar FP register"),
+    (FEAT_SME_TMOP,          Armv9_6,  ID_AA64SMFR0_EL1,  RegisterMatch(16..=16, AtLeast(0b1)),    Fill,                                      Sme,       "Structured sparsity outer product instructions"),
+
+    // SVE
+    (FEAT_B16B16,       Armv9_4,  ID_AA64ZFR0_EL1,   RegisterMatch(24..=27, AtLeast(0b0001)), Fill,                                      Sve,       "Non-widening BFloat16 to BFloat16 arithmetic for SVE2.1 and SME2.1"),
+    (FEAT_SVE_BFSCALE,  Armv9_6,  ID_AA64ZFR0_EL1,   RegisterMatch(24..=27, AtLeast(0b0010)), Fill,                                      Sve,       "BFloat16 Floating-Point Adjust Exponent"),
+    (FEAT_SVE_AES2,     Armv9_6,  ID_AA64ZFR0_EL1,   RegisterMatch( 4..= 7, AtLeast(0b0011)), Fill,                                      Sve,       "SVE multi-vector Advanced Encryption Standard and 128-bit polynomial multiply long instructions"),
+    (FEAT_SVE_F16F32MM, Armv9_6,  ID_AA64ZFR0_EL1,   RegisterMatch(48..=51, AtLeast(0b0001)), Fill,                                      Sve,       "SVE Half-precision floating-point matrix multiply-accumulate to single-precision"),
+
+    // ID_AA64DFR1_EL1
+    (FEAT_ITE,          Armv8_9,  ID_AA64DFR1_EL1,   RegisterMatch(44..=47, AtLeast(0b0001)), Fill,                                      Debug,     "Instrumentation trace extension"),
+    (FEAT_ABLE,         Armv8_9,  ID_AA64DFR1_EL1,   RegisterMatch(40..=43, AtLeast(0b0001)), Fill,                                      Debug,     "Address Breakpoint Linking extension"),
+    (FEAT_PMUv3_ICNTR,  Armv8_9,  ID_AA64DFR1_EL1,   RegisterMatch(36..=39, AtLeast(0b0001)), Fill,                                      Profiling, "PMU instruction counter"),
+    (FEAT_SPMU,         Armv8_9,  ID_AA64DFR1_EL1,   RegisterMatch(32..=35, AtLeast(0b0001)), Fill,                                      Profiling, "System PMU"),
+
+    // ID_AA64MMFR3_EL1
+    (FEAT_TCR2,         Armv8_9,  ID_AA64MMFR3_EL1,  RegisterMatch( 0..= 3, AtLeast(0b0001)), Fill,                                      Memory,    "Extension to TCR_ELx"),
+    (FEAT_SCTLR2,       Armv8_9,  ID_AA64MMFR3_EL1,  RegisterMatch( 4..= 7, AtLeast(0b0001)), Fill,                                      System,    "Extension to SCTLR_ELx"),
+    (FEAT_S1PIE,        Armv8_9,  ID_AA64MMFR3_EL1,  RegisterMatch( 8..=11, AtLeast(0b0001)), Fill,                                      Memory,    "Permission model enhancements"),
+    (FEAT_S2PIE,        Armv8_9,  ID_AA64MMFR3_EL1,  RegisterMatch(12..=15, AtLeast(0b0001)), Fill,                                      Memory,    "Permission model enhancements"),
+    (FEAT_S1POE,        Armv8_9,  ID_AA64MMFR3_EL1,  RegisterMatch(16..=19, AtLeast(0b0001)), Fill,                                      Memory,    "Permission model enhancements"),
+    (FEAT_S2POE,        Armv8_9,  ID_AA64MMFR3_EL1,  RegisterMatch(20..=23, AtLeast(0b0001)), Fill,                                      Memory,    "Permission model enhancements"),
+    (FEAT_AIE,          Armv8_9,  ID_AA64MMFR3_EL1,  RegisterMatch(24..=27, AtLeast(0b0001)), Fill,                                      Memory,    "Memory Attribute Index Enhancement"),
+    (FEAT_MEC,          Armv9_4,  ID_AA64MMFR3_EL1,  RegisterMatch(28..=31, AtLeast(0b0001)), Fill,                                      Security,  "Memory Encryption Contexts"),
+    (FEAT_D128,         Armv9_4,  ID_AA64MMFR3_EL1,  RegisterMatch(32..=35, AtLeast(0b0001)), Fill,                                      Memory,    "128-bit Translation Tables, 56 bit PA"),
+    (FEAT_ANERR,        Armv8_9,  ID_AA64MMFR3_EL1,  RegisterMatch(44..=47, AtLeast(0b0001)), Fill,                                      Ras,       "RASv2 Additional Error syndrome reporting, for Device and Normal memory"),
+    (FEAT_ADERR,        Armv8_9,  ID_AA64MMFR3_EL1,  RegisterMatch(56..=59, AtLeast(0b0001)), Fill,                                      Ras,       "RASv2 Additional Error syndrome reporting, for Device and Normal memory"),
+
+    // ID_AA64MMFR4_EL1
+    (FEAT_ASID2,        Armv9_5,  ID_AA64MMFR4_EL1,  RegisterMatch( 8..=11, AtLeast(0b0001)), Fill,                                      Memory,    "Support for concurrent use of two ASIDs"),
+    (FEAT_HACDBS,       Armv9_5,  ID_AA64MMFR4_EL1,  RegisterMatch(12..=15, AtLeast(0b0001)), Fill,                                      Virtual,   "Hardware accelerator for cleaning Dirty state"),
+    (FEAT_FGWTE3,       Armv9_5,  ID_AA64MMFR4_EL1,  RegisterMatch(16..=19, AtLeast(0b0001)), Fill,                                      Security,  "Fine-Grained Write Trap EL3"),
+    (FEAT_E2HO,         Armv9_5,  ID_AA64MMFR4_EL1,  RegisterMatch(24..=27, SignedAtLeast(0)),    Fill,                                      Virtual,   "Programming of HCR_EL2.E2H"),
+    (FEAT_E3DSE,        Armv9_5,  ID_AA64MMFR4_EL1,  RegisterMatch(36..=39, AtLeast(0b0001)), Fill,                                      Ras,       "Delegated SError exception injection"),
+
+    // ID_AA64PFR2_EL1
+    (FEAT_MTE_PERM,     Armv8_9,  ID_AA64PFR2_EL1,   RegisterMatch( 0..= 3, AtLeast(0b0001)), Fill,                                      Security,  "Allocation tag access permission"),
+    (FEAT_MTE_STORE_ONLY, Armv8_9,  ID_AA64PFR2_EL1,   RegisterMatch( 4..= 7, AtLeast(0b0001)), Fill,                                      Security,  "Store-only Tag checking"),
+    (FEAT_MTE_TAGGED_FAR, Armv8_9,  ID_AA64PFR2_EL1,   RegisterMatch( 8..=11, AtLeast(0b0001)), Fill,                                      Security,  "Reporting of all non-address bits on a fault"),
+    (FEAT_FPMR,         Armv9_5,  ID_AA64PFR2_EL1,   RegisterMatch(32..=35, AtLeast(0b0001)), Fill,                                      Float,     "Floating-point Mode Register"),
+
+    // ID_AA64FPFR0_EL1
+    (FEAT_FP8,          Armv9_5,  ID_AA64FPFR0_EL1,  RegisterMatch(31..=31, AtLeast(0b1)),    Fill,                                      Float,     "FP8 convert instructions"),
+    // FP8 is the name of FEAT_FP8 from older versions of the crate,
+    // matched from the same field so that both are always detected
+    (FP8,               Armv9_5,  ID_AA64FPFR0_EL1,  RegisterMatch(31..=31, AtLeast(0b1)),    Fill,                                      Float,     "FP8 convert instructions"),
+    (FEAT_FP8FMA,       Armv9_5,  ID_AA64FPFR0_EL1,  RegisterMatch(30..=30, AtLeast(0b1)),    Fill,                                      Float,     "FP8 multiply-accumulate to half-precision and single-precision"),
+    (FEAT_FP8DOT4,      Armv9_5,  ID_AA64FPFR0_EL1,  RegisterMatch(29..=29, AtLeast(0b1)),    Fill,                                      Float,     "FP8 4-way dot product to single-precision instructions"),
+    (FEAT_FP8DOT2,      Armv9_5,  ID_AA64FPFR0_EL1,  RegisterMatch(28..=28, AtLeast(0b1)),    Fill,                                      Float,     "FP8 2-way dot product to half-precision instructions"),
+
+    // ID_AA64SMFR0_EL1, SMEver is 0b0000 for FEAT_SME and 0b0001 for FEAT_SME2
+    (FEAT_SME2p1,       Armv9_4,  ID_AA64SMFR0_EL1,  RegisterMatch(56..=59, AtLeast(0b0010)), Fill,                                      Sme,       "Scalable Matrix Extension version 2.1"),
+    (FEAT_SME2p2,       Armv9_6,  ID_AA64SMFR0_EL1,  RegisterMatch(56..=59, AtLeast(0b0011)), Fill,                                      Sme,       "Scalable Matrix Extension version 2.2"),
+    (FEAT_SME_LUTv2,    Armv9_5,  ID_AA64SMFR0_EL1,  RegisterMatch(60..=60, AtLeast(0b1)),    Fill,                                      Sme,       "Lookup table instructions with 4-bit indices and 8-bit elements"),
+    (FEAT_SME_B16B16,   Armv9_4,  ID_AA64SMFR0_EL1,  RegisterMatch(43..=43, AtLeast(0b1)),    Fill,                                      Sme,       "Non-widening BFloat16 to BFloat16 arithmetic for SME2.1"),
+    (FEAT_SME_F16F16,   Armv9_4,  ID_AA64SMFR0_EL1,  RegisterMatch(42..=42, AtLeast(0b1)),    Fill,                                      Sme,       "Non-widening half-precision FP16 to FP16 arithmetic for SME2.1"),
+    (FEAT_SME_F8F16,    Armv9_5,  ID_AA64SMFR0_EL1,  RegisterMatch(41..=41, AtLeast(0b1)),    Fill,                                      Sme,       "SME2 ZA-targeting FP8 multiply-accumulate, dot product, and outer product to half-precision instructions"),
+    (FEAT_SME_F8F32,    Armv9_5,  ID_AA64SMFR0_EL1,  RegisterMatch(40..=40, AtLeast(0b1)),    Fill,                                      Sme,       "SME2 ZA-targeting FP8 multiply-accumulate, dot product, and outer product to single-precision instructions"),
+    (FEAT_SSVE_BitPerm, Armv9_6,  ID_AA64SMFR0_EL1,  RegisterMatch(25..=25, AtLeast(0b1)),    Fill,                                      Sme,       "Streaming Scalable Vector Bit Permutes instructions"),
+    (FEAT_SSVE_AES,     Armv9_6,  ID_AA64SMFR0_EL1,  RegisterMatch(24..=24, AtLeast(0b1)),    Fill,                                      Sme,       "Streaming SVE Mode Advanced Encryption Standard and 128-bit polynomial multiply long instructions"),
+    (FEAT_SSVE_FEXPA,   Armv9_6,  ID_AA64SMFR0_EL1,  RegisterMatch(23..=23, AtLeast(0b1)),    Fill,                                      Sme,       "Streaming SVE Mode FEXPA instruction"),
+    (FEAT_SME_MOP4,     Armv9_6,  ID_AA64SMFR0_EL1,  RegisterMatch( 0..= 0, AtLeast(0b1)),    Fill,                                      Sme,       "Quarter-tile outer product instructions"),
+    (FEAT_SSVE_FP8FMA,  Armv9_5,  ID_AA64SMFR0_EL1,  RegisterMatch(30..=30, AtLeast(0b1)),    Fill,                                      Sme,       "SVE2 FP8 multiply-accumulate to half-precision and single-precision instructions in Streaming SVE mode"),
+    (FEAT_SSVE_FP8DOT4, Armv9_5,  ID_AA64SMFR0_EL1,  RegisterMatch(29..=29, AtLeast(0b1)),    Fill,                                      Sme,       "SVE2 FP8 4-way dot product to single-precision instructions in Streaming SVE mode"),
+    (FEAT_SSVE_FP8DOT2, Armv9_5,  ID_AA64SMFR0_EL1,  RegisterMatch(28..=28, AtLeast(0b1)),    Fill,                                      Sme,       "SVE FP8 2-way dot product to half-precision instructions in Streaming SVE mode"),
 
 );
 
@@ -315,24 +577,610 @@ declare_features!(
 
 // FIXME######: missing FEAT_CP15SDISABLE2
 // FIXME: missing FEAT_PCSRv8
-// FIXME: missing FEAT_HPDS
 // FIXME: missing FEAT_ASMv8p2
-// FIXME: missing FEAT_FPACCOMBINE
-// FIXME: missing FEAT_PACQARMA3
 // FIXME: missing FEAT_CONSTPACFIELD
 // FIXME: missing FEAT_GTG
 // FIXME: missing FEAT_LPA2
 // FIXME: missing FEAT_PMUv3_TH
 // FIXME: missing FEAT_RME
-// FIXME: missing FEAT_RAS
-// FIXME: missing FEAT_SPE
 
 #[cfg(test)]
 mod tests {
-    use strum::IntoEnumIterator;
+    use strum::{EnumCount, IntoEnumIterator};
 
     use super::*;
 
+    #[test]
+    fn test_snapshot_lse() {
+        let mut snapshot = RegisterSnapshot::new();
+        snapshot.set(Register::ID_AA64ISAR0_EL1, 0b0010 << 20);
+
+        let features = get_features_from_snapshot(&snapshot);
+
+        assert!(features.contains(&Feature::FEAT_LSE));
+        assert!(!features.contains(&Feature::FEAT_SVE));
+    }
+
+    #[test]
+    fn test_snapshot_unknown_registers() {
+        let features = get_features_from_snapshot(&RegisterSnapshot::new());
+
+        assert!(features.is_empty());
+
+        // FEAT_DoubleLock is reported for 0b0000, but only if ID_AA64DFR0_EL1 is known
+        let mut snapshot = RegisterSnapshot::new();
+        snapshot.set(Register::ID_AA64DFR0_EL1, 0);
+
+        let features = get_features_from_snapshot(&snapshot);
+        assert!(features.contains(&Feature::FEAT_DoubleLock));
+
+        snapshot.set_unknown(Register::ID_AA64DFR0_EL1);
+        assert!(get_features_from_snapshot(&snapshot).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_all_registers() {
+        let mut snapshot = RegisterSnapshot::new();
+        for register in Register::iter() {
+            snapshot.set(register, 0);
+        }
+
+        assert_eq!(snapshot.get(Register::NoRegister), None);
+        assert_eq!(snapshot.iter().count(), Register::COUNT - 1);
+
+        // must not panic on features without a register
+        get_features_from_snapshot(&snapshot);
+    }
+
+    #[test]
+    fn test_description() {
+        // AARCH64_FEATURES is not sorted by the feature
+        for desc in AARCH64_FEATURES {
+            assert_eq!(
+                get_feature_description(&desc.feature).as_deref(),
+                Some(desc.description)
+            );
+        }
+
+        assert_eq!(
+            Feature::FEAT_SME_TMOP.description(),
+            Some("Structured sparsity outer product instructions")
+        );
+        assert_eq!(Feature::FEAT_GTG.description(), None);
+    }
+
+    #[test]
+    fn test_metadata() {
+        assert_eq!(Feature::FEAT_LSE2.arch_version(), Some(ArmVersion::Armv8_4));
+        assert_eq!(Feature::FEAT_PAuth.category(), Some(Category::Security));
+        assert_eq!(Feature::FEAT_SVE2.category(), Some(Category::Sve));
+        assert_eq!(Feature::FEAT_GTG.arch_version(), None);
+        assert_eq!(Feature::FEAT_GTG.category(), None);
+
+        let armv8_4 = features_introduced_in(ArmVersion::Armv8_4);
+        assert!(armv8_4.contains(&Feature::FEAT_LSE2));
+        assert!(!armv8_4.contains(&Feature::FEAT_LSE));
+        assert!(armv8_4.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_lowercase_names() {
+        let mut names = HashSet::new();
+
+        for feature in Feature::iter() {
+            let name = feature.lowercase_name();
+
+            assert!(
+                name.chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'),
+                "{name}"
+            );
+            // FP8 is a duplicate of FEAT_FP8
+            if feature != Feature::FP8 {
+                assert!(names.insert(name), "{feature:?}");
+            }
+        }
+
+        assert_eq!(Feature::FEAT_PMUv3p9.lowercase_name(), "pmuv3p9");
+        assert_eq!(Feature::FP8.lowercase_name(), "fp8");
+    }
+
+    #[test]
+    fn test_categories_partition_features() {
+        let mut features: Vec<Feature> = Category::iter().flat_map(features_in_category).collect();
+        features.sort();
+
+        let mut expected: Vec<Feature> = AARCH64_FEATURES.iter().map(|desc| desc.feature).collect();
+        expected.sort();
+
+        assert_eq!(features, expected);
+        assert!(Category::iter().all(|category| !features_in_category(category).is_empty()));
+
+        let versions: usize = ArmVersion::iter()
+            .map(|version| features_introduced_in(version).len())
+            .sum();
+        assert_eq!(versions, AARCH64_FEATURES.len());
+    }
+
+    /// the start bits of the fields that `description` reads
+    fn field_starts(description: &FeatureDescription) -> Vec<u64> {
+        [&description.matcher, &description.matcher2]
+            .into_iter()
+            .filter_map(|matcher| match matcher {
+                RegisterMatches::RegisterMatch(range, _) => Some(*range.start()),
+                RegisterMatches::Fill => None,
+            })
+            .collect()
+    }
+
+    /// the features of the field at `start` of `register` for `value`
+    fn field_features(register: Register, start: u64, value: u64) -> HashSet<Feature> {
+        let mut snapshot = RegisterSnapshot::new();
+        snapshot.set(register, value << start);
+
+        let field: HashSet<Feature> = AARCH64_FEATURES
+            .iter()
+            .filter(|desc| desc.register == register && field_starts(desc).contains(&start))
+            .map(|desc| desc.feature)
+            .collect();
+
+        get_features_from_snapshot(&snapshot)
+            .intersection(&field)
+            .copied()
+            .collect()
+    }
+
+    /// walk all 16 values of a 4-bit field
+    fn walk_field(register: Register, start: u64, expected: &[(Feature, RangeInclusive<u64>)]) {
+        for value in 0..=0b1111 {
+            let expected: HashSet<Feature> = expected
+                .iter()
+                .filter(|(_, values)| values.contains(&value))
+                .map(|(feature, _)| *feature)
+                .collect();
+
+            assert_eq!(
+                field_features(register, start, value),
+                expected,
+                "{register:?}[{}:{start}] = {value:#06b}",
+                start + 3
+            );
+        }
+    }
+
+    #[test]
+    fn test_sign_extend() {
+        assert_eq!(sign_extend(0b0000, 4), 0);
+        assert_eq!(sign_extend(0b0111, 4), 7);
+        assert_eq!(sign_extend(0b1000, 4), -8);
+        assert_eq!(sign_extend(0b1111, 4), -1);
+        assert_eq!(sign_extend(0b1, 1), -1);
+    }
+
+    #[test]
+    fn test_field_isar0_atomics() {
+        walk_field(
+            Register::ID_AA64ISAR0_EL1,
+            20,
+            &[
+                (Feature::FEAT_LSE, 0b0010..=0b1111),
+                (Feature::FEAT_LSE128, 0b0011..=0b1111),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_field_isar0_aes() {
+        walk_field(
+            Register::ID_AA64ISAR0_EL1,
+            4,
+            &[
+                (Feature::FEAT_AES, 0b0001..=0b1111),
+                (Feature::FEAT_PMULL, 0b0010..=0b1111),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_field_isar1_apa() {
+        let expected = [
+            (Feature::FEAT_PAuth, 0b0001..=0b1111),
+            (Feature::FEAT_PAuth2, 0b0011..=0b1111),
+            (Feature::FEAT_FPAC, 0b0100..=0b1111),
+            (Feature::FEAT_FPACCOMBINE, 0b0101..=0b1111),
+        ];
+
+        // APA and API
+        walk_field(Register::ID_AA64ISAR1_EL1, 4, &expected);
+        walk_field(Register::ID_AA64ISAR1_EL1, 8, &expected);
+    }
+
+    #[test]
+    fn test_field_isar1_lrcpc() {
+        walk_field(
+            Register::ID_AA64ISAR1_EL1,
+            20,
+            &[
+                (Feature::FEAT_LRCPC, 0b0001..=0b1111),
+                (Feature::FEAT_LRCPC2, 0b0010..=0b1111),
+                (Feature::FEAT_LRCPC3, 0b0011..=0b1111),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_field_pfr0_fp() {
+        // signed: 0b1111 is not implemented
+        walk_field(
+            Register::ID_AA64PFR0_EL1,
+            16,
+            &[(Feature::FEAT_FP16, 0b0001..=0b0111)],
+        );
+    }
+
+    #[test]
+    fn test_field_pfr0_advsimd() {
+        // signed: 0b1111 is not implemented
+        walk_field(
+            Register::ID_AA64PFR0_EL1,
+            20,
+            &[(Feature::FEAT_AdvSIMD, 0b0000..=0b0111)],
+        );
+    }
+
+    #[test]
+    fn test_field_pfr0_ras() {
+        walk_field(
+            Register::ID_AA64PFR0_EL1,
+            28,
+            &[
+                (Feature::FEAT_RAS, 0b0001..=0b1111),
+                (Feature::FEAT_RASv1p1, 0b0010..=0b1111),
+                (Feature::FEAT_DoubleFault, 0b0010..=0b1111),
+                (Feature::FEAT_RASv2, 0b0011..=0b1111),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_field_pfr1_mte() {
+        walk_field(
+            Register::ID_AA64PFR1_EL1,
+            8,
+            &[
+                (Feature::FEAT_MTE, 0b0001..=0b1111),
+                (Feature::FEAT_MTE2, 0b0010..=0b1111),
+                (Feature::FEAT_MTE3, 0b0011..=0b1111),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_field_dfr0_pmuver() {
+        // 0b1111 is an IMPLEMENTATION DEFINED PMU
+        walk_field(
+            Register::ID_AA64DFR0_EL1,
+            8,
+            &[
+                (Feature::FEAT_PMUv3p1, 0b0100..=0b1110),
+                (Feature::FEAT_PMUv3p4, 0b0101..=0b1110),
+                (Feature::FEAT_PMUv3p5, 0b0110..=0b1110),
+                (Feature::FEAT_PMUv3p7, 0b0111..=0b1110),
+                (Feature::FEAT_PMUv3p8, 0b1000..=0b1110),
+                (Feature::FEAT_PMUv3p9, 0b1001..=0b1110),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_field_dfr0_double_lock() {
+        // signed: 0b0000 is implemented, 0b1111 is not implemented
+        walk_field(
+            Register::ID_AA64DFR0_EL1,
+            36,
+            &[(Feature::FEAT_DoubleLock, 0b0000..=0b0111)],
+        );
+    }
+
+    #[test]
+    fn test_field_dfr0_mtpmu() {
+        // signed: 0b1111 is not implemented
+        walk_field(
+            Register::ID_AA64DFR0_EL1,
+            48,
+            &[(Feature::FEAT_MTPMU, 0b0001..=0b0111)],
+        );
+    }
+
+    #[test]
+    fn test_field_zfr0_svever() {
+        walk_field(
+            Register::ID_AA64ZFR0_EL1,
+            0,
+            &[
+                (Feature::FEAT_SVE2, 0b0001..=0b1111),
+                (Feature::FEAT_SVE2p1, 0b0010..=0b1111),
+                (Feature::FEAT_SVE2p2, 0b0011..=0b1111),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_field_zfr0_b16b16() {
+        walk_field(
+            Register::ID_AA64ZFR0_EL1,
+            24,
+            &[
+                (Feature::FEAT_B16B16, 0b0001..=0b1111),
+                (Feature::FEAT_SVE_BFSCALE, 0b0010..=0b1111),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_field_zfr0_aes() {
+        walk_field(
+            Register::ID_AA64ZFR0_EL1,
+            4,
+            &[
+                (Feature::FEAT_SVE_AES, 0b0001..=0b1111),
+                (Feature::FEAT_SVE_PMULL128, 0b0010..=0b1111),
+                (Feature::FEAT_SVE_AES2, 0b0011..=0b1111),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_field_mmfr4_e2h0() {
+        // signed: 0b0000 is implemented, 0b1110 and 0b1111 are not
+        walk_field(
+            Register::ID_AA64MMFR4_EL1,
+            24,
+            &[(Feature::FEAT_E2HO, 0b0000..=0b0111)],
+        );
+    }
+
+    #[test]
+    fn test_field_pfr2_fpmr() {
+        walk_field(
+            Register::ID_AA64PFR2_EL1,
+            32,
+            &[(Feature::FEAT_FPMR, 0b0001..=0b1111)],
+        );
+    }
+
+    /// the single-bit fields of ID_AA64SMFR0_EL1
+    const SMFR0_BITS: &[(u64, Feature)] = &[
+        (63, Feature::FEAT_SME_FA64),
+        (60, Feature::FEAT_SME_LUTv2),
+        (48, Feature::FEAT_SME_F64F64),
+        (43, Feature::FEAT_SME_B16B16),
+        (42, Feature::FEAT_SME_F16F16),
+        (41, Feature::FEAT_SME_F8F16),
+        (40, Feature::FEAT_SME_F8F32),
+        (30, Feature::FEAT_SSVE_FP8FMA),
+        (29, Feature::FEAT_SSVE_FP8DOT4),
+        (28, Feature::FEAT_SSVE_FP8DOT2),
+        (25, Feature::FEAT_SSVE_BitPerm),
+        (24, Feature::FEAT_SSVE_AES),
+        (23, Feature::FEAT_SSVE_FEXPA),
+        (16, Feature::FEAT_SME_TMOP),
+        (0, Feature::FEAT_SME_MOP4),
+    ];
+
+    /// the features of all rows that read ID_AA64SMFR0_EL1
+    fn smfr0_features(snapshot: &RegisterSnapshot) -> HashSet<Feature> {
+        let rows: HashSet<Feature> = AARCH64_FEATURES
+            .iter()
+            .filter(|desc| desc.register == Register::ID_AA64SMFR0_EL1)
+            .map(|desc| desc.feature)
+            .collect();
+
+        get_features_from_snapshot(snapshot)
+            .intersection(&rows)
+            .copied()
+            .collect()
+    }
+
+    #[test]
+    fn test_field_smfr0_smever() {
+        walk_field(
+            Register::ID_AA64SMFR0_EL1,
+            56,
+            &[
+                (Feature::FEAT_SME2p1, 0b0010..=0b1111),
+                (Feature::FEAT_SME2p2, 0b0011..=0b1111),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_field_smfr0_i16i64() {
+        walk_field(
+            Register::ID_AA64SMFR0_EL1,
+            52,
+            &[(Feature::FEAT_SME_I16I64, 0b1111..=0b1111)],
+        );
+    }
+
+    #[test]
+    fn test_field_smfr0_bits() {
+        for (bit, feature) in SMFR0_BITS {
+            assert_eq!(
+                field_features(Register::ID_AA64SMFR0_EL1, *bit, 0b0),
+                HashSet::new(),
+                "ID_AA64SMFR0_EL1[{bit}] = 0"
+            );
+            assert_eq!(
+                field_features(Register::ID_AA64SMFR0_EL1, *bit, 0b1),
+                HashSet::from([*feature]),
+                "ID_AA64SMFR0_EL1[{bit}] = 1"
+            );
+        }
+    }
+
+    #[test]
+    fn test_smfr0_rows_are_tested() {
+        let fields = [
+            Feature::FEAT_SME_I16I64,
+            Feature::FEAT_SME2p1,
+            Feature::FEAT_SME2p2,
+        ];
+
+        for desc in AARCH64_FEATURES {
+            if desc.register != Register::ID_AA64SMFR0_EL1 {
+                continue;
+            }
+
+            assert!(
+                fields.contains(&desc.feature)
+                    || SMFR0_BITS
+                        .iter()
+                        .any(|(_, feature)| *feature == desc.feature),
+                "{:?}",
+                desc.feature
+            );
+        }
+    }
+
+    #[test]
+    fn test_snapshot_smfr0() {
+        let mut snapshot = RegisterSnapshot::new();
+
+        // SME without any optional feature
+        snapshot.set(
+            Register::ID_AA64SMFR0_EL1,
+            0b0101 << 44 | 0b1111 << 36 | 0b1111 << 32,
+        );
+        assert_eq!(smfr0_features(&snapshot), HashSet::new());
+
+        // SME2.1 with FA64, F64F64, and I16I64
+        snapshot.set(
+            Register::ID_AA64SMFR0_EL1,
+            1 << 63 | 0b0010 << 56 | 0b1111 << 52 | 1 << 48 | 1 << 42 | 0b0101 << 44,
+        );
+        assert_eq!(
+            smfr0_features(&snapshot),
+            HashSet::from([
+                Feature::FEAT_SME_FA64,
+                Feature::FEAT_SME2p1,
+                Feature::FEAT_SME_I16I64,
+                Feature::FEAT_SME_F64F64,
+                Feature::FEAT_SME_F16F16,
+            ])
+        );
+
+        // SME2.2 with the FP8 and the Armv9.6 extensions
+        snapshot.set(
+            Register::ID_AA64SMFR0_EL1,
+            1 << 60 | 0b0011 << 56 | 0b11 << 40 | 0b111 << 28 | 0b11 << 24 | 1 << 16 | 1,
+        );
+        assert_eq!(
+            smfr0_features(&snapshot),
+            HashSet::from([
+                Feature::FEAT_SME_LUTv2,
+                Feature::FEAT_SME2p1,
+                Feature::FEAT_SME2p2,
+                Feature::FEAT_SME_F8F16,
+                Feature::FEAT_SME_F8F32,
+                Feature::FEAT_SSVE_FP8FMA,
+                Feature::FEAT_SSVE_FP8DOT4,
+                Feature::FEAT_SSVE_FP8DOT2,
+                Feature::FEAT_SSVE_BitPerm,
+                Feature::FEAT_SSVE_AES,
+                Feature::FEAT_SME_TMOP,
+                Feature::FEAT_SME_MOP4,
+            ])
+        );
+
+        // every row
+        snapshot.set(Register::ID_AA64SMFR0_EL1, u64::MAX);
+        assert_eq!(smfr0_features(&snapshot).len(), SMFR0_BITS.len() + 3);
+    }
+
+    #[test]
+    fn test_snapshot_new_registers() {
+        let mut snapshot = RegisterSnapshot::new();
+        snapshot.set(Register::ID_AA64MMFR3_EL1, 0b0001 << 8 | 0b0001);
+        snapshot.set(Register::ID_AA64PFR2_EL1, 0b0001);
+        snapshot.set(Register::ID_AA64DFR1_EL1, 0b0001 << 44);
+        snapshot.set(Register::ID_AA64FPFR0_EL1, 0b1011 << 28);
+
+        let features = get_features_from_snapshot(&snapshot);
+
+        for feature in [
+            Feature::FEAT_TCR2,
+            Feature::FEAT_S1PIE,
+            Feature::FEAT_MTE_PERM,
+            Feature::FEAT_ITE,
+            Feature::FEAT_FP8,
+            Feature::FP8,
+            Feature::FEAT_FP8DOT4,
+            Feature::FEAT_FP8DOT2,
+        ] {
+            assert!(features.contains(&feature), "{feature:?}");
+        }
+        for feature in [
+            Feature::FEAT_SCTLR2,
+            Feature::FEAT_MTE_STORE_ONLY,
+            Feature::FEAT_FP8FMA,
+            Feature::FEAT_E2HO,
+        ] {
+            assert!(!features.contains(&feature), "{feature:?}");
+        }
+    }
+
+    #[test]
+    fn test_unsigned_fields_are_monotonic() {
+        // fields that do not follow the ID scheme
+        let exact = [
+            Feature::FEAT_VPIPT,
+            Feature::FEAT_PCSRv8p2,
+            Feature::FEAT_BBM,
+        ];
+
+        let mut fields = HashSet::new();
+        let mut exact_fields = HashSet::new();
+
+        for desc in AARCH64_FEATURES {
+            for matcher in [&desc.matcher, &desc.matcher2] {
+                let RegisterMatches::RegisterMatch(range, value) = matcher else {
+                    continue;
+                };
+
+                let exact_matcher = matches!(
+                    value,
+                    ValueMatcher::Value(_) | ValueMatcher::Or(..) | ValueMatcher::Any(..)
+                );
+                assert_eq!(
+                    exact_matcher,
+                    exact.contains(&desc.feature),
+                    "{:?}",
+                    desc.feature
+                );
+
+                if exact_matcher {
+                    exact_fields.insert((desc.register, *range.start()));
+                } else if range.end() - range.start() == 3 {
+                    fields.insert((desc.register, *range.start()));
+                }
+            }
+        }
+
+        for (register, start) in fields.difference(&exact_fields) {
+            // a higher value is a superset, up to the first special value
+            let mut previous = HashSet::new();
+            for value in 0..=0b0111 {
+                let features = field_features(*register, *start, value);
+                assert!(
+                    previous.is_subset(&features),
+                    "{register:?}[{start}] = {value:#06b}"
+                );
+                previous = features;
+            }
+        }
+    }
+
     //    #[test]
     //    fn check_number_of_variant() {
     //        // not exposed to userspace!
@@ -0,0 +1,48 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A temporary directory for tests of sysfs readers, e.g., a fake
+/// `/sys/devices/system/cpu`. It is removed on drop.
+pub(crate) struct Fixture {
+    root: PathBuf,
+}
+
+impl Fixture {
+    /// Create an empty directory. The name only needs to be unique
+    /// within a test module.
+    pub(crate) fn new(name: &str) -> Self {
+        // tests run in parallel threads of the same process
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        let root = std::env::temp_dir().join(format!(
+            "aarch64_features_{}_{}_{}",
+            name,
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        Fixture { root }
+    }
+
+    /// The root of the directory
+    pub(crate) fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Create the directory `path` relative to the root and write the
+    /// files into it
+    pub(crate) fn write(&self, path: impl AsRef<Path>, files: &[(&str, &str)]) {
+        let dir = self.root.join(path);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, text) in files {
+            std::fs::write(dir.join(name), text).unwrap();
+        }
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
@@ -1,9 +1,31 @@
-use crate::Feature;
+use crate::sve_sme::VectorExtension;
+use crate::{Feature, SetVectorLengthError, VectorLength, VectorLengths};
+use std::collections::HashSet;
 
 pub(crate) fn is_exposed_to_userspace(_feat: Feature) -> bool {
     false
 }
 
+pub(crate) fn fallback_features() -> Option<HashSet<Feature>> {
+    None
+}
+
+pub(crate) fn current_vector_lengths() -> VectorLengths {
+    VectorLengths::default()
+}
+
+pub(crate) fn supported_vector_lengths(_extension: VectorExtension) -> Vec<u64> {
+    Vec::new()
+}
+
+pub(crate) fn set_vector_length(
+    _extension: VectorExtension,
+    _bytes: u64,
+    _inherit: bool,
+) -> Result<VectorLength, SetVectorLengthError> {
+    Err(SetVectorLengthError::Unsupported)
+}
+
 #[cfg(all(target_arch = "aarch64", target_os = "macos"))]
 pub(crate) fn check_availability() -> bool {
     true
@@ -0,0 +1,354 @@
+//! Decoder for the hardware capabilities that the Linux kernel passes
+//! to userspace in the auxiliary vector, i.e., `AT_HWCAP`,
+//! `AT_HWCAP2`, and `AT_HWCAP3`.
+//!
+//! The capabilities are available even if the kernel does not emulate
+//! `MRS` on the ID registers (`HWCAP_CPUID`), e.g., on old kernels, in
+//! seccomp sandboxes, or in gVisor. The decoding is a pure function
+//! over the words. It works on any host with recorded values.
+//!
+//! ```rust
+//! use aarch64_features::hwcap::HwCaps;
+//! use aarch64_features::Feature;
+//!
+//! // HWCAP_ATOMICS, HWCAP2_SVE2
+//! let caps = HwCaps::new(1 << 8, 1 << 1, 0);
+//! let features = caps.features();
+//!
+//! assert!(features.contains(&Feature::FEAT_LSE));
+//! assert!(features.contains(&Feature::FEAT_SVE2));
+//! ```
+//!
+//! The list of capabilities is from the [ELF hwcaps](https://github.com/torvalds/linux/blob/master/Documentation/arch/arm64/elf_hwcaps.rst) of the Linux kernel.
+
+use crate::Feature;
+use std::collections::HashSet;
+
+/// The auxiliary vector entries with hardware capabilities
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct HwCaps {
+    hwcap: u64,
+    hwcap2: u64,
+    hwcap3: u64,
+}
+
+impl HwCaps {
+    /// Create from the values of `AT_HWCAP`, `AT_HWCAP2`, and `AT_HWCAP3`
+    pub fn new(hwcap: u64, hwcap2: u64, hwcap3: u64) -> Self {
+        HwCaps {
+            hwcap,
+            hwcap2,
+            hwcap3,
+        }
+    }
+
+    /// Read the capabilities of the current process. Everything is
+    /// zero if the auxiliary vector is not available, e.g., on macOS
+    /// or on x86.
+    pub fn read() -> Self {
+        #[cfg(all(target_arch = "aarch64", target_os = "linux"))]
+        {
+            /// not yet in libc
+            const AT_HWCAP3: libc::c_ulong = 29;
+
+            // SAFETY: getauxval has no preconditions and returns 0 for unknown entries
+            let (hwcap, hwcap2, hwcap3) = unsafe {
+                (
+                    libc::getauxval(libc::AT_HWCAP),
+                    libc::getauxval(libc::AT_HWCAP2),
+                    libc::getauxval(AT_HWCAP3),
+                )
+            };
+
+            HwCaps::new(hwcap, hwcap2, hwcap3)
+        }
+        #[cfg(not(all(target_arch = "aarch64", target_os = "linux")))]
+        {
+            HwCaps::default()
+        }
+    }
+
+    /// The value of `AT_HWCAP`
+    pub fn hwcap(&self) -> u64 {
+        self.hwcap
+    }
+
+    /// The value of `AT_HWCAP2`
+    pub fn hwcap2(&self) -> u64 {
+        self.hwcap2
+    }
+
+    /// The value of `AT_HWCAP3`
+    pub fn hwcap3(&self) -> u64 {
+        self.hwcap3
+    }
+
+    /// Whether the kernel emulates `MRS` on the ID registers
+    pub fn has_cpuid(&self) -> bool {
+        self.is_set(HwCapWord::Hwcap, HWCAP_CPUID_BIT)
+    }
+
+    /// The names of all capabilities that are set, in the spelling of
+    /// the `Features` line of `/proc/cpuinfo`
+    pub fn names(&self) -> Vec<&'static str> {
+        HWCAPS
+            .iter()
+            .filter(|cap| self.is_set(cap.word, cap.bit))
+            .map(|cap| cap.name)
+            .collect()
+    }
+
+    /// Decode all capabilities that are set into features
+    pub fn features(&self) -> HashSet<Feature> {
+        HWCAPS
+            .iter()
+            .filter(|cap| self.is_set(cap.word, cap.bit))
+            .filter_map(|cap| cap.feature)
+            .collect()
+    }
+
+    fn is_set(&self, word: HwCapWord, bit: u32) -> bool {
+        let value = match word {
+            HwCapWord::Hwcap => self.hwcap,
+            HwCapWord::Hwcap2 => self.hwcap2,
+            HwCapWord::Hwcap3 => self.hwcap3,
+        };
+
+        (value >> bit) & 0b1 == 0b1
+    }
+}
+
+/// Look up the feature of a capability by its `/proc/cpuinfo` name.
+/// Returns `None` for unknown names and `Some(None)` for capabilities
+/// without a corresponding [`Feature`], e.g., `crc32` or `cpuid`.
+pub(crate) fn feature_by_name(name: &str) -> Option<Option<Feature>> {
+    HWCAPS
+        .iter()
+        .find(|cap| cap.name == name)
+        .map(|cap| cap.feature)
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum HwCapWord {
+    Hwcap,
+    Hwcap2,
+    Hwcap3,
+}
+
+struct HwCapDescription {
+    word: HwCapWord,
+    bit: u32,
+    name: &'static str,
+    feature: Option<Feature>,
+}
+
+const HWCAP_CPUID_BIT: u32 = 11;
+
+macro_rules! declare_hwcaps {
+    ($(
+        ($word:ident, $bit:expr, $name:expr, $feature:expr),
+    )+) => {
+        /// The hardware capabilities of the Linux kernel
+        use crate::hwcap::HwCapWord::*;
+        use crate::Feature::*;
+        const HWCAPS: &[HwCapDescription] = &[
+            $(
+                HwCapDescription{
+                    word: $word,
+                    bit: $bit,
+                    name: $name,
+                    feature: $feature,
+                }
+            ),+
+        ];
+    }
+}
+
+#[rustfmt::skip]
+declare_hwcaps!(
+    // AT_HWCAP
+    (Hwcap,   0, "fp",            None),
+    (Hwcap,   1, "asimd",         Some(FEAT_AdvSIMD)),
+    (Hwcap,   2, "evtstrm",       None),
+    (Hwcap,   3, "aes",           Some(FEAT_AES)),
+    (Hwcap,   4, "pmull",         Some(FEAT_PMULL)),
+    (Hwcap,   5, "sha1",          Some(FEAT_SHA1)),
+    (Hwcap,   6, "sha2",          Some(FEAT_SHA256)),
+    (Hwcap,   7, "crc32",         None),
+    (Hwcap,   8, "atomics",       Some(FEAT_LSE)),
+    (Hwcap,   9, "fphp",          Some(FEAT_FP16)),
+    (Hwcap,  10, "asimdhp",       Some(FEAT_FP16)),
+    (Hwcap,  11, "cpuid",         None),
+    (Hwcap,  12, "asimdrdm",      Some(FEAT_RDM)),
+    (Hwcap,  13, "jscvt",         Some(FEAT_JSCVT)),
+    (Hwcap,  14, "fcma",          Some(FEAT_FCMA)),
+    (Hwcap,  15, "lrcpc",         Some(FEAT_LRCPC)),
+    (Hwcap,  16, "dcpop",         Some(FEAT_DPB)),
+    (Hwcap,  17, "sha3",          Some(FEAT_SHA3)),
+    (Hwcap,  18, "sm3",           Some(FEAT_SM3)),
+    (Hwcap,  19, "sm4",           Some(FEAT_SM4)),
+    (Hwcap,  20, "asimddp",       Some(FEAT_DotProd)),
+    (Hwcap,  21, "sha512",        Some(FEAT_SHA512)),
+    (Hwcap,  22, "sve",           Some(FEAT_SVE)),
+    (Hwcap,  23, "asimdfhm",      Some(FEAT_FHM)),
+    (Hwcap,  24, "dit",           Some(FEAT_DIT)),
+    (Hwcap,  25, "uscat",         Some(FEAT_LSE2)),
+    (Hwcap,  26, "ilrcpc",        Some(FEAT_LRCPC2)),
+    (Hwcap,  27, "flagm",         Some(FEAT_FlagM)),
+    (Hwcap,  28, "ssbs",          Some(FEAT_SSBS)),
+    (Hwcap,  29, "sb",            Some(FEAT_SB)),
+    (Hwcap,  30, "paca",          Some(FEAT_PAuth)),
+    (Hwcap,  31, "pacg",          Some(FEAT_PAuth)),
+    (Hwcap,  32, "gcs",           None),
+    (Hwcap,  33, "cmpbr",         Some(FEAT_CMPBR)),
+    (Hwcap,  34, "fprcvt",        Some(FEAT_FPRCVT)),
+    (Hwcap,  35, "f8mm8",         Some(FEAT_F8F32MM)),
+    (Hwcap,  36, "f8mm4",         Some(FEAT_F8F16MM)),
+    (Hwcap,  37, "svef16mm",      Some(FEAT_SVE_F16F32MM)),
+    (Hwcap,  38, "sveeltperm",    None),
+    (Hwcap,  39, "sveaes2",       Some(FEAT_SVE_AES2)),
+    (Hwcap,  40, "svebfscale",    Some(FEAT_SVE_BFSCALE)),
+    (Hwcap,  41, "sve2p2",        Some(FEAT_SVE2p2)),
+    (Hwcap,  42, "sme2p2",        Some(FEAT_SME2p2)),
+    (Hwcap,  43, "smesbitperm",   Some(FEAT_SSVE_BitPerm)),
+    (Hwcap,  44, "smeaes",        Some(FEAT_SSVE_AES)),
+    (Hwcap,  45, "smesfexpa",     None),
+    (Hwcap,  46, "smestmop",      Some(FEAT_SME_TMOP)),
+    (Hwcap,  47, "smesmop4",      Some(FEAT_SME_MOP4)),
+    // AT_HWCAP2
+    (Hwcap2,  0, "dcpodp",        Some(FEAT_DPB2)),
+    (Hwcap2,  1, "sve2",          Some(FEAT_SVE2)),
+    (Hwcap2,  2, "sveaes",        Some(FEAT_SVE_AES)),
+    (Hwcap2,  3, "svepmull",      Some(FEAT_SVE_PMULL128)),
+    (Hwcap2,  4, "svebitperm",    Some(FEAT_SVE_BitPerm)),
+    (Hwcap2,  5, "svesha3",       Some(FEAT_SVE_SHA3)),
+    (Hwcap2,  6, "svesm4",        Some(FEAT_SVE_SM4)),
+    (Hwcap2,  7, "flagm2",        Some(FEAT_FlagM2)),
+    (Hwcap2,  8, "frint",         Some(FEAT_FRINTTS)),
+    (Hwcap2,  9, "svei8mm",       Some(FEAT_I8MM)),
+    (Hwcap2, 10, "svef32mm",      Some(FEAT_F32MM)),
+    (Hwcap2, 11, "svef64mm",      Some(FEAT_F64MM)),
+    (Hwcap2, 12, "svebf16",       Some(FEAT_BF16)),
+    (Hwcap2, 13, "i8mm",          Some(FEAT_I8MM)),
+    (Hwcap2, 14, "bf16",          Some(FEAT_BF16)),
+    (Hwcap2, 15, "dgh",           Some(FEAT_DGH)),
+    (Hwcap2, 16, "rng",           Some(FEAT_RNG)),
+    (Hwcap2, 17, "bti",           Some(FEAT_BTI)),
+    (Hwcap2, 18, "mte",           Some(FEAT_MTE2)),
+    (Hwcap2, 19, "ecv",           Some(FEAT_ECV)),
+    (Hwcap2, 20, "afp",           Some(FEAT_AFP)),
+    (Hwcap2, 21, "rpres",         Some(FEAT_RPRES)),
+    (Hwcap2, 22, "mte3",          Some(FEAT_MTE3)),
+    (Hwcap2, 23, "sme",           Some(FEAT_SME)),
+    (Hwcap2, 24, "smei16i64",     Some(FEAT_SME_I16I64)),
+    (Hwcap2, 25, "smef64f64",     Some(FEAT_SME_F64F64)),
+    (Hwcap2, 26, "smei8i32",      None),
+    (Hwcap2, 27, "smef16f32",     None),
+    (Hwcap2, 28, "smeb16f32",     None),
+    (Hwcap2, 29, "smef32f32",     None),
+    (Hwcap2, 30, "smefa64",       Some(FEAT_SME_FA64)),
+    (Hwcap2, 31, "wfxt",          Some(FEAT_WFxT)),
+    (Hwcap2, 32, "ebf16",         Some(FEAT_EBF16)),
+    (Hwcap2, 33, "sveebf16",      Some(FEAT_EBF16)),
+    (Hwcap2, 34, "cssc",          Some(FEAT_CSSC)),
+    (Hwcap2, 35, "rprfm",         Some(FEAT_RPRFM)),
+    (Hwcap2, 36, "sve2p1",        Some(FEAT_SVE2p1)),
+    (Hwcap2, 37, "sme2",          Some(FEAT_SME2)),
+    (Hwcap2, 38, "sme2p1",        Some(FEAT_SME2p1)),
+    (Hwcap2, 39, "smei16i32",     None),
+    (Hwcap2, 40, "smebi32i32",    None),
+    (Hwcap2, 41, "smeb16b16",     Some(FEAT_SME_B16B16)),
+    (Hwcap2, 42, "smef16f16",     Some(FEAT_SME_F16F16)),
+    (Hwcap2, 43, "mops",          Some(FEAT_MOPS)),
+    (Hwcap2, 44, "hbc",           Some(FEAT_HBC)),
+    (Hwcap2, 45, "sveb16b16",     Some(FEAT_B16B16)),
+    (Hwcap2, 46, "lrcpc3",        Some(FEAT_LRCPC3)),
+    (Hwcap2, 47, "lse128",        Some(FEAT_LSE128)),
+    (Hwcap2, 48, "fpmr",          Some(FEAT_FPMR)),
+    (Hwcap2, 49, "lut",           Some(FEAT_LUT)),
+    (Hwcap2, 50, "faminmax",      Some(FEAT_FAMINMAX)),
+    (Hwcap2, 51, "f8cvt",         Some(FEAT_FP8)),
+    (Hwcap2, 52, "f8fma",         Some(FEAT_FP8FMA)),
+    (Hwcap2, 53, "f8dp4",         Some(FEAT_FP8DOT4)),
+    (Hwcap2, 54, "f8dp2",         Some(FEAT_FP8DOT2)),
+    (Hwcap2, 55, "f8e4m3",        None),
+    (Hwcap2, 56, "f8e5m2",        None),
+    (Hwcap2, 57, "smelutv2",      Some(FEAT_SME_LUTv2)),
+    (Hwcap2, 58, "smef8f16",      Some(FEAT_SME_F8F16)),
+    (Hwcap2, 59, "smef8f32",      Some(FEAT_SME_F8F32)),
+    (Hwcap2, 60, "smesf8fma",     Some(FEAT_SSVE_FP8FMA)),
+    (Hwcap2, 61, "smesf8dp4",     Some(FEAT_SSVE_FP8DOT4)),
+    (Hwcap2, 62, "smesf8dp2",     Some(FEAT_SSVE_FP8DOT2)),
+    (Hwcap2, 63, "poe",           Some(FEAT_S1POE)),
+    // AT_HWCAP3
+    (Hwcap3,  0, "mtefar",        Some(FEAT_MTE_TAGGED_FAR)),
+    (Hwcap3,  1, "mtestoreonly",  Some(FEAT_MTE_STORE_ONLY)),
+    (Hwcap3,  2, "lsfe",          Some(FEAT_LSFE)),
+    (Hwcap3,  3, "ls64",          Some(FEAT_LS64)),
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// AWS Graviton 2 (Neoverse N1)
+    const GRAVITON2_HWCAP: u64 = 0x1011_9fff;
+
+    #[test]
+    fn test_graviton2() {
+        let caps = HwCaps::new(GRAVITON2_HWCAP, 0, 0);
+        let features = caps.features();
+
+        assert!(caps.has_cpuid());
+        assert!(features.contains(&Feature::FEAT_AdvSIMD));
+        assert!(features.contains(&Feature::FEAT_LSE));
+        assert!(features.contains(&Feature::FEAT_LRCPC));
+        assert!(features.contains(&Feature::FEAT_DotProd));
+        assert!(features.contains(&Feature::FEAT_SSBS));
+        assert!(!features.contains(&Feature::FEAT_SVE));
+        assert!(!features.contains(&Feature::FEAT_JSCVT));
+
+        assert_eq!(
+            caps.names(),
+            vec![
+                "fp", "asimd", "evtstrm", "aes", "pmull", "sha1", "sha2", "crc32", "atomics",
+                "fphp", "asimdhp", "cpuid", "asimdrdm", "lrcpc", "dcpop", "asimddp", "ssbs"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hwcap2_and_hwcap3() {
+        let caps = HwCaps::new(0, (1 << 1) | (1 << 22) | (1 << 47), 1 << 2);
+        let features = caps.features();
+
+        assert!(!caps.has_cpuid());
+        assert!(features.contains(&Feature::FEAT_SVE2));
+        assert!(features.contains(&Feature::FEAT_MTE3));
+        assert!(features.contains(&Feature::FEAT_LSE128));
+        assert!(features.contains(&Feature::FEAT_LSFE));
+        assert_eq!(features.len(), 4);
+    }
+
+    #[test]
+    fn test_every_bit_is_decoded() {
+        for (word, bits) in [
+            (HwCapWord::Hwcap, 48),
+            (HwCapWord::Hwcap2, 64),
+            (HwCapWord::Hwcap3, 4),
+        ] {
+            for bit in 0..bits {
+                assert!(HWCAPS.iter().any(|cap| cap.word == word && cap.bit == bit));
+            }
+        }
+
+        let mut names: Vec<&str> = HWCAPS.iter().map(|cap| cap.name).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), HWCAPS.len());
+
+        assert_eq!(feature_by_name("sve2"), Some(Some(Feature::FEAT_SVE2)));
+        assert_eq!(feature_by_name("crc32"), Some(None));
+        assert_eq!(feature_by_name("unknown"), None);
+    }
+}
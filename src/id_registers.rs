@@ -0,0 +1,598 @@
+use crate::features_list::features_of_field;
+use crate::{Feature, Register};
+use std::fmt;
+
+/// A field of an ID register with its raw value, e.g., `Atomic`,
+/// bits `[23:20]` of `ID_AA64ISAR0_EL1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Field {
+    register: Register,
+    name: &'static str,
+    msb: u64,
+    lsb: u64,
+    value: u64,
+    description: &'static str,
+}
+
+impl Field {
+    /// The register of the field
+    pub fn register(&self) -> Register {
+        self.register
+    }
+
+    /// The name of the field from the ARMARM, e.g., `Atomic`
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The most significant bit of the field
+    pub fn msb(&self) -> u64 {
+        self.msb
+    }
+
+    /// The least significant bit of the field
+    pub fn lsb(&self) -> u64 {
+        self.lsb
+    }
+
+    /// The number of bits of the field
+    pub fn width(&self) -> u64 {
+        self.msb - self.lsb + 1
+    }
+
+    /// The raw value of the field
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// What the field describes
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+
+    /// The features that the value of the field implies. Some features,
+    /// e.g., [`Feature::FEAT_PAuth`], are implied by one of several
+    /// fields.
+    ///
+    /// ```rust
+    /// use aarch64_features::{Feature, IdAa64Isar0};
+    ///
+    /// let isar0 = IdAa64Isar0::decode(0b0011 << 20);
+    /// let atomics = isar0.fields().into_iter().find(|field| field.name() == "Atomic").unwrap();
+    ///
+    /// assert_eq!(atomics.features(), vec![Feature::FEAT_LSE, Feature::FEAT_LSE128]);
+    /// ```
+    pub fn features(&self) -> Vec<Feature> {
+        features_of_field(self.register, self.msb, self.lsb, self.value)
+    }
+}
+
+/// One line with the name, the bits, the raw value, and the implied
+/// features, e.g., `Atomic       [23:20] 0b0010  FEAT_LSE`
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bits = if self.msb == self.lsb {
+            format!("[{}]", self.msb)
+        } else {
+            format!("[{}:{}]", self.msb, self.lsb)
+        };
+        let value = format!("{:#0width$b}", self.value, width = field_width(self) + 2);
+
+        write!(
+            f,
+            "{:<12} {bits:<7} {value:<6}  {}",
+            self.name, self.description
+        )?;
+
+        let features = self.features();
+        if !features.is_empty() {
+            let names: Vec<String> = features.iter().map(Feature::to_string).collect();
+            write!(f, " ({})", names.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[allow(clippy::as_conversions)]
+fn field_width(field: &Field) -> usize {
+    field.width() as usize
+}
+
+fn extract(value: u64, msb: u64, lsb: u64) -> u64 {
+    let width = msb - lsb + 1;
+
+    (value >> lsb) & (u64::MAX >> (64 - width))
+}
+
+fn write_fields(f: &mut fmt::Formatter<'_>, register: Register, fields: &[Field]) -> fmt::Result {
+    writeln!(f, "{register:?}")?;
+    for field in fields {
+        writeln!(f, "  {field}")?;
+    }
+    Ok(())
+}
+
+macro_rules! declare_registers {
+    ($(
+        $(#[doc = $doc:expr])*
+        $name:ident, $register:ident {
+            $(($field:ident, $field_name:literal, $msb:literal, $lsb:literal, $description:literal),)+
+        }
+    )+) => {
+        $(
+            $(#[doc = $doc])*
+            #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+            pub struct $name {
+                $(
+                    #[doc = concat!($field_name, ", bits `[", $msb, ":", $lsb, "]`: ", $description)]
+                    pub $field: u64,
+                )+
+            }
+
+            impl $name {
+                /// The decoded register
+                pub const REGISTER: Register = Register::$register;
+
+                /// Decode the raw value of the register
+                pub fn decode(value: u64) -> Self {
+                    $name {
+                        $($field: extract(value, $msb, $lsb),)+
+                    }
+                }
+
+                /// The fields from the most to the least significant bits.
+                /// Reserved fields are skipped.
+                pub fn fields(&self) -> Vec<Field> {
+                    vec![
+                        $(
+                            Field {
+                                register: Self::REGISTER,
+                                name: $field_name,
+                                msb: $msb,
+                                lsb: $lsb,
+                                value: self.$field,
+                                description: $description,
+                            },
+                        )+
+                    ]
+                }
+            }
+
+            /// The name of the register and one line per field, see
+            /// [`Field`]
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write_fields(f, Self::REGISTER, &self.fields())
+                }
+            }
+        )+
+
+        impl Register {
+            /// Decode the raw value of the register into its fields.
+            /// Returns an empty list for registers without a decoder,
+            /// e.g., [`Register::CTR_EL0`].
+            ///
+            /// ```rust
+            /// use aarch64_features::Register;
+            ///
+            /// for field in Register::ID_AA64ISAR0_EL1.decode(0x0000100010211120) {
+            ///     println!("{field}");
+            /// }
+            /// ```
+            pub fn decode(self, value: u64) -> Vec<Field> {
+                match self {
+                    $(Register::$register => $name::decode(value).fields(),)+
+                    _ => Vec::new(),
+                }
+            }
+        }
+    }
+}
+
+#[rustfmt::skip]
+declare_registers!(
+    /// AArch64 Debug Feature Register 0
+    IdAa64Dfr0, ID_AA64DFR0_EL1 {
+        (hpmn0,        "HPMN0",        63, 60, "Zero PMU event counters for a guest"),
+        (exttrcbuff,   "ExtTrcBuff",   59, 56, "Trace Buffer External Mode"),
+        (brbe,         "BRBE",         55, 52, "Branch Record Buffer Extension"),
+        (mtpmu,        "MTPMU",        51, 48, "Multi-threaded PMU Extension"),
+        (tracebuffer,  "TraceBuffer",  47, 44, "Trace Buffer Extension"),
+        (tracefilt,    "TraceFilt",    43, 40, "Armv8.4 Self-hosted Trace Extension"),
+        (doublelock,   "DoubleLock",   39, 36, "OS Double Lock"),
+        (pmsver,       "PMSVer",       35, 32, "Statistical Profiling Extension"),
+        (ctx_cmps,     "CTX_CMPs",     31, 28, "Number of context-aware breakpoints minus 1"),
+        (sebep,        "SEBEP",        27, 24, "Synchronous-exception-based event profiling"),
+        (wrps,         "WRPs",         23, 20, "Number of watchpoints minus 1"),
+        (pmss,         "PMSS",         19, 16, "PMU snapshot extension"),
+        (brps,         "BRPs",         15, 12, "Number of breakpoints minus 1"),
+        (pmuver,       "PMUVer",       11,  8, "Performance Monitors Extension version"),
+        (tracever,     "TraceVer",      7,  4, "Trace support"),
+        (debugver,     "DebugVer",      3,  0, "Debug architecture version"),
+    }
+
+    /// AArch64 Debug Feature Register 1
+    IdAa64Dfr1, ID_AA64DFR1_EL1 {
+        (abl_cmps,     "ABL_CMPs",     63, 56, "Number of breakpoints with address linking"),
+        (dpfzs,        "DPFZS",        55, 52, "PMU counting in Debug state"),
+        (ebep,         "EBEP",         51, 48, "Exception-based event profiling"),
+        (ite,          "ITE",          47, 44, "Instrumentation Trace Extension"),
+        (able,         "ABLE",         43, 40, "Address Breakpoint Linking Extension"),
+        (pmicntr,      "PMICNTR",      39, 36, "PMU fixed-function instruction counter"),
+        (spmu,         "SPMU",         35, 32, "System PMU Extension"),
+        (ctx_cmps,     "CTX_CMPs",     31, 24, "Number of context-aware breakpoints minus 1"),
+        (wrps,         "WRPs",         23, 16, "Number of watchpoints minus 1"),
+        (brps,         "BRPs",         15,  8, "Number of breakpoints minus 1"),
+        (syspmuid,     "SYSPMUID",      7,  0, "System PMU ID"),
+    }
+
+    /// AArch64 Instruction Set Attribute Register 0
+    IdAa64Isar0, ID_AA64ISAR0_EL1 {
+        (rndr,         "RNDR",         63, 60, "Random number instructions"),
+        (tlb,          "TLB",          59, 56, "Outer Shareable and TLB range maintenance instructions"),
+        (ts,           "TS",           55, 52, "Flag manipulation instructions"),
+        (fhm,          "FHM",          51, 48, "FMLAL and FMLSL instructions"),
+        (dp,           "DP",           47, 44, "Dot Product instructions"),
+        (sm4,          "SM4",          43, 40, "SM4 instructions"),
+        (sm3,          "SM3",          39, 36, "SM3 instructions"),
+        (sha3,         "SHA3",         35, 32, "SHA3 instructions"),
+        (rdm,          "RDM",          31, 28, "SQRDMLAH and SQRDMLSH instructions"),
+        (tme,          "TME",          27, 24, "Transactional Memory Extension"),
+        (atomics,      "Atomic",       23, 20, "Atomic instructions"),
+        (crc32,        "CRC32",        19, 16, "CRC32 instructions"),
+        (sha2,         "SHA2",         15, 12, "SHA2 instructions"),
+        (sha1,         "SHA1",         11,  8, "SHA1 instructions"),
+        (aes,          "AES",           7,  4, "AES instructions"),
+    }
+
+    /// AArch64 Instruction Set Attribute Register 1
+    IdAa64Isar1, ID_AA64ISAR1_EL1 {
+        (ls64,         "LS64",         63, 60, "LD64B and ST64B instructions"),
+        (xs,           "XS",           59, 56, "XS attribute"),
+        (i8mm,         "I8MM",         55, 52, "Int8 matrix multiplication instructions"),
+        (dgh,          "DGH",          51, 48, "Data Gathering Hint"),
+        (bf16,         "BF16",         47, 44, "BFloat16 instructions"),
+        (specres,      "SPECRES",      43, 40, "Speculation restriction instructions"),
+        (sb,           "SB",           39, 36, "SB instruction"),
+        (frintts,      "FRINTTS",      35, 32, "FRINT32Z, FRINT32X, FRINT64Z, and FRINT64X instructions"),
+        (gpi,          "GPI",          31, 28, "IMPLEMENTATION DEFINED generic code authentication"),
+        (gpa,          "GPA",          27, 24, "QARMA5 generic code authentication"),
+        (lrcpc,        "LRCPC",        23, 20, "Load-Acquire RCpc instructions"),
+        (fcma,         "FCMA",         19, 16, "FCMLA and FCADD instructions"),
+        (jscvt,        "JSCVT",        15, 12, "FJCVTZS instruction"),
+        (api,          "API",          11,  8, "IMPLEMENTATION DEFINED address authentication"),
+        (apa,          "APA",           7,  4, "QARMA5 address authentication"),
+        (dpb,          "DPB",           3,  0, "DC CVAP and DC CVADP instructions"),
+    }
+
+    /// AArch64 Instruction Set Attribute Register 2
+    IdAa64Isar2, ID_AA64ISAR2_EL1 {
+        (ats1a,        "ATS1A",        63, 60, "AT S1E1A and AT S1E2A instructions"),
+        (lut,          "LUT",          59, 56, "Lookup table instructions"),
+        (cssc,         "CSSC",         55, 52, "Common Short Sequence Compression instructions"),
+        (rprfm,        "RPRFM",        51, 48, "RPRFM hint instruction"),
+        (prfmslc,      "PRFMSLC",      43, 40, "PRFM instructions with the SLC target"),
+        (sysinstr_128, "SYSINSTR_128", 39, 36, "SYSP instruction"),
+        (sysreg_128,   "SYSREG_128",   35, 32, "MRRS and MSRR instructions"),
+        (clrbhb,       "CLRBHB",       31, 28, "CLRBHB instruction"),
+        (pac_frac,     "PAC_frac",     27, 24, "ConstPACField"),
+        (bc,           "BC",           23, 20, "BC instruction"),
+        (mops,         "MOPS",         19, 16, "Memory Copy and Memory Set instructions"),
+        (apa3,         "APA3",         15, 12, "QARMA3 address authentication"),
+        (gpa3,         "GPA3",         11,  8, "QARMA3 generic code authentication"),
+        (rpres,        "RPRES",         7,  4, "Reciprocal estimate precision"),
+        (wfxt,         "WFxT",          3,  0, "WFET and WFIT instructions"),
+    }
+
+    /// AArch64 Instruction Set Attribute Register 3
+    IdAa64Isar3, ID_AA64ISAR3_EL1 {
+        (fprcvt,       "FPRCVT",       31, 28, "Conversions between floating-point and integer in SIMD&FP registers"),
+        (lsui,         "LSUI",         27, 24, "Unprivileged load and store instructions"),
+        (occmo,        "OCCMO",        23, 20, "Outer cacheable cache maintenance operations"),
+        (lsfe,         "LSFE",         19, 16, "Atomic floating-point memory operations"),
+        (pacm,         "PACM",         15, 12, "PACM hint instruction"),
+        (tlbiw,        "TLBIW",        11,  8, "TLBI VMALLWS2E1 instructions"),
+        (faminmax,     "FAMINMAX",      7,  4, "FAMIN and FAMAX instructions"),
+        (cpa,          "CPA",           3,  0, "Checked Pointer Arithmetic"),
+    }
+
+    /// AArch64 Memory Model Feature Register 0
+    IdAa64Mmfr0, ID_AA64MMFR0_EL1 {
+        (ecv,          "ECV",          63, 60, "Enhanced Counter Virtualization"),
+        (fgt,          "FGT",          59, 56, "Fine-Grained Traps"),
+        (exs,          "ExS",          47, 44, "Non-context synchronizing exception entry and exit"),
+        (tgran4_2,     "TGran4_2",     43, 40, "4KB granule at stage 2"),
+        (tgran64_2,    "TGran64_2",    39, 36, "64KB granule at stage 2"),
+        (tgran16_2,    "TGran16_2",    35, 32, "16KB granule at stage 2"),
+        (tgran4,       "TGran4",       31, 28, "4KB granule"),
+        (tgran64,      "TGran64",      27, 24, "64KB granule"),
+        (tgran16,      "TGran16",      23, 20, "16KB granule"),
+        (bigendel0,    "BigEndEL0",    19, 16, "Mixed-endian support at EL0"),
+        (snsmem,       "SNSMem",       15, 12, "Secure versus Non-secure Memory distinction"),
+        (bigend,       "BigEnd",       11,  8, "Mixed-endian support"),
+        (asidbits,     "ASIDBits",      7,  4, "Number of ASID bits"),
+        (parange,      "PARange",       3,  0, "Physical Address range"),
+    }
+
+    /// AArch64 Memory Model Feature Register 1
+    IdAa64Mmfr1, ID_AA64MMFR1_EL1 {
+        (ecbhb,        "ECBHB",        63, 60, "Branch history updates on exception level changes"),
+        (cmow,         "CMOW",         59, 56, "Cache maintenance instruction permission"),
+        (tidcp1,       "TIDCP1",       55, 52, "SCTLR_ELx.TIDCP"),
+        (ntlbpa,       "nTLBPA",       51, 48, "Intermediate caching of translation table walks"),
+        (afp,          "AFP",          47, 44, "FPCR.{AH, FIZ, NEP}"),
+        (hcx,          "HCX",          43, 40, "HCRX_EL2"),
+        (ets,          "ETS",          39, 36, "Enhanced Translation Synchronization"),
+        (twed,         "TWED",         35, 32, "Configurable delayed trapping of WFE"),
+        (xnx,          "XNX",          31, 28, "Stage 2 execute-never for EL0 and EL1"),
+        (specsei,      "SpecSEI",      27, 24, "SError interrupt on speculative reads"),
+        (pan,          "PAN",          23, 20, "Privileged Access Never"),
+        (lo,           "LO",           19, 16, "LORegions"),
+        (hpds,         "HPDS",         15, 12, "Hierarchical Permission Disables"),
+        (vh,           "VH",           11,  8, "Virtualization Host Extensions"),
+        (vmidbits,     "VMIDBits",      7,  4, "Number of VMID bits"),
+        (hafdbs,       "HAFDBS",        3,  0, "Hardware updates of the Access flag and dirty state"),
+    }
+
+    /// AArch64 Memory Model Feature Register 2
+    IdAa64Mmfr2, ID_AA64MMFR2_EL1 {
+        (e0pd,         "E0PD",         63, 60, "TCR_ELx.{E0PD0, E0PD1}"),
+        (evt,          "EVT",          59, 56, "Enhanced Virtualization Traps"),
+        (bbm,          "BBM",          55, 52, "Break-before-make levels"),
+        (ttl,          "TTL",          51, 48, "TLB maintenance with a translation table level hint"),
+        (fwb,          "FWB",          43, 40, "HCR_EL2.FWB"),
+        (ids,          "IDS",          39, 36, "ID register traps to EL2"),
+        (at,           "AT",           35, 32, "Unaligned single-copy atomicity"),
+        (st,           "ST",           31, 28, "Small translation tables"),
+        (nv,           "NV",           27, 24, "Nested Virtualization"),
+        (ccidx,        "CCIDX",        23, 20, "64-bit format of CCSIDR_EL1"),
+        (varange,      "VARange",      19, 16, "Virtual Address range"),
+        (iesb,         "IESB",         15, 12, "SCTLR_ELx.IESB"),
+        (lsm,          "LSM",          11,  8, "SCTLR_ELx.{nTLSMD, LSMAOE}"),
+        (uao,          "UAO",           7,  4, "User Access Override"),
+        (cnp,          "CnP",           3,  0, "Common not Private translations"),
+    }
+
+    /// AArch64 Memory Model Feature Register 3
+    IdAa64Mmfr3, ID_AA64MMFR3_EL1 {
+        (spec_fpacc,   "Spec_FPACC",   63, 60, "Speculative behavior of FPAC"),
+        (aderr,        "ADERR",        59, 56, "Asynchronous Device error exceptions"),
+        (sderr,        "SDERR",        55, 52, "Synchronous Device error exceptions"),
+        (anerr,        "ANERR",        47, 44, "Asynchronous Normal error exceptions"),
+        (snerr,        "SNERR",        43, 40, "Synchronous Normal error exceptions"),
+        (d128_2,       "D128_2",       39, 36, "128-bit translation table descriptors at stage 2"),
+        (d128,         "D128",         35, 32, "128-bit translation table descriptors"),
+        (mec,          "MEC",          31, 28, "Memory Encryption Contexts"),
+        (aie,          "AIE",          27, 24, "Attribute Index Enhancement"),
+        (s2poe,        "S2POE",        23, 20, "Stage 2 Permission Overlay"),
+        (s1poe,        "S1POE",        19, 16, "Stage 1 Permission Overlay"),
+        (s2pie,        "S2PIE",        15, 12, "Stage 2 Permission Indirection"),
+        (s1pie,        "S1PIE",        11,  8, "Stage 1 Permission Indirection"),
+        (sctlrx,       "SCTLRX",        7,  4, "SCTLR2_ELx"),
+        (tcrx,         "TCRX",          3,  0, "TCR2_ELx"),
+    }
+
+    /// AArch64 Memory Model Feature Register 4
+    IdAa64Mmfr4, ID_AA64MMFR4_EL1 {
+        (e3dse,        "E3DSE",        39, 36, "Delegated SError exceptions for EL3"),
+        (e2h0,         "E2H0",         27, 24, "Programming of HCR_EL2.E2H, signed"),
+        (nv_frac,      "NV_frac",      23, 20, "Nested Virtualization minor version"),
+        (fgwte3,       "FGWTE3",       19, 16, "Fine-grained write traps for EL3"),
+        (hacdbs,       "HACDBS",       15, 12, "Hardware accelerator for cleaning dirty state"),
+        (asid2,        "ASID2",        11,  8, "Concurrent use of two ASIDs"),
+        (eiesb,        "EIESB",         7,  4, "Implicit Error Synchronization event on exception entry, signed"),
+    }
+
+    /// AArch64 Processor Feature Register 0
+    IdAa64Pfr0, ID_AA64PFR0_EL1 {
+        (csv3,         "CSV3",         63, 60, "Speculative use of faulting data"),
+        (csv2,         "CSV2",         59, 56, "Speculative use of out of context branch targets"),
+        (rme,          "RME",          55, 52, "Realm Management Extension"),
+        (dit,          "DIT",          51, 48, "Data Independent Timing"),
+        (amu,          "AMU",          47, 44, "Activity Monitors Extension"),
+        (mpam,         "MPAM",         43, 40, "Memory Partitioning and Monitoring Extension"),
+        (sel2,         "SEL2",         39, 36, "Secure EL2"),
+        (sve,          "SVE",          35, 32, "Scalable Vector Extension"),
+        (ras,          "RAS",          31, 28, "RAS Extension"),
+        (gic,          "GIC",          27, 24, "System register GIC CPU interface"),
+        (advsimd,      "AdvSIMD",      23, 20, "Advanced SIMD, signed"),
+        (fp,           "FP",           19, 16, "Floating-point, signed"),
+        (el3,          "EL3",          15, 12, "EL3 Exception level handling"),
+        (el2,          "EL2",          11,  8, "EL2 Exception level handling"),
+        (el1,          "EL1",           7,  4, "EL1 Exception level handling"),
+        (el0,          "EL0",           3,  0, "EL0 Exception level handling"),
+    }
+
+    /// AArch64 Processor Feature Register 1
+    IdAa64Pfr1, ID_AA64PFR1_EL1 {
+        (pfar,         "PFAR",         63, 60, "Physical Fault Address Registers"),
+        (df2,          "DF2",          59, 56, "Double Fault Extension 2"),
+        (mte_x,        "MTEX",         55, 52, "MTE canonical tag checking"),
+        (the,          "THE",          51, 48, "Translation Hardening Extension"),
+        (gcs,          "GCS",          47, 44, "Guarded Control Stack"),
+        (mte_frac,     "MTE_frac",     43, 40, "Asynchronous Tag Check Fault"),
+        (nmi,          "NMI",          39, 36, "Non-maskable Interrupts"),
+        (csv2_frac,    "CSV2_frac",    35, 32, "CSV2 fractional field"),
+        (rndr_trap,    "RNDR_trap",    31, 28, "Trapping of RNDR and RNDRRS"),
+        (sme,          "SME",          27, 24, "Scalable Matrix Extension"),
+        (mpam_frac,    "MPAM_frac",    19, 16, "MPAM minor version"),
+        (ras_frac,     "RAS_frac",     15, 12, "RAS minor version"),
+        (mte,          "MTE",          11,  8, "Memory Tagging Extension"),
+        (ssbs,         "SSBS",          7,  4, "Speculative Store Bypassing controls"),
+        (bt,           "BT",            3,  0, "Branch Target Identification"),
+    }
+
+    /// AArch64 Processor Feature Register 2
+    IdAa64Pfr2, ID_AA64PFR2_EL1 {
+        (fpmr,         "FPMR",         35, 32, "FPMR register"),
+        (mtefar,       "MTEFAR",       11,  8, "Tag bits of the FAR on a Tag Check Fault"),
+        (mtestoreonly, "MTESTOREONLY",  7,  4, "Store-only Tag Checking"),
+        (mteperm,      "MTEPERM",       3,  0, "Allocation tag access permission"),
+    }
+
+    /// SVE Feature ID Register 0
+    IdAa64Zfr0, ID_AA64ZFR0_EL1 {
+        (f64mm,        "F64MM",        59, 56, "FP64 matrix multiplication instructions"),
+        (f32mm,        "F32MM",        55, 52, "FP32 matrix multiplication instructions"),
+        (f16mm,        "F16MM",        51, 48, "FP16 matrix multiplication instructions"),
+        (i8mm,         "I8MM",         47, 44, "Int8 matrix multiplication instructions"),
+        (sm4,          "SM4",          43, 40, "SVE SM4 instructions"),
+        (sha3,         "SHA3",         35, 32, "SVE SHA3 instructions"),
+        (b16b16,       "B16B16",       27, 24, "SVE non-widening BFloat16 instructions"),
+        (bf16,         "BF16",         23, 20, "SVE BFloat16 instructions"),
+        (bitperm,      "BitPerm",      19, 16, "SVE bit permute instructions"),
+        (eltperm,      "EltPerm",      15, 12, "SVE FP16 element permute instructions"),
+        (aes,          "AES",           7,  4, "SVE AES instructions"),
+        (svever,       "SVEver",        3,  0, "SVE version"),
+    }
+
+    /// SME Feature ID Register 0
+    IdAa64Smfr0, ID_AA64SMFR0_EL1 {
+        (fa64,         "FA64",         63, 63, "Full A64 instruction set in Streaming SVE mode"),
+        (lutv2,        "LUTv2",        60, 60, "SME2 lookup table instructions with 4 registers"),
+        (smever,       "SMEver",       59, 56, "SME version, 0b0001 for SME2, 0b0010 for SME2.1, 0b0011 for SME2.2"),
+        (i16i64,       "I16I64",       55, 52, "SMOPA and UMOPA with 16-bit inputs and 64-bit accumulation"),
+        (f64f64,       "F64F64",       48, 48, "FMOPA with 64-bit floating-point inputs"),
+        (i16i32,       "I16I32",       47, 44, "SMOPA and UMOPA with 16-bit inputs and 32-bit accumulation"),
+        (b16b16,       "B16B16",       43, 43, "SME non-widening BFloat16 instructions"),
+        (f16f16,       "F16F16",       42, 42, "SME non-widening half-precision instructions"),
+        (f8f16,        "F8F16",        41, 41, "SME FP8 to half-precision instructions"),
+        (f8f32,        "F8F32",        40, 40, "SME FP8 to single-precision instructions"),
+        (i8i32,        "I8I32",        39, 36, "SMOPA and UMOPA with 8-bit inputs and 32-bit accumulation"),
+        (f16f32,       "F16F32",       35, 35, "FMOPA with half-precision inputs and single-precision accumulation"),
+        (b16f32,       "B16F32",       34, 34, "BFMOPA with BFloat16 inputs and single-precision accumulation"),
+        (bi32i32,      "BI32I32",      33, 33, "BMOPA and BMOPS"),
+        (f32f32,       "F32F32",       32, 32, "FMOPA with single-precision inputs"),
+        (sf8fma,       "SF8FMA",       30, 30, "Streaming SVE FP8 multiply-add instructions"),
+        (sf8dp4,       "SF8DP4",       29, 29, "Streaming SVE FP8 four-way dot product instructions"),
+        (sf8dp2,       "SF8DP2",       28, 28, "Streaming SVE FP8 two-way dot product instructions"),
+        (sbitperm,     "SBitPerm",     25, 25, "Streaming SVE bit permute instructions"),
+        (aes,          "AES",          24, 24, "Streaming SVE AES and 128-bit polynomial multiply long instructions"),
+        (sfexpa,       "SFEXPA",       23, 23, "Streaming SVE FEXPA instruction"),
+        (stmop,        "STMOP",        16, 16, "Structured sparsity outer product instructions"),
+        (smop4,        "SMOP4",         0,  0, "Quarter-tile outer product instructions"),
+    }
+
+    /// AArch64 Floating-point Feature Register 0
+    IdAa64Fpfr0, ID_AA64FPFR0_EL1 {
+        (f8cvt,        "F8CVT",        31, 31, "FP8 convert instructions"),
+        (f8fma,        "F8FMA",        30, 30, "FP8 multiply-add instructions"),
+        (f8dp4,        "F8DP4",        29, 29, "FP8 four-way dot product instructions"),
+        (f8dp2,        "F8DP2",        28, 28, "FP8 two-way dot product instructions"),
+        (f8e4m3,       "F8E4M3",        1,  1, "E4M3 FP8 format"),
+        (f8e5m2,       "F8E5M2",        0,  0, "E5M2 FP8 format"),
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features_list::matched_fields;
+    use crate::{features_from_snapshot, RegisterSnapshot};
+    use std::collections::HashSet;
+    use strum::IntoEnumIterator;
+
+    /// Neoverse N1
+    const ISAR0: u64 = 0x0000100010211120;
+
+    #[test]
+    fn test_decode() {
+        let isar0 = IdAa64Isar0::decode(ISAR0);
+
+        assert_eq!(isar0.atomics, 0b0010);
+        assert_eq!(isar0.rdm, 0b0001);
+        assert_eq!(isar0.crc32, 0b0001);
+        assert_eq!(isar0.sha2, 0b0001);
+        assert_eq!(isar0.sha1, 0b0001);
+        assert_eq!(isar0.aes, 0b0010);
+        assert_eq!(isar0.dp, 0b0001);
+        assert_eq!(isar0.rndr, 0);
+        assert_eq!(IdAa64Isar0::decode(0), IdAa64Isar0::default());
+
+        let smfr0 = IdAa64Smfr0::decode(1 << 63 | 0b1111 << 52 | 1 << 48);
+        assert_eq!(smfr0.fa64, 1);
+        assert_eq!(smfr0.i16i64, 0b1111);
+        assert_eq!(smfr0.f64f64, 1);
+        assert_eq!(smfr0.lutv2, 0);
+
+        let smfr0 = IdAa64Smfr0::decode(0b0011 << 56 | 1 << 24 | 1);
+        assert_eq!(smfr0.smever, 0b0011);
+        assert_eq!(smfr0.aes, 1);
+        assert_eq!(smfr0.smop4, 1);
+        assert_eq!(smfr0.sfexpa, 0);
+    }
+
+    #[test]
+    fn test_fields() {
+        for register in Register::iter() {
+            let fields = register.decode(u64::MAX);
+            let mut used = 0u64;
+
+            for (field, next) in fields.iter().zip(fields.iter().skip(1)) {
+                assert!(field.lsb() > next.msb(), "{register:?} {}", field.name());
+            }
+            for field in &fields {
+                assert_eq!(field.register(), register);
+                assert!(field.msb() < 64 && field.msb() >= field.lsb());
+                assert_eq!(field.value(), u64::MAX >> (64 - field.width()));
+
+                let mask = field.value() << field.lsb();
+                assert_eq!(used & mask, 0, "{register:?} {}", field.name());
+                used |= mask;
+            }
+        }
+
+        assert!(Register::CTR_EL0.decode(u64::MAX).is_empty());
+        assert!(Register::NoRegister.decode(u64::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_matched_fields() {
+        for (register, msb, lsb) in matched_fields() {
+            if register.decode(0).is_empty() {
+                continue;
+            }
+
+            assert!(
+                register
+                    .decode(0)
+                    .iter()
+                    .any(|field| field.msb() == msb && field.lsb() == lsb),
+                "{register:?} [{msb}:{lsb}]"
+            );
+        }
+    }
+
+    #[test]
+    fn test_features() {
+        let mut snapshot = RegisterSnapshot::new();
+        snapshot.set(Register::ID_AA64ISAR0_EL1, ISAR0);
+        snapshot.set(Register::ID_AA64ISAR1_EL1, 0x0000000000100001);
+        snapshot.set(Register::ID_AA64PFR0_EL1, 0x1100000010111111);
+
+        let from_fields: HashSet<Feature> = snapshot
+            .iter()
+            .flat_map(|(register, value)| register.decode(value))
+            .flat_map(|field| field.features())
+            .collect();
+
+        assert_eq!(from_fields, features_from_snapshot(&snapshot));
+    }
+
+    #[test]
+    fn test_display() {
+        let isar0 = IdAa64Isar0::decode(ISAR0);
+        let text = isar0.to_string();
+
+        assert!(text.starts_with("ID_AA64ISAR0_EL1\n"));
+        assert!(text.contains("  Atomic       [23:20] 0b0010  Atomic instructions (FEAT_LSE)\n"));
+        assert!(text.contains("  RNDR         [63:60] 0b0000  Random number instructions\n"));
+        assert_eq!(text.lines().count(), isar0.fields().len() + 1);
+
+        let smfr0 = IdAa64Smfr0::decode(1 << 63);
+        assert!(smfr0.to_string().contains(
+            "  FA64         [63]    0b1     Full A64 instruction set in Streaming SVE mode (FEAT_SME_FA64)\n"
+        ));
+    }
+}
@@ -59,13 +59,11 @@
 //! ```rust
 //! use aarch64_features::{check_features, Feature};
 //!
-//! fn main() {
-//!   let features = check_features();
+//! let features = check_features();
 //!
-//!   if features.contains(&Feature::FEAT_LSE) {
-//!     println!("happy");
-//!   }
-//!}
+//! if features.contains(&Feature::FEAT_LSE) {
+//!   println!("happy");
+//! }
 //! ```
 //!
 //! It removes the need to understand the details of low-level AArch64 system programming.
@@ -116,41 +114,39 @@
 //! ```rust
 //! use aarch64_features::{check_features, Feature, get_description};
 //!
-//! fn main() {
-//!   let features = check_features();
+//! let features = check_features();
 //!
-//!   if features.contains(&Feature::FEAT_LSE) {
-//!     println!("happy");
-//!   }
-//!
-//!   if features.contains(&Feature::FEAT_BF16) {
-//!     println!("^");
-//!   }
+//! if features.contains(&Feature::FEAT_LSE) {
+//!   println!("happy");
+//! }
 //!
-//!   if features.contains(&Feature::FEAT_LRCPC) {
-//!     println!("really happy");
+//! if features.contains(&Feature::FEAT_BF16) {
+//!   println!("^");
+//! }
 //!
-//!   }
+//! if features.contains(&Feature::FEAT_LRCPC) {
+//!   println!("really happy");
 //!
-//!   if features.contains(&Feature::FEAT_AdvSIMD) {
-//!     println!("no surprise");
-//!   }
+//! }
 //!
-//!   if features.contains(&Feature::FEAT_FCMA) {
-//!     println!("too complex for me");
-//!   }
+//! if features.contains(&Feature::FEAT_AdvSIMD) {
+//!   println!("no surprise");
+//! }
 //!
-//!   if features.contains(&Feature::FEAT_LRCPC2) {
-//!     println!("atomic");
-//!   }
+//! if features.contains(&Feature::FEAT_FCMA) {
+//!   println!("too complex for me");
+//! }
 //!
-//!   if features.contains(&Feature::FEAT_LSE2) {
-//!     println!("blows my mind");
-//!   }
+//! if features.contains(&Feature::FEAT_LRCPC2) {
+//!   println!("atomic");
+//! }
 //!
-//!   // Limited ordering regions
-//!   println!("FEAT_LOR: {:?}", get_description(&Feature::FEAT_LOR));
+//! if features.contains(&Feature::FEAT_LSE2) {
+//!   println!("blows my mind");
 //! }
+//!
+//! // Limited ordering regions
+//! println!("FEAT_LOR: {:?}", get_description(&Feature::FEAT_LOR));
 //! ```
 //!
 //!# Tests
@@ -181,6 +177,8 @@
 //!
 //!The Linux kernel only exports a subset of the feature to userspace, see [features](https://github.com/torvalds/linux/blob/master/Documentation/arm64/cpu-feature-registers.rst). The exported features change over time. Thus the reported features of this crate may change between releases, see [exposed features](crate::linux_exported_features::EXPORTED_FEATURES).
 //!
+//! Without `MRS` emulation, i.e., `HWCAP_CPUID` is not set, the features are decoded from the [hardware capabilities](crate::hwcap) of the auxiliary vector.
+//!
 //!
 //!
 //!# Test your core
@@ -202,7 +200,26 @@
 //!
 //!```
 
-use crate::features_list::get_features;
+pub use crate::all_cpus::{check_features_all_cpus, SystemFeatures};
+pub use crate::cached::has;
+pub use crate::compiler::{march_string, mcpu_name, Compiler};
+pub use crate::dependencies::{implied_closure, validate, Inconsistency};
+pub use crate::feature_names::ParseFeatureError;
+pub use crate::feature_set::FeatureSet;
+pub use crate::features_list::{features_in_category, features_introduced_in};
+use crate::features_list::{get_features, get_features_from_snapshot};
+pub use crate::id_registers::{
+    Field, IdAa64Dfr0, IdAa64Dfr1, IdAa64Fpfr0, IdAa64Isar0, IdAa64Isar1, IdAa64Isar2, IdAa64Isar3,
+    IdAa64Mmfr0, IdAa64Mmfr1, IdAa64Mmfr2, IdAa64Mmfr3, IdAa64Mmfr4, IdAa64Pfr0, IdAa64Pfr1,
+    IdAa64Pfr2, IdAa64Smfr0, IdAa64Zfr0,
+};
+pub use crate::profile::{ArchProfile, ComplianceReport};
+pub use crate::registers_info::{Register, RegisterSnapshot};
+pub use crate::sve_sme::{
+    set_sme_vl, set_sve_vl, supported_sme_vls, supported_sve_vls, SetVectorLengthError,
+    VectorLength, VectorLengths, ZaTiles,
+};
+pub use crate::target_features::{target_features, TargetFeatures};
 use std::collections::HashSet;
 use strum_macros::{EnumCount, EnumIter};
 
@@ -550,6 +567,8 @@ pub enum Feature {
     FEAT_LSE128,
     /// Scalable Matrix Extension version 2.1
     FEAT_SME2p1,
+    /// Non-widening BFloat16 to BFloat16 arithmetic for SME2.1
+    FEAT_SME_B16B16,
     /// Scalable Vector Extensions version 2.1
     FEAT_SVE2p1,
 
@@ -572,7 +591,7 @@ pub enum Feature {
     FEAT_FGWTE3,
     /// FP8 convert instructions
     FEAT_FP8,
-    /// FP8 2-way dot product to half-precision instructions    FEAT_FP8DOT2,
+    /// FP8 2-way dot product to half-precision instructions
     FEAT_FP8DOT2,
     /// FP8 4-way dot product to single-precision instructions
     FEAT_FP8DOT4,
@@ -678,6 +697,8 @@ pub enum Feature {
     FEAT_SSVE_AES,
     /// Streaming Scalable Vector Bit Permutes instructions
     FEAT_SSVE_BitPerm,
+    /// Streaming SVE Mode FEXPA instruction
+    FEAT_SSVE_FEXPA,
     /// Scalable Vector Extensions version 2.2
     FEAT_SVE2p2,
     /// SVE multi-vector Advanced Encryption Standard and 128-bit polynomial multiply long instructions
@@ -801,13 +822,16 @@ pub enum Feature {
     // 2023 Architecture Extensions
     /// Programming of HCR_EL2.E2H
     FEAT_E2HO,
-    /// FP8 convert instructions
+    /// FP8 convert instructions, the same as [`Feature::FEAT_FP8`]
     FP8,
 }
 
 /// test for all aarch64 features
 pub fn check_features() -> HashSet<Feature> {
     if !check_availability() {
+        if let Some(features) = fallback_features() {
+            return features;
+        }
         eprintln!("warning unsupported os or cpu");
         return HashSet::new();
     }
@@ -815,6 +839,92 @@ pub fn check_features() -> HashSet<Feature> {
     get_features()
 }
 
+/// Where [`check_features`] gets the features from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DetectionSource {
+    /// The ID registers, e.g., through the `MRS` emulation of Linux
+    IdRegisters,
+    /// The fallback of the OS, e.g., the hwcaps of Linux
+    Fallback,
+    /// No source, e.g., on a different architecture
+    Unavailable,
+}
+
+impl std::fmt::Display for DetectionSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DetectionSource::IdRegisters => write!(f, "id-registers"),
+            DetectionSource::Fallback => write!(f, "fallback"),
+            DetectionSource::Unavailable => write!(f, "unavailable"),
+        }
+    }
+}
+
+/// The source that [`check_features`] uses on this machine
+///
+/// ```rust
+/// use aarch64_features::{detection_source, DetectionSource};
+///
+/// if detection_source() == DetectionSource::Unavailable {
+///     println!("no features");
+/// }
+/// ```
+pub fn detection_source() -> DetectionSource {
+    if check_availability() {
+        DetectionSource::IdRegisters
+    } else if fallback_features().is_some() {
+        DetectionSource::Fallback
+    } else {
+        DetectionSource::Unavailable
+    }
+}
+
+/// The SVE vector length of the current thread in bits. Returns `None`
+/// without SVE or if the OS does not report it.
+///
+/// ```rust
+/// use aarch64_features::sve_vector_length;
+///
+/// if let Some(bits) = sve_vector_length() {
+///     assert_eq!(bits % 128, 0);
+/// }
+/// ```
+pub fn sve_vector_length() -> Option<u64> {
+    vector_lengths().sve().map(|vl| vl.bits())
+}
+
+/// The SVE and SME vector lengths of the current thread and the ZA
+/// geometry. On Linux, they are from `prctl` and `/proc/sys/abi`. Other
+/// OSes report no vector lengths.
+///
+/// ```rust
+/// use aarch64_features::vector_lengths;
+///
+/// let lengths = vector_lengths();
+///
+/// if let Some(svl) = lengths.streaming_sve() {
+///     assert_eq!(lengths.za_size(), Some(svl.bytes() * svl.bytes()));
+/// }
+/// ```
+pub fn vector_lengths() -> VectorLengths {
+    current_vector_lengths()
+}
+
+/// test for all aarch64 features in a snapshot of the registers,
+/// e.g., a dump collected on a different machine, see [`RegisterSnapshot`]
+///
+/// ```rust
+///use aarch64_features::{features_from_snapshot, RegisterSnapshot};
+///
+/// // all registers are unknown
+/// let features = features_from_snapshot(&RegisterSnapshot::new());
+/// assert!(features.is_empty());
+///```
+pub fn features_from_snapshot(snapshot: &RegisterSnapshot) -> HashSet<Feature> {
+    get_features_from_snapshot(snapshot)
+}
+
 use crate::features_list::get_feature_description;
 
 /// find a description of the given feature, see [`Feature`]
@@ -830,16 +940,28 @@ pub fn get_description(feature: &Feature) -> Option<String> {
 }
 
 #[cfg(all(target_arch = "aarch64", target_os = "macos"))]
-use crate::macos_aarch64::cpu::{check_availability, is_exposed_to_userspace};
+use crate::macos_aarch64::cpu::{
+    check_availability, current_vector_lengths, fallback_features, is_exposed_to_userspace,
+    set_vector_length, supported_vector_lengths,
+};
 
 #[cfg(all(target_arch = "aarch64", target_os = "linux"))]
-use crate::linux_aarch64::cpu::{check_availability, is_exposed_to_userspace};
+use crate::linux_aarch64::cpu::{
+    check_availability, current_vector_lengths, fallback_features, is_exposed_to_userspace,
+    set_vector_length, supported_vector_lengths,
+};
 
 #[cfg(all(target_arch = "aarch64", target_os = "windows"))]
-use crate::windows_aarch64::cpu::{check_availability, is_exposed_to_userspace};
+use crate::windows_aarch64::cpu::{
+    check_availability, current_vector_lengths, fallback_features, is_exposed_to_userspace,
+    set_vector_length, supported_vector_lengths,
+};
 
 #[cfg(not(target_arch = "aarch64"))]
-use crate::generic::cpu::{check_availability, is_exposed_to_userspace};
+use crate::generic::cpu::{
+    check_availability, current_vector_lengths, fallback_features, is_exposed_to_userspace,
+    set_vector_length, supported_vector_lengths,
+};
 
 #[cfg(all(target_arch = "aarch64", target_os = "linux"))]
 mod linux_aarch64 {
@@ -867,56 +989,133 @@ pub mod cpu_type;
 /// Representation of the MIDR_EL1 register
 pub mod midr;
 
+/// Known errata of cores keyed on MIDR_EL1
+pub mod errata;
+
+/// Decoder for the Linux hardware capabilities
+pub mod hwcap;
+
+/// Parser for the Linux /proc/cpuinfo file
+pub mod cpuinfo;
+
+/// Reader for the per-CPU identification registers in Linux sysfs
+pub mod sysfs;
+
+/// Decoder for the cache geometry and reader for the Linux cache hierarchy
+pub mod cache;
+
+/// Helpers for build scripts that emit cfgs for detected features
+#[cfg(feature = "build-support")]
+pub mod build_support;
+
 mod aarch64;
+mod all_cpus;
+mod cached;
+mod compiler;
+mod dependencies;
+mod feature_names;
+mod feature_set;
 mod features_list;
+mod id_registers;
+mod profile;
 mod registers_info;
 mod sve_sme;
+mod target_features;
+
+#[cfg(test)]
+mod fixture;
 
 /// The list of features that are exported by the kernel to userspace.
 mod linux_exported_features;
 
-#[allow(unused)]
-#[derive(Hash, Eq, PartialEq)]
-enum Category {
+/// The category of a feature, see [`Feature::category`]
+#[derive(EnumIter, EnumCount, Debug, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[non_exhaustive]
+pub enum Category {
+    /// Atomic operations and memory ordering
     Atomics,
+    /// Branch instructions
     Branch,
+    /// Floating-point and Advanced SIMD instructions
     Float,
+    /// Cache maintenance and cache speculation
     Caches,
+    /// Cryptographic instructions and random numbers
     Crypto,
+    /// Scalable Matrix Extension
     Sme,
+    /// Scalable Vector Extension
     Sve,
+    /// Virtualization
     Virtual,
+    /// Performance monitors, statistical profiling, and activity monitors
     Profiling,
+    /// Pointer authentication, memory tagging, and speculation barriers
     Security,
+    /// Self-hosted and external debug, and trace
     Debug,
+    /// Translation tables, TLB maintenance, and memory operations
     Memory,
+    /// Branch records
     Jump,
-    Unknown,
+    /// Reliability, Availability, and Serviceability
+    Ras,
+    /// Exceptions, interrupts, timers, and system control
+    System,
+    /// General-purpose integer instructions
+    Integer,
 }
 
+/// The version of the Arm architecture that introduced a feature,
+/// see [`Feature::arch_version`]. Versions are ordered, e.g.,
+/// `ArmVersion::Armv8_4 < ArmVersion::Armv9_0`.
 #[allow(non_camel_case_types)]
-#[allow(unused)]
-#[derive(Copy, Clone, Hash, Eq, PartialEq)]
-enum ARMVersion {
+#[derive(EnumIter, EnumCount, Debug, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[non_exhaustive]
+pub enum ArmVersion {
+    /// Armv8.0-A
     Armv8_0,
+    /// Armv8.1-A
     Armv8_1,
+    /// Armv8.2-A
     Armv8_2,
+    /// Armv8.3-A
     Armv8_3,
+    /// Armv8.4-A
     Armv8_4,
+    /// Armv8.5-A
     Armv8_5,
+    /// Armv8.6-A
     Armv8_6,
+    /// Armv8.7-A
     Armv8_7,
+    /// Armv8.8-A
     Armv8_8,
+    /// Armv8.9-A
     Armv8_9,
+    /// Armv9.0-A
     Armv9_0,
+    /// Armv9.1-A
     Armv9_1,
+    /// Armv9.2-A
     Armv9_2,
+    /// Armv9.3-A
     Armv9_3,
+    /// Armv9.4-A
     Armv9_4,
+    /// Armv9.5-A
     Armv9_5,
+    /// Armv9.6-A
     Armv9_6,
 }
 
+/// The spelling of the Arm ARM without the profile, e.g., `Armv8.4`
+impl std::fmt::Display for ArmVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format!("{self:?}").replace('_', "."))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use strum::EnumCount;
@@ -939,7 +1138,13 @@ mod tests {
         //    }
         //}
 
-        assert_eq!(Feature::COUNT, 201);
+        assert_eq!(Feature::COUNT, 273);
+    }
+
+    #[test]
+    fn test_arm_version_display() {
+        assert_eq!(ArmVersion::Armv8_4.to_string(), "Armv8.4");
+        assert_eq!(ArmVersion::Armv9_0.to_string(), "Armv9.0");
     }
 
     #[test]
@@ -966,14 +1171,13 @@ mod tests {
     }
 
     #[allow(unused)]
-    fn test_features(test_features: &Vec<Feature>) {
+    fn test_features(test_features: &[Feature]) {
         let features = check_features();
         let test_hashset: HashSet<Feature> = test_features.iter().cloned().collect();
         let intersection = features.intersection(&test_hashset).collect::<Vec<_>>();
 
         assert_eq!(intersection.len(), features.len());
         assert_eq!(intersection.len(), test_features.len());
-        assert_eq!(2 + 2, 4);
     }
 }
 
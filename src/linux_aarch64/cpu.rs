@@ -1,22 +1,105 @@
-use crate::Feature;
-use libc::AT_HWCAP;
-use libc::HWCAP_CPUID;
+use crate::hwcap::HwCaps;
+use crate::sve_sme::{parse_default_vector_length, probe_vector_lengths, VectorExtension};
+use crate::{Feature, SetVectorLengthError, VectorLength, VectorLengths};
+use std::collections::HashSet;
 // MIDR_EL1
 use crate::linux_exported_features::EXPORTED_FEATURES;
 
 /// see https://www.kernel.org/doc/html/latest/arm64/cpu-feature-registers.html
 pub(crate) fn check_availability() -> bool {
-    let caps = unsafe { libc::getauxval(AT_HWCAP) };
-    if (caps & HWCAP_CPUID) != 0 {
-        return false;
-    }
-    true
+    HwCaps::read().has_cpuid()
 }
 
 pub(crate) fn is_exposed_to_userspace(feat: Feature) -> bool {
     EXPORTED_FEATURES.contains(&feat)
 }
 
+/// Without MRS emulation, the kernel still reports the hwcaps
+pub(crate) fn fallback_features() -> Option<HashSet<Feature>> {
+    Some(HwCaps::read().features())
+}
+
+// linux/prctl.h
+const PR_SVE_SET_VL: libc::c_int = 50;
+const PR_SVE_GET_VL: libc::c_int = 51;
+const PR_SME_SET_VL: libc::c_int = 63;
+const PR_SME_GET_VL: libc::c_int = 64;
+const PR_VL_INHERIT: libc::c_ulong = 1 << 17;
+
+/// see https://www.kernel.org/doc/html/latest/arch/arm64/sve.html and
+/// https://www.kernel.org/doc/html/latest/arch/arm64/sme.html
+pub(crate) fn current_vector_lengths() -> VectorLengths {
+    VectorLengths::new(
+        get_vector_length(PR_SVE_GET_VL),
+        get_vector_length(PR_SME_GET_VL),
+        read_default_vector_length("/proc/sys/abi/sve_default_vector_length"),
+        read_default_vector_length("/proc/sys/abi/sme_default_vector_length"),
+    )
+}
+
+fn get_vector_length(option: libc::c_int) -> Option<VectorLength> {
+    // SAFETY: PR_SVE_GET_VL and PR_SME_GET_VL take no further arguments
+    // and fail with EINVAL without SVE or SME
+    let ret = unsafe { libc::prctl(option) };
+
+    VectorLength::from_prctl(ret)
+}
+
+/// Probe from the longest length down on a short-lived thread. The
+/// vector length is per thread and setting it discards the SVE and ZA
+/// state, so the calling thread is not touched.
+pub(crate) fn supported_vector_lengths(extension: VectorExtension) -> Vec<u64> {
+    let set = set_vl_option(extension);
+
+    std::thread::spawn(move || {
+        probe_vector_lengths(|bytes| {
+            // SAFETY: the thread runs no code that uses the SVE or SME
+            // register state, which changing the vector length discards
+            let ret = unsafe { libc::prctl(set, bytes) };
+            VectorLength::from_prctl(ret).map(|vl| vl.bytes())
+        })
+    })
+    .join()
+    .unwrap_or_default()
+}
+
+pub(crate) fn set_vector_length(
+    extension: VectorExtension,
+    bytes: u64,
+    inherit: bool,
+) -> Result<VectorLength, SetVectorLengthError> {
+    let set = set_vl_option(extension);
+    let flags = if inherit { PR_VL_INHERIT } else { 0 };
+
+    // SAFETY: the length was checked by the caller, who also ensures
+    // that no live SVE or SME register state of the current thread is
+    // discarded, see set_sve_vl
+    let ret = unsafe { libc::prctl(set, bytes | flags) };
+
+    VectorLength::from_prctl(ret).ok_or_else(|| {
+        match std::io::Error::last_os_error().raw_os_error() {
+            // without SVE or SME
+            Some(libc::EINVAL) => SetVectorLengthError::Unsupported,
+            Some(errno) => SetVectorLengthError::Os(errno),
+            None => SetVectorLengthError::Unsupported,
+        }
+    })
+}
+
+/// The `SET_VL` option of `prctl`
+fn set_vl_option(extension: VectorExtension) -> libc::c_int {
+    match extension {
+        VectorExtension::Sve => PR_SVE_SET_VL,
+        VectorExtension::Sme => PR_SME_SET_VL,
+    }
+}
+
+fn read_default_vector_length(path: &str) -> Option<u64> {
+    let text = std::fs::read_to_string(path).ok()?;
+
+    parse_default_vector_length(&text)
+}
+
 // https://developer.arm.com/documentation/102099/0000/AArch64-registers/AArch64-identification-registers/MIDR-EL1--Main-ID-Register
 
 // https://developer.arm.com/documentation/100616/0301/register-descriptions/aarch64-system-registers/midr-el1--main-id-register--el1
@@ -1,10 +1,16 @@
 use crate::Feature;
 
 /// These are the features exported by the kernel to userspace, see [kernel](https://github.com/torvalds/linux/blob/master/Documentation/arch/arm64/cpu-feature-registers.rst).
-/// The list was last updated at 16.8.2022 (non-exhaustive).
+/// The list was last updated at 17.10.2026 for the fields of the
+/// matchers in `features_list`.
+#[cfg_attr(
+    not(all(target_arch = "aarch64", target_os = "linux")),
+    allow(dead_code)
+)]
 pub(crate) const EXPORTED_FEATURES: &[Feature] = &[
     // ID_AA64ISAR0_EL1
     Feature::FEAT_RNG,     // RNDR
+    Feature::FEAT_FlagM,   // TS
     Feature::FEAT_FlagM2,  // TS
     Feature::FEAT_FHM,     // FHM
     Feature::FEAT_DotProd, // DP
@@ -13,22 +19,144 @@ pub(crate) const EXPORTED_FEATURES: &[Feature] = &[
     Feature::FEAT_SHA3,    // SHA3
     Feature::FEAT_RDM,     // RDM
     Feature::FEAT_LSE,     // ATOMICS
+    Feature::FEAT_LSE128,  // ATOMICS
     // CRC32 instructions have no features
     Feature::FEAT_SHA256, // SHA2
+    Feature::FEAT_SHA512, // SHA2
     Feature::FEAT_SHA1,   // SHA1
     Feature::FEAT_PMULL,  // AES
     Feature::FEAT_AES,    // AES
     // ID_AA64PFR0_EL1
-    Feature::FEAT_DIT,     // DIR
+    Feature::FEAT_DIT,     // DIT
     Feature::FEAT_SVE,     // SVE
     Feature::FEAT_AdvSIMD, // AdvSIMD
+    Feature::FEAT_FP16,    // FP
     // ID_AA64PFR1_EL1
-    Feature::FEAT_MTE, // MTE
-    Feature::FEAT_SME,
+    Feature::FEAT_SME,  // SME
+    Feature::FEAT_SME2, // SME
+    Feature::FEAT_MTE,  // MTE
+    Feature::FEAT_MTE2, // MTE
+    Feature::FEAT_MTE3, // MTE
+    Feature::FEAT_SSBS, // SSBS
+    Feature::FEAT_BTI,  // BT
     // MIDR_EL1
     // ID_AA64ISAR1_EL1
-    Feature::FEAT_LRCPC,
+    Feature::FEAT_LS64,         // LS64
+    Feature::FEAT_LS64_V,       // LS64
+    Feature::FEAT_LS64_ACCDATA, // LS64
+    Feature::FEAT_XS,           // XS
+    Feature::FEAT_I8MM,         // I8MM
+    Feature::FEAT_DGH,          // DGH
+    Feature::FEAT_BF16,         // BF16
+    Feature::FEAT_EBF16,        // BF16
+    Feature::FEAT_SPECRES,      // SPECRES
+    Feature::FEAT_SB,           // SB
+    Feature::FEAT_FRINTTS,      // FRINTTS
+    Feature::FEAT_LRCPC,        // LRCPC
+    Feature::FEAT_LRCPC2,       // LRCPC
+    Feature::FEAT_LRCPC3,       // LRCPC
+    Feature::FEAT_FCMA,         // FCMA
+    Feature::FEAT_JSCVT,        // JSCVT
+    Feature::FEAT_PAuth,        // API, APA
+    Feature::FEAT_PAuth2,       // API, APA
+    Feature::FEAT_FPAC,         // API, APA
+    Feature::FEAT_FPACCOMBINE,  // API, APA
+    Feature::FEAT_DPB,          // DPB
+    Feature::FEAT_DPB2,         // DPB
+    // ID_AA64MMFR0_EL1
+    Feature::FEAT_ECV, // ECV
+    // ID_AA64MMFR1_EL1
+    Feature::FEAT_AFP, // AFP
+    // ID_AA64MMFR2_EL1
+    Feature::FEAT_LSE2, // AT
+    // ID_AA64MMFR3_EL1
+    Feature::FEAT_S1POE, // S1POE
+    // ID_AA64ZFR0_EL1
+    Feature::FEAT_F64MM,        // F64MM
+    Feature::FEAT_F32MM,        // F32MM
+    Feature::FEAT_SVE_F16F32MM, // F16MM
+    Feature::FEAT_SVE_SM4,      // SM4
+    Feature::FEAT_SVE_SHA3,     // SHA3
+    Feature::FEAT_B16B16,       // B16B16
+    Feature::FEAT_SVE_BFSCALE,  // B16B16
+    Feature::FEAT_SVE_BitPerm,  // BitPerm
+    Feature::FEAT_SVE_AES,      // AES
+    Feature::FEAT_SVE_PMULL128, // AES
+    Feature::FEAT_SVE_AES2,     // AES
+    Feature::FEAT_SVE2,         // SVEver
+    Feature::FEAT_SVE2p1,       // SVEver
+    Feature::FEAT_SVE2p2,       // SVEver
     // ID_AA64ISAR2_EL1
-    Feature::FEAT_CSSC,
-    Feature::FEAT_MOPS,
+    Feature::FEAT_LUT,       // LUT
+    Feature::FEAT_CSSC,      // CSSC
+    Feature::FEAT_CMPBR,     // CSSC
+    Feature::FEAT_HBC,       // BC
+    Feature::FEAT_MOPS,      // MOPS
+    Feature::FEAT_PACQARMA3, // APA3, GPA3
+    Feature::FEAT_RPRES,     // RPRES
+    Feature::FEAT_WFxT,      // WFXT
+    // ID_AA64ISAR3_EL1
+    Feature::FEAT_FPRCVT,   // FPRCVT
+    Feature::FEAT_LSFE,     // LSFE
+    Feature::FEAT_FAMINMAX, // FAMINMAX
+    Feature::FEAT_CPA,      // CPA
+    // ID_AA64PFR2_EL1
+    Feature::FEAT_FPMR, // FPMR
+    // ID_AA64FPFR0_EL1
+    Feature::FEAT_FP8,
+    Feature::FP8,
+    Feature::FEAT_FP8FMA,
+    Feature::FEAT_FP8DOT4,
+    Feature::FEAT_FP8DOT2,
+    // ID_AA64SMFR0_EL1
+    Feature::FEAT_SME_FA64,     // FA64
+    Feature::FEAT_SME_LUTv2,    // LUTv2
+    Feature::FEAT_SME2p1,       // SMEver
+    Feature::FEAT_SME2p2,       // SMEver
+    Feature::FEAT_SME_I16I64,   // I16I64
+    Feature::FEAT_SME_F64F64,   // F64F64
+    Feature::FEAT_SME_B16B16,   // B16B16
+    Feature::FEAT_SME_F16F16,   // F16F16
+    Feature::FEAT_SME_F8F16,    // F8F16
+    Feature::FEAT_SME_F8F32,    // F8F32
+    Feature::FEAT_SSVE_BitPerm, // SBitPerm
+    Feature::FEAT_SSVE_AES,     // AES
+    Feature::FEAT_SSVE_FEXPA,   // SFEXPA
+    Feature::FEAT_SME_TMOP,     // STMOP
+    Feature::FEAT_SME_MOP4,     // SMOP4
+    Feature::FEAT_SSVE_FP8FMA,
+    Feature::FEAT_SSVE_FP8DOT4,
+    Feature::FEAT_SSVE_FP8DOT2,
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArchProfile;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_mandatory_features_are_exported() {
+        for profile in ArchProfile::iter() {
+            for feature in profile.mandatory() {
+                assert!(
+                    EXPORTED_FEATURES.contains(&feature),
+                    "{profile:?} {feature:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_prerequisites_are_exported() {
+        // validate() of check_features() must not report the filter
+        for feature in EXPORTED_FEATURES {
+            for implied in feature.implies() {
+                assert!(
+                    EXPORTED_FEATURES.contains(implied),
+                    "{feature:?} {implied:?}"
+                );
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use crate::Feature;
+use crate::sve_sme::VectorExtension;
+use crate::{Feature, SetVectorLengthError, VectorLength, VectorLengths};
+use std::collections::HashSet;
 
 // MIDR_EL1
 pub(crate) fn check_availability() -> bool {
@@ -9,6 +11,26 @@ pub(crate) fn is_exposed_to_userspace(_feat: Feature) -> bool {
     true
 }
 
+pub(crate) fn fallback_features() -> Option<HashSet<Feature>> {
+    None
+}
+
+pub(crate) fn current_vector_lengths() -> VectorLengths {
+    VectorLengths::default()
+}
+
+pub(crate) fn supported_vector_lengths(_extension: VectorExtension) -> Vec<u64> {
+    Vec::new()
+}
+
+pub(crate) fn set_vector_length(
+    _extension: VectorExtension,
+    _bytes: u64,
+    _inherit: bool,
+) -> Result<VectorLength, SetVectorLengthError> {
+    Err(SetVectorLengthError::Unsupported)
+}
+
 // AArch64: Add initial apple-m1 target.
 // https://reviews.llvm.org/D92619
 
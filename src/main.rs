@@ -1,33 +1,837 @@
-use strum::EnumCount;
+//! Print the features of the current machine, e.g., for an inventory
+//! of a fleet.
+//!
+//! ```bash
+//! aarch64_features --format json > old.json
+//! aarch64_features diff old.json new.json
+//! aarch64_features require FEAT_LSE FEAT_SVE2 --min-arch armv8.4-a
+//! ```
 
-fn main() {
-    let count = aarch64_features::Feature::COUNT;
-    println!("number of features: {count}");
+use aarch64_features::cpu_type::Core;
+use aarch64_features::midr::{Midr, MidrEL1};
+use aarch64_features::{
+    check_features, detection_source, supported_sme_vls, supported_sve_vls, sve_vector_length,
+    ArchProfile, ArmVersion, Category, DetectionSource, Feature, FeatureSet, RegisterSnapshot,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::FromStr;
 
-    #[cfg(not(target_arch = "aarch64"))]
-    {
-        println!("this not an aarch64 cpu!");
+const USAGE: &str = "usage: aarch64_features [--format json|yaml|toml|rust|text]
+       aarch64_features diff <old.json> <new.json> [--require FEAT_X[,FEAT_Y]]...
+       aarch64_features require [FEAT_X]... [--min-arch armv8.4-a] [--json]";
+
+/// The output formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Yaml,
+    Toml,
+    Rust,
+    Text,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "json" => Ok(Format::Json),
+            "yaml" => Ok(Format::Yaml),
+            "toml" => Ok(Format::Toml),
+            "rust" => Ok(Format::Rust),
+            "text" => Ok(Format::Text),
+            _ => Err(format!("unknown format: {name}")),
+        }
+    }
+}
+
+/// The parsed command line
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Show(Format),
+    /// Compare two reports. Without required features, every feature
+    /// of the old report is required.
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+        required: Option<FeatureSet>,
+    },
+    /// Check the current machine against requirements
+    Require {
+        features: FeatureSet,
+        min_arch: Option<ArchProfile>,
+        json: bool,
+    },
+    Help,
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    match args.first().map(String::as_str) {
+        Some("diff") => parse_diff_args(&args[1..]),
+        Some("require") => parse_require_args(&args[1..]),
+        _ => parse_show_args(args),
+    }
+}
+
+fn parse_diff_args(args: &[String]) -> Result<Command, String> {
+    let mut paths = Vec::new();
+    let mut required: Option<FeatureSet> = None;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--require" => {
+                let names = args.next().ok_or("--require requires a value")?;
+                required
+                    .get_or_insert_with(FeatureSet::new)
+                    .extend(parse_features(names)?);
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown argument: {arg}")),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    match <[PathBuf; 2]>::try_from(paths) {
+        Ok([old, new]) => Ok(Command::Diff { old, new, required }),
+        Err(_) => Err(String::from("diff requires two reports")),
+    }
+}
+
+fn parse_require_args(args: &[String]) -> Result<Command, String> {
+    let mut features = FeatureSet::new();
+    let mut min_arch = None;
+    let mut json = false;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--json" => json = true,
+            "--min-arch" => {
+                let name = args.next().ok_or("--min-arch requires a value")?;
+                min_arch = Some(parse_profile(name)?);
+            }
+            _ => match arg.strip_prefix("--min-arch=") {
+                Some(name) => min_arch = Some(parse_profile(name)?),
+                None if arg.starts_with("--") => return Err(format!("unknown argument: {arg}")),
+                None => features.extend(parse_features(arg)?),
+            },
+        }
+    }
+
+    if features.is_empty() && min_arch.is_none() {
+        return Err(String::from("require needs features or --min-arch"));
+    }
+
+    Ok(Command::Require {
+        features,
+        min_arch,
+        json,
+    })
+}
+
+fn parse_profile(name: &str) -> Result<ArchProfile, String> {
+    name.parse().map_err(|err| format!("{err}: {name}"))
+}
+
+/// a comma-separated list of features
+fn parse_features(names: &str) -> Result<Vec<Feature>, String> {
+    names
+        .split(',')
+        .map(|name| name.parse::<Feature>().map_err(|err| err.to_string()))
+        .collect()
+}
+
+fn parse_show_args(args: &[String]) -> Result<Command, String> {
+    let mut format = Format::Text;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--format" => {
+                let name = args.next().ok_or("--format requires a value")?;
+                format = name.parse()?;
+            }
+            _ => match arg.strip_prefix("--format=") {
+                Some(name) => format = name.parse()?,
+                None => return Err(format!("unknown argument: {arg}")),
+            },
+        }
+    }
+
+    Ok(Command::Show(format))
+}
+
+/// Everything that we know about the current machine
+#[derive(Debug, Serialize, Deserialize)]
+struct Report {
+    #[serde(default)]
+    source: String,
+    core: Option<Core>,
+    sve_vector_length: Option<u64>,
+    #[serde(default)]
+    supported_sve_vector_lengths: Vec<u64>,
+    #[serde(default)]
+    supported_sme_vector_lengths: Vec<u64>,
+    #[serde(default)]
+    features: FeatureSet,
+    midr: Option<Midr>,
+    registers: Option<RegisterSnapshot>,
+}
+
+impl Report {
+    fn detect() -> Self {
+        let source = detection_source();
+
+        // without the MRS emulation, reading MIDR_EL1 traps
+        let midr = match source {
+            DetectionSource::IdRegisters => match MidrEL1::new() {
+                MidrEL1::Known(midr) => Some(midr),
+                MidrEL1::Unknown => None,
+            },
+            _ => None,
+        };
+        let core = midr
+            .clone()
+            .and_then(|midr| Core::try_from(MidrEL1::Known(midr)).ok());
+        let registers = match source {
+            DetectionSource::IdRegisters => Some(RegisterSnapshot::read()),
+            _ => None,
+        };
+
+        let features = match source {
+            DetectionSource::Unavailable => FeatureSet::new(),
+            _ => FeatureSet::from(check_features()),
+        };
+
+        Report {
+            source: source.to_string(),
+            core,
+            sve_vector_length: sve_vector_length(),
+            supported_sve_vector_lengths: supported_sve_vls(),
+            supported_sme_vector_lengths: supported_sme_vls(),
+            features,
+            midr,
+            registers,
+        }
+    }
+
+    fn load(path: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|err| format!("cannot read {}: {err}", path.display()))?;
+
+        serde_json::from_str(&json)
+            .map_err(|err| format!("invalid report {}: {err}", path.display()))
+    }
+
+    fn render(&self, format: Format) -> Result<String, String> {
+        match format {
+            Format::Json => serde_json::to_string_pretty(self)
+                .map(|json| json + "\n")
+                .map_err(|err| err.to_string()),
+            Format::Yaml => serde_norway::to_string(self).map_err(|err| err.to_string()),
+            Format::Toml => toml::to_string(self).map_err(|err| err.to_string()),
+            Format::Rust => Ok(self.render_rust()),
+            Format::Text => Ok(self.render_text()),
+        }
+    }
+
+    fn render_rust(&self) -> String {
+        let mut out = String::from("let features = vec![\n");
+        for feature in self.features.iter() {
+            out.push_str(&format!("    Feature::{feature},\n"));
+        }
+        out.push_str("];\n");
+        out
+    }
+
+    fn render_text(&self) -> String {
+        let core = match self.core {
+            Some(core) => format!("{core:?}"),
+            None => String::from("unknown"),
+        };
+        let midr = match &self.midr {
+            Some(midr) => midr.to_string(),
+            None => String::from("unknown"),
+        };
+        let sve = match self.sve_vector_length {
+            Some(bits) => format!("{bits} bits"),
+            None => String::from("none"),
+        };
+
+        let mut out = format!(
+            "source: {}\ncore: {core}\nmidr: {midr}\nsve vector length: {sve}\n",
+            self.source
+        );
+        out.push_str(&format!(
+            "supported sve vector lengths: {}\n",
+            render_lengths(&self.supported_sve_vector_lengths)
+        ));
+        out.push_str(&format!(
+            "supported sme vector lengths: {}\n",
+            render_lengths(&self.supported_sme_vector_lengths)
+        ));
+        out.push_str(&format!("features: {}\n", self.features.len()));
+        for feature in self.features.iter() {
+            out.push_str(&format!("  {feature}\n"));
+        }
+        for (register, value) in self.registers.iter().flat_map(RegisterSnapshot::iter) {
+            let fields = register.decode(value);
+            if fields.is_empty() {
+                continue;
+            }
+
+            out.push_str(&format!("{register:?} = {value:#018x}\n"));
+            for field in fields {
+                out.push_str(&format!("  {field}\n"));
+            }
+        }
+        out
+    }
+}
+
+/// e.g., `128, 256 bits`
+fn render_lengths(lengths: &[u64]) -> String {
+    if lengths.is_empty() {
+        return String::from("none");
+    }
+
+    let lengths: Vec<String> = lengths.iter().map(u64::to_string).collect();
+    format!("{} bits", lengths.join(", "))
+}
+
+/// The features that one report has and the other lacks
+#[derive(Debug, PartialEq, Eq)]
+struct Diff {
+    added: FeatureSet,
+    removed: FeatureSet,
+}
+
+impl Diff {
+    fn new(old: &Report, new: &Report) -> Self {
+        Diff {
+            added: new.features.difference(&old.features),
+            removed: old.features.difference(&new.features),
+        }
+    }
+
+    fn render(&self, old: (&Path, &Report), new: (&Path, &Report)) -> String {
+        let mut out = String::new();
+
+        for (marker, (path, report)) in [("---", old), ("+++", new)] {
+            let core = match report.core {
+                Some(core) => format!("{core:?}"),
+                None => String::from("unknown core"),
+            };
+            out.push_str(&format!("{marker} {}: {core}\n", path.display()));
+        }
+
+        if let (Some(old), Some(new)) = (&old.1.midr, &new.1.midr) {
+            if old.to_string() != new.to_string() {
+                out.push_str(&format!("midr: {old}\n   -> {new}\n"));
+            }
+        }
+
+        out.push_str(&render_group("added", &self.added));
+        out.push_str(&render_group("removed", &self.removed));
+
+        if let (Some(old), Some(new)) = (&old.1.registers, &new.1.registers) {
+            let old: BTreeMap<_, _> = old.iter().collect();
+            let new: BTreeMap<_, _> = new.iter().collect();
+            for (register, old_value) in &old {
+                match new.get(register) {
+                    Some(new_value) if new_value != old_value => out.push_str(&format!(
+                        "{register:?}: {old_value:#018x} -> {new_value:#018x}\n"
+                    )),
+                    _ => {}
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// the features grouped by category and version
+fn render_group(title: &str, features: &FeatureSet) -> String {
+    let mut groups: BTreeMap<Option<Category>, BTreeMap<Option<ArmVersion>, Vec<Feature>>> =
+        BTreeMap::new();
+    for feature in features.iter() {
+        groups
+            .entry(feature.category())
+            .or_default()
+            .entry(feature.arch_version())
+            .or_default()
+            .push(feature);
+    }
+
+    let mut out = format!("{title} features: {}\n", features.len());
+    for (category, versions) in groups {
+        match category {
+            Some(category) => out.push_str(&format!("  {category:?}\n")),
+            None => out.push_str("  Other\n"),
+        }
+        for (version, features) in versions {
+            let version = version.map_or(String::from("unknown"), |version| version.to_string());
+            let names: Vec<String> = features.iter().map(Feature::to_string).collect();
+            out.push_str(&format!("    {version}: {}\n", names.join(" ")));
+        }
+    }
+    out
+}
+
+/// The result of checking the current machine against requirements
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct Requirements {
+    satisfied: bool,
+    /// the required features and the mandatory features of the
+    /// minimum level that are missing
+    missing: FeatureSet,
+    min_arch: Option<String>,
+    highest_arch: Option<String>,
+}
+
+impl Requirements {
+    fn check(features: &FeatureSet, required: &FeatureSet, min_arch: Option<ArchProfile>) -> Self {
+        let mut missing = required.difference(features);
+        let mut highest_arch = None;
+
+        if let Some(profile) = min_arch {
+            let report = profile.check(&HashSet::from(features));
+            missing.extend(report.missing().iter().copied());
+            highest_arch = report
+                .highest_satisfied()
+                .map(|profile| profile.to_string());
+        }
+
+        Requirements {
+            satisfied: missing.is_empty(),
+            missing,
+            min_arch: min_arch.map(|profile| profile.to_string()),
+            highest_arch,
+        }
+    }
+
+    fn render_text(&self) -> String {
+        if self.satisfied {
+            return String::from("all requirements are satisfied\n");
+        }
+
+        let mut out = format!("missing features: {}\n", self.missing.len());
+        for feature in self.missing.iter() {
+            match feature.description() {
+                Some(description) => out.push_str(&format!("  {feature}: {description}\n")),
+                None => out.push_str(&format!("  {feature}\n")),
+            }
+        }
+        if let Some(min_arch) = &self.min_arch {
+            let highest = self.highest_arch.as_deref().unwrap_or("none");
+            out.push_str(&format!(
+                "required level: {min_arch}, highest satisfied level: {highest}\n"
+            ));
+        }
+        out
+    }
+}
+
+fn show(format: Format) -> ExitCode {
+    match Report::detect().render(format) {
+        Ok(out) => {
+            print!("{out}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn diff(old_path: &Path, new_path: &Path, required: Option<FeatureSet>) -> ExitCode {
+    let (old, new) = match (Report::load(old_path), Report::load(new_path)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(err), _) | (_, Err(err)) => {
+            eprintln!("error: {err}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let diff = Diff::new(&old, &new);
+    print!("{}", diff.render((old_path, &old), (new_path, &new)));
+
+    let required = required.unwrap_or(old.features);
+    let lost = diff.removed.intersection(&required);
+    if lost.is_empty() {
+        return ExitCode::SUCCESS;
+    }
+
+    let names: Vec<String> = lost.iter().map(|feature| feature.to_string()).collect();
+    eprintln!("error: required features disappeared: {}", names.join(" "));
+    ExitCode::FAILURE
+}
+
+fn require(required: &FeatureSet, min_arch: Option<ArchProfile>, json: bool) -> ExitCode {
+    let features = FeatureSet::from(check_features());
+    let requirements = Requirements::check(&features, required, min_arch);
+
+    if json {
+        match serde_json::to_string_pretty(&requirements) {
+            Ok(json) => println!("{json}"),
+            Err(err) => {
+                eprintln!("error: {err}");
+                return ExitCode::from(2);
+            }
+        }
+    } else {
+        print!("{}", requirements.render_text());
+    }
+
+    if requirements.satisfied {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match parse_args(&args) {
+        Ok(Command::Show(format)) => show(format),
+        Ok(Command::Diff { old, new, required }) => diff(&old, &new, required),
+        Ok(Command::Require {
+            features,
+            min_arch,
+            json,
+        }) => require(&features, min_arch, json),
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {err}\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aarch64_features::Feature;
+    use aarch64_features::Register;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    /// A temporary file that is removed on drop
+    struct TempFile {
+        path: PathBuf,
     }
 
-    #[cfg(target_arch = "aarch64")]
-    {
-        use aarch64_features::check_features;
-        use aarch64_features::midr::Midr;
-        use aarch64_features::midr::MidrEL1;
-        let features = check_features();
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            // tests run in parallel threads of the same process
+            static NEXT: AtomicUsize = AtomicUsize::new(0);
 
-        println!();
+            let path = std::env::temp_dir().join(format!(
+                "aarch64_features_{}_{}_{}",
+                std::process::id(),
+                NEXT.fetch_add(1, Ordering::Relaxed),
+                name
+            ));
+            TempFile { path }
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
 
-        println!("let features = vec![");
-        for feature in features {
-            println!("  {:?},", feature);
+    fn report() -> Report {
+        Report {
+            source: DetectionSource::IdRegisters.to_string(),
+            core: None,
+            sve_vector_length: Some(256),
+            supported_sve_vector_lengths: vec![128, 256],
+            supported_sme_vector_lengths: Vec::new(),
+            features: [Feature::FEAT_SVE, Feature::FEAT_LSE].into_iter().collect(),
+            midr: Some(Midr::from(0x413f_d0c1)),
+            registers: None,
         }
-        println!("];");
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse_args(&args(&[])), Ok(Command::Show(Format::Text)));
+        assert_eq!(
+            parse_args(&args(&["--format", "json"])),
+            Ok(Command::Show(Format::Json))
+        );
+        assert_eq!(
+            parse_args(&args(&["--format=toml"])),
+            Ok(Command::Show(Format::Toml))
+        );
+        assert_eq!(
+            parse_args(&args(&["--format", "yaml"])),
+            Ok(Command::Show(Format::Yaml))
+        );
+        assert_eq!(parse_args(&args(&["-h"])), Ok(Command::Help));
+        assert!(parse_args(&args(&["--format"])).is_err());
+        assert!(parse_args(&args(&["--format", "xml"])).is_err());
+        assert!(parse_args(&args(&["--verbose"])).is_err());
+    }
+
+    #[test]
+    fn test_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&report().render(Format::Json).unwrap()).unwrap();
+
+        assert_eq!(json["source"], "id-registers");
+        assert_eq!(json["core"], serde_json::Value::Null);
+        assert_eq!(json["sve_vector_length"], 256);
+        assert_eq!(json["supported_sve_vector_lengths"][1], 256);
+        assert_eq!(json["features"][0], "FEAT_LSE");
+        assert_eq!(json["features"][1], "FEAT_SVE");
+        assert_eq!(json["midr"]["part_num"], 0xd0c);
+    }
+
+    #[test]
+    fn test_yaml_and_toml() {
+        let report = report();
+
+        let yaml = report.render(Format::Yaml).unwrap();
+        assert!(yaml.contains("source: id-registers\n"));
+        assert!(yaml.contains("- FEAT_LSE\n"));
+
+        let toml = report.render(Format::Toml).unwrap();
+        assert!(toml.contains("source = \"id-registers\"\n"));
+        assert!(toml.contains("[midr]\n"));
+        assert!(!toml.contains("core"));
+    }
+
+    #[test]
+    fn test_rust_and_text() {
+        let report = report();
+
+        assert_eq!(
+            report.render(Format::Rust).unwrap(),
+            "let features = vec![\n    Feature::FEAT_LSE,\n    Feature::FEAT_SVE,\n];\n"
+        );
+
+        let text = report.render(Format::Text).unwrap();
+        assert!(text.contains("core: unknown\n"));
+        assert!(text.contains("sve vector length: 256 bits\n"));
+        assert!(text.contains("supported sve vector lengths: 128, 256 bits\n"));
+        assert!(text.contains("supported sme vector lengths: none\n"));
+        assert!(text.contains("features: 2\n  FEAT_LSE\n  FEAT_SVE\n"));
+        assert!(!text.contains("ID_AA64ISAR0_EL1"));
+
+        let mut registers = RegisterSnapshot::new();
+        registers.set(Register::ID_AA64ISAR0_EL1, 0x0021_0000);
+        registers.set(Register::CTR_EL0, 0);
+        let report = Report {
+            registers: Some(registers),
+            ..report
+        };
+
+        let text = report.render(Format::Text).unwrap();
+        assert!(text.contains("ID_AA64ISAR0_EL1 = 0x0000000000210000\n"));
+        assert!(text.contains("  Atomic       [23:20] 0b0010  Atomic instructions (FEAT_LSE)\n"));
+        assert!(!text.contains("CTR_EL0"));
+    }
+
+    #[test]
+    fn test_detect() {
+        let report = Report::detect();
+
+        assert!(report.render(Format::Json).is_ok());
+        assert!(report.render(Format::Yaml).is_ok());
+        assert!(report.render(Format::Toml).is_ok());
+    }
+
+    #[test]
+    fn test_parse_diff_args() {
+        assert_eq!(
+            parse_args(&args(&["diff", "a.json", "b.json"])),
+            Ok(Command::Diff {
+                old: PathBuf::from("a.json"),
+                new: PathBuf::from("b.json"),
+                required: None,
+            })
+        );
+        assert_eq!(
+            parse_args(&args(&[
+                "diff",
+                "--require",
+                "lse,FEAT_SVE",
+                "a.json",
+                "b.json",
+                "--require",
+                "feat_sve2"
+            ])),
+            Ok(Command::Diff {
+                old: PathBuf::from("a.json"),
+                new: PathBuf::from("b.json"),
+                required: Some(
+                    [Feature::FEAT_LSE, Feature::FEAT_SVE, Feature::FEAT_SVE2]
+                        .into_iter()
+                        .collect()
+                ),
+            })
+        );
+        assert!(parse_args(&args(&["diff", "a.json"])).is_err());
+        assert!(parse_args(&args(&["diff", "a.json", "b.json", "c.json"])).is_err());
+        assert!(parse_args(&args(&["diff", "--require", "FEAT_FOO", "a", "b"])).is_err());
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = report();
+        let mut new = report();
+        new.core = Some(Core::NeoverseV2);
+        new.midr = Some(Midr::from(0x410f_d4f0));
+        new.features = [
+            Feature::FEAT_LSE,
+            Feature::FEAT_SVE2,
+            Feature::FEAT_SVE_BitPerm,
+            Feature::FEAT_SHA3,
+        ]
+        .into_iter()
+        .collect();
+
+        let diff = Diff::new(&old, &new);
+
+        assert_eq!(diff.removed, [Feature::FEAT_SVE].into_iter().collect());
+        assert_eq!(diff.added.len(), 3);
+
+        let text = diff.render((Path::new("old.json"), &old), (Path::new("new.json"), &new));
+        assert!(text.starts_with("--- old.json: unknown core\n+++ new.json: NeoverseV2\n"));
+        assert!(text.contains("part_num: 0xd4f"));
+        assert!(text.contains(
+            "added features: 3\n  Crypto\n    Armv8.2: FEAT_SHA3\n  Sve\n    Armv9.0: FEAT_SVE2 FEAT_SVE_BitPerm\n"
+        ));
+        assert!(text.contains("removed features: 1\n  Sve\n    Armv8.2: FEAT_SVE\n"));
+    }
+
+    #[test]
+    fn test_load() {
+        let file = TempFile::new("report.json");
+
+        let mut report = report();
+        let mut registers = RegisterSnapshot::new();
+        registers.set(aarch64_features::Register::ID_AA64ISAR0_EL1, 0x0021_0000);
+        report.registers = Some(registers.clone());
+        std::fs::write(&file.path, report.render(Format::Json).unwrap()).unwrap();
+
+        let loaded = Report::load(&file.path).unwrap();
+        assert_eq!(loaded.features, report.features);
+        assert_eq!(loaded.registers, Some(registers));
+
+        // only the features are required
+        let minimal: Report = serde_json::from_str(r#"{"features": ["FEAT_LSE"]}"#).unwrap();
+        assert_eq!(minimal.features.len(), 1);
+        assert!(Report::load(Path::new("/nonexistent/report.json")).is_err());
+    }
+
+    #[test]
+    fn test_parse_require_args() {
+        assert_eq!(
+            parse_args(&args(&[
+                "require",
+                "FEAT_LSE",
+                "sve2",
+                "--min-arch",
+                "armv8.4-a",
+                "--json"
+            ])),
+            Ok(Command::Require {
+                features: [Feature::FEAT_LSE, Feature::FEAT_SVE2]
+                    .into_iter()
+                    .collect(),
+                min_arch: Some(ArchProfile::Armv8_4A),
+                json: true,
+            })
+        );
+        assert_eq!(
+            parse_args(&args(&["require", "--min-arch=armv9-a"])),
+            Ok(Command::Require {
+                features: FeatureSet::new(),
+                min_arch: Some(ArchProfile::Armv9_0A),
+                json: false,
+            })
+        );
+        assert!(parse_args(&args(&["require"])).is_err());
+        assert!(parse_args(&args(&["require", "FEAT_FOO"])).is_err());
+        assert!(parse_args(&args(&["require", "--min-arch", "armv7-a"])).is_err());
+        assert!(parse_args(&args(&["require", "FEAT_LSE", "--verbose"])).is_err());
+    }
+
+    #[test]
+    fn test_requirements() {
+        let features: FeatureSet = ArchProfile::Armv8_2A.mandatory().into_iter().collect();
+
+        let satisfied = Requirements::check(
+            &features,
+            &[Feature::FEAT_LSE].into_iter().collect(),
+            Some(ArchProfile::Armv8_1A),
+        );
+        assert!(satisfied.satisfied);
+        assert_eq!(satisfied.render_text(), "all requirements are satisfied\n");
+
+        let missing = Requirements::check(
+            &features,
+            &[Feature::FEAT_LSE, Feature::FEAT_SVE2]
+                .into_iter()
+                .collect(),
+            Some(ArchProfile::Armv8_4A),
+        );
+        assert!(!missing.satisfied);
+        assert!(missing.missing.contains(Feature::FEAT_SVE2));
+        assert!(missing.missing.contains(Feature::FEAT_LRCPC2));
+        assert!(!missing.missing.contains(Feature::FEAT_LSE));
+        assert_eq!(missing.highest_arch.as_deref(), Some("armv8.2-a"));
+
+        let text = missing.render_text();
+        assert!(text.contains("  FEAT_SVE2: "));
+        assert!(text.ends_with("required level: armv8.4-a, highest satisfied level: armv8.2-a\n"));
+
+        let json = serde_json::to_value(&missing).unwrap();
+        assert_eq!(json["satisfied"], false);
+        assert_eq!(json["min_arch"], "armv8.4-a");
+    }
 
-        println!();
+    #[test]
+    fn test_requirements_on_host() {
+        // the Features line of /proc/cpuinfo does not depend on the
+        // features that the MRS emulation exports
+        let Some(cpu) = aarch64_features::cpuinfo::read()
+            .ok()
+            .and_then(|cpus| cpus.into_iter().next())
+        else {
+            return;
+        };
+        let Some(level) = ArchProfile::Armv8_0A
+            .check(cpu.features())
+            .highest_satisfied()
+        else {
+            return;
+        };
 
-        let midr_el1 = MidrEL1::new();
+        let features = FeatureSet::from(check_features());
+        let required: FeatureSet = level.mandatory().into_iter().collect();
+        let requirements = Requirements::check(&features, &required, Some(level));
 
-        let _core = aarch64_features::cpu_type::Core::try_from(midr_el1).unwrap();
+        assert!(requirements.satisfied, "{}", requirements.render_text());
     }
 }
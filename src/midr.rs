@@ -1,7 +1,9 @@
+use crate::errata::{errata_of, Erratum, Revision};
 use std::fmt;
 
 #[non_exhaustive]
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) enum Implementer {
     Arm = 0x41,
     Fujitsu = 0x46,
@@ -26,6 +28,8 @@ impl TryFrom<u64> for Implementer {
             Ok(Implementer::Apple)
         } else if value == 0xc0 {
             Ok(Implementer::Ampere)
+        } else if value == 0x6d {
+            Ok(Implementer::Microsoft)
         } else {
             Err("unknown implementer")
         }
@@ -61,6 +65,7 @@ impl fmt::Display for Implementer {
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) enum Architecture {
     Armv4 = 0x01,
     Armv4T = 0x02,
@@ -156,6 +161,7 @@ impl Default for MidrEL1 {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A MIDR_EL1 register
 pub struct Midr {
     implementer: Implementer,
@@ -172,6 +178,7 @@ impl Midr {
         {
             let mut midr: u64;
             use std::arch::asm;
+            // SAFETY: reading MIDR_EL1 has no side effects
             unsafe {
                 asm!("mrs {midr}, MIDR_EL1", midr = out(reg) midr);
             }
@@ -214,6 +221,7 @@ impl Midr {
     //        self.implementer == Implementer::Apple
     //    }
     //
+    #[cfg(test)]
     pub(crate) fn check_variant(&self, im: u64) -> bool {
         self.variant == im
     }
@@ -234,6 +242,16 @@ impl Midr {
     //        self.revision == im
     //    }
 
+    pub(crate) fn revision(&self) -> Revision {
+        Revision::new(self.variant, self.revision)
+    }
+
+    /// The known errata that affect this core and revision, see
+    /// [`errata`](crate::errata)
+    pub fn applicable_errata(&self) -> Vec<&'static Erratum> {
+        errata_of(self)
+    }
+
     #[allow(unused)]
     pub(crate) fn dump(&self) {
         println!("implementer : {}", self.implementer);
@@ -244,6 +262,23 @@ impl Midr {
     }
 }
 
+impl fmt::Display for Midr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "implementer: {}, variant: {:#x}, architecture: {}, part_num: {:#x}, revision: {:#x}",
+            self.implementer, self.variant, self.architecture, self.part_num, self.revision
+        )
+    }
+}
+
+impl From<u64> for Midr {
+    /// Decode the raw value of a MIDR_EL1 register
+    fn from(midr: u64) -> Self {
+        Self::extract_parts(midr)
+    }
+}
+
 impl Default for Midr {
     fn default() -> Self {
         Self::new()
@@ -251,16 +286,17 @@ impl Default for Midr {
 }
 
 const MIDR_IMPLEMENTOR_SHIFT: u64 = 24;
-const MIDR_VARIANT_SHIFT: u64 = 24;
-const MIDR_ARCHITECTURE_SHIFT: u64 = 24;
+const MIDR_VARIANT_SHIFT: u64 = 20;
+const MIDR_ARCHITECTURE_SHIFT: u64 = 16;
 const MIDR_PART_NUM_SHIFT: u64 = 4;
 const MIDR_REVISION_SHIFT: u64 = 0;
 
+// the masks apply after shifting
 const MIDR_REVISION_MASK: u64 = 0xf;
-const MIDR_PART_NUM_MASK: u64 = 0xfff << MIDR_PART_NUM_SHIFT;
-const MIDR_ARCHITECTURE_MASK: u64 = 0xf << MIDR_ARCHITECTURE_SHIFT;
-const MIDR_VARIANT_MASK: u64 = 0xf << MIDR_VARIANT_SHIFT;
-const MIDR_IMPLEMENTOR_MASK: u64 = 0xff << MIDR_IMPLEMENTOR_SHIFT;
+const MIDR_PART_NUM_MASK: u64 = 0xfff;
+const MIDR_ARCHITECTURE_MASK: u64 = 0xf;
+const MIDR_VARIANT_MASK: u64 = 0xf;
+const MIDR_IMPLEMENTOR_MASK: u64 = 0xff;
 
 fn extract(midr: u64, shift: u64, mask: u64) -> u64 {
     (midr >> shift) & mask
@@ -334,6 +370,22 @@ mod tests {
         assert!(!midr.check_implementer(Implementer::Ampere));
     }
 
+    #[test]
+    fn test_extract_parts() {
+        // Neoverse N1 r3p1
+        let midr = Midr::from(0x413f_d0c1);
+
+        assert!(midr.check_implementer(Implementer::Arm));
+        assert!(midr.check_variant(0x3));
+        assert!(midr.check_part_num(0xd0c));
+        assert_eq!(midr.architecture, Architecture::IDRegisters);
+        assert_eq!(midr.revision, 0x1);
+        assert_eq!(
+            midr.to_string(),
+            "implementer: Arm, variant: 0x3, architecture: ID Registers, part_num: 0xd0c, revision: 0x1"
+        );
+    }
+
     #[test]
     fn test_variant() {
         let midr = MidrBuilder::new().variant(0x7).build();
@@ -344,4 +396,21 @@ mod tests {
 
         assert!(midr.check_variant(0x0));
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let midr = Midr::from(0x413f_d0c1);
+
+        let json = serde_json::to_string(&midr).unwrap();
+        assert_eq!(
+            json,
+            r#"{"implementer":"Arm","variant":3,"architecture":"IDRegisters","part_num":3340,"revision":1}"#
+        );
+
+        let midr: Midr = serde_json::from_str(&json).unwrap();
+        assert!(midr.check_implementer(Implementer::Arm));
+        assert!(midr.check_part_num(0xd0c));
+        assert!(serde_json::from_str::<Midr>(r#"{"implementer":"Foo"}"#).is_err());
+    }
 }
@@ -0,0 +1,462 @@
+use crate::{ArmVersion, Feature};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+use strum_macros::{EnumCount, EnumIter};
+
+/// The A-profile levels of the Arm architecture
+///
+/// The tables of mandatory and optional features only list features
+/// that are visible at EL0, i.e., features of the instruction set.
+/// System features, e.g., FEAT_VHE or FEAT_TTL, cannot be detected
+/// through the `MRS` emulation of the kernels and would render every
+/// check non-compliant.
+///
+/// ```rust
+/// use aarch64_features::{ArchProfile, Feature};
+/// use std::collections::HashSet;
+///
+/// let features = HashSet::from([Feature::FEAT_AdvSIMD, Feature::FEAT_LSE, Feature::FEAT_RDM]);
+///
+/// let report = ArchProfile::Armv8_4A.check(&features);
+///
+/// assert!(!report.is_compliant());
+/// assert!(report.missing().contains(&Feature::FEAT_LRCPC2));
+/// assert_eq!(report.highest_satisfied(), Some(ArchProfile::Armv8_1A));
+/// ```
+#[allow(non_camel_case_types)]
+#[derive(EnumIter, EnumCount, Debug, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[non_exhaustive]
+pub enum ArchProfile {
+    /// Armv8.0-A
+    Armv8_0A,
+    /// Armv8.1-A
+    Armv8_1A,
+    /// Armv8.2-A
+    Armv8_2A,
+    /// Armv8.3-A
+    Armv8_3A,
+    /// Armv8.4-A
+    Armv8_4A,
+    /// Armv8.5-A
+    Armv8_5A,
+    /// Armv8.6-A
+    Armv8_6A,
+    /// Armv8.7-A
+    Armv8_7A,
+    /// Armv8.8-A
+    Armv8_8A,
+    /// Armv8.9-A
+    Armv8_9A,
+    /// Armv9.0-A, which extends Armv8.5-A
+    Armv9_0A,
+    /// Armv9.1-A, which extends Armv8.6-A
+    Armv9_1A,
+    /// Armv9.2-A, which extends Armv8.7-A
+    Armv9_2A,
+    /// Armv9.3-A, which extends Armv8.8-A
+    Armv9_3A,
+    /// Armv9.4-A, which extends Armv8.9-A
+    Armv9_4A,
+    /// Armv9.5-A
+    Armv9_5A,
+    /// Armv9.6-A
+    Armv9_6A,
+}
+
+impl ArchProfile {
+    /// Check a set of features against the mandatory features of this
+    /// level and of all levels that it extends
+    pub fn check(&self, features: &HashSet<Feature>) -> ComplianceReport {
+        let mandatory = self.mandatory();
+
+        let missing = sorted(mandatory.iter().filter(|feat| !features.contains(feat)));
+
+        let optional_present = sorted(
+            self.ancestors()
+                .iter()
+                .flat_map(|profile| profile.description().optional)
+                .filter(|feat| features.contains(feat) && !mandatory.contains(feat)),
+        );
+
+        let highest_satisfied = ArchProfile::iter()
+            .filter(|profile| profile.mandatory().is_subset(features))
+            .max();
+
+        ComplianceReport {
+            profile: *self,
+            missing,
+            highest_satisfied,
+            optional_present,
+        }
+    }
+
+    /// The mandatory features of this level, including the levels that
+    /// it extends
+    pub fn mandatory(&self) -> HashSet<Feature> {
+        self.ancestors()
+            .iter()
+            .flat_map(|profile| profile.description().mandatory)
+            .copied()
+            .collect()
+    }
+
+    /// The version of the architecture of this level
+    pub fn version(&self) -> ArmVersion {
+        self.description().version
+    }
+
+    /// The name of the level for `-march`, e.g., `armv8.4-a`
+    pub fn name(&self) -> &'static str {
+        self.description().name
+    }
+
+    /// this level and all levels that it extends
+    pub(crate) fn ancestors(&self) -> Vec<ArchProfile> {
+        let mut ancestors = vec![*self];
+        let mut idx = 0;
+
+        while let Some(profile) = ancestors.get(idx) {
+            for parent in profile.description().extends {
+                if !ancestors.contains(parent) {
+                    ancestors.push(*parent);
+                }
+            }
+            idx += 1;
+        }
+
+        ancestors
+    }
+
+    fn description(&self) -> &'static ProfileDescription {
+        ARCH_PROFILES
+            .iter()
+            .find(|desc| desc.profile == *self)
+            .expect("every profile has a description")
+    }
+}
+
+impl From<ArmVersion> for ArchProfile {
+    fn from(version: ArmVersion) -> Self {
+        ARCH_PROFILES
+            .iter()
+            .find(|desc| desc.version == version)
+            .map(|desc| desc.profile)
+            .expect("every version has a profile")
+    }
+}
+
+/// Parse the name of a level, see [`ArchProfile::name`]. The name is
+/// case-insensitive and `armv8.0-a` and `armv9.0-a` are accepted.
+///
+/// ```rust
+/// use aarch64_features::ArchProfile;
+///
+/// assert_eq!("armv8.4-a".parse(), Ok(ArchProfile::Armv8_4A));
+/// assert_eq!("ARMv9.0-A".parse(), Ok(ArchProfile::Armv9_0A));
+/// ```
+impl FromStr for ArchProfile {
+    type Err = &'static str;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let name = name.trim().to_ascii_lowercase();
+        let name = match name.as_str() {
+            "armv8.0-a" => "armv8-a",
+            "armv9.0-a" => "armv9-a",
+            name => name,
+        };
+
+        ARCH_PROFILES
+            .iter()
+            .find(|desc| desc.name == name)
+            .map(|desc| desc.profile)
+            .ok_or("unknown architecture profile")
+    }
+}
+
+impl fmt::Display for ArchProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The result of [`ArchProfile::check`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComplianceReport {
+    profile: ArchProfile,
+    missing: Vec<Feature>,
+    highest_satisfied: Option<ArchProfile>,
+    optional_present: Vec<Feature>,
+}
+
+impl ComplianceReport {
+    /// The checked level
+    pub fn profile(&self) -> ArchProfile {
+        self.profile
+    }
+
+    /// Whether all mandatory features of the level are present
+    pub fn is_compliant(&self) -> bool {
+        self.missing.is_empty()
+    }
+
+    /// The mandatory features of the level that are missing, sorted
+    /// like [`Feature`]
+    pub fn missing(&self) -> &[Feature] {
+        &self.missing
+    }
+
+    /// The highest level whose mandatory features are all present
+    pub fn highest_satisfied(&self) -> Option<ArchProfile> {
+        self.highest_satisfied
+    }
+
+    /// The optional features of the level that are present, sorted
+    /// like [`Feature`]
+    pub fn optional_present(&self) -> &[Feature] {
+        &self.optional_present
+    }
+}
+
+fn sorted<'a>(features: impl Iterator<Item = &'a Feature>) -> Vec<Feature> {
+    let mut features: Vec<Feature> = features.copied().collect();
+
+    features.sort();
+    features.dedup();
+
+    features
+}
+
+struct ProfileDescription {
+    profile: ArchProfile,
+    version: ArmVersion,
+    name: &'static str,
+    extends: &'static [ArchProfile],
+    mandatory: &'static [Feature],
+    optional: &'static [Feature],
+}
+
+macro_rules! declare_profiles {
+    ($(
+        ($profile:ident, $version:ident, $name:expr, [$($extends:ident),*], [$($mandatory:ident),*], [$($optional:ident),*]),
+    )+) => {
+        /// The mandatory and optional features of the levels from the ARMARM
+        const ARCH_PROFILES: &[ProfileDescription] = &[
+            $(
+                ProfileDescription {
+                    profile: ArchProfile::$profile,
+                    version: ArmVersion::$version,
+                    name: $name,
+                    extends: &[$(ArchProfile::$extends),*],
+                    mandatory: &[$(Feature::$mandatory),*],
+                    optional: &[$(Feature::$optional),*],
+                }
+            ),+
+        ];
+    }
+}
+
+#[rustfmt::skip]
+declare_profiles!(
+    (Armv8_0A, Armv8_0, "armv8-a",   [],
+        [FEAT_AdvSIMD],
+        [FEAT_AES, FEAT_PMULL, FEAT_SHA1, FEAT_SHA256, FEAT_SB, FEAT_SSBS, FEAT_SPECRES, FEAT_DGH, FEAT_CSV2, FEAT_CSV3]),
+    (Armv8_1A, Armv8_1, "armv8.1-a", [Armv8_0A],
+        [FEAT_LSE, FEAT_RDM],
+        []),
+    (Armv8_2A, Armv8_2, "armv8.2-a", [Armv8_1A],
+        [FEAT_DPB],
+        [FEAT_FP16, FEAT_DotProd, FEAT_FHM, FEAT_SHA512, FEAT_SHA3, FEAT_SM3, FEAT_SM4, FEAT_BF16, FEAT_I8MM,
+         FEAT_DPB2, FEAT_SVE, FEAT_F32MM, FEAT_F64MM]),
+    (Armv8_3A, Armv8_3, "armv8.3-a", [Armv8_2A],
+        [FEAT_FCMA, FEAT_JSCVT, FEAT_LRCPC, FEAT_PAuth],
+        [FEAT_PAuth2, FEAT_FPAC, FEAT_FPACCOMBINE, FEAT_PACQARMA3]),
+    (Armv8_4A, Armv8_4, "armv8.4-a", [Armv8_3A],
+        [FEAT_DIT, FEAT_FlagM, FEAT_LRCPC2, FEAT_LSE2, FEAT_DotProd],
+        []),
+    // FEAT_CSV2 and FEAT_CSV3 are not visible at EL0
+    (Armv8_5A, Armv8_5, "armv8.5-a", [Armv8_4A],
+        [FEAT_FlagM2, FEAT_FRINTTS, FEAT_SB, FEAT_SSBS, FEAT_SPECRES, FEAT_DPB2, FEAT_BTI],
+        [FEAT_RNG, FEAT_MTE, FEAT_MTE2, FEAT_MTE3]),
+    (Armv8_6A, Armv8_6, "armv8.6-a", [Armv8_5A],
+        [FEAT_BF16, FEAT_I8MM, FEAT_PAuth2],
+        []),
+    (Armv8_7A, Armv8_7, "armv8.7-a", [Armv8_6A],
+        [FEAT_WFxT, FEAT_AFP, FEAT_RPRES],
+        [FEAT_LS64, FEAT_LS64_V, FEAT_LS64_ACCDATA]),
+    (Armv8_8A, Armv8_8, "armv8.8-a", [Armv8_7A],
+        [FEAT_MOPS, FEAT_HBC],
+        []),
+    (Armv8_9A, Armv8_9, "armv8.9-a", [Armv8_8A],
+        [FEAT_CSSC],
+        [FEAT_LRCPC3]),
+    (Armv9_0A, Armv9_0, "armv9-a",   [Armv8_5A],
+        [FEAT_SVE, FEAT_SVE2],
+        [FEAT_SVE_AES, FEAT_SVE_PMULL128, FEAT_SVE_BitPerm, FEAT_SVE_SHA3, FEAT_SVE_SM4]),
+    (Armv9_1A, Armv9_1, "armv9.1-a", [Armv9_0A, Armv8_6A],
+        [],
+        []),
+    (Armv9_2A, Armv9_2, "armv9.2-a", [Armv9_1A, Armv8_7A],
+        [],
+        [FEAT_SME, FEAT_SME_FA64, FEAT_SME_F64F64, FEAT_SME_I16I64, FEAT_EBF16]),
+    (Armv9_3A, Armv9_3, "armv9.3-a", [Armv9_2A, Armv8_8A],
+        [],
+        [FEAT_SME2]),
+    (Armv9_4A, Armv9_4, "armv9.4-a", [Armv9_3A, Armv8_9A],
+        [],
+        [FEAT_SVE2p1, FEAT_LSE128]),
+    (Armv9_5A, Armv9_5, "armv9.5-a", [Armv9_4A],
+        [FEAT_CPA, FEAT_FAMINMAX, FEAT_LUT],
+        []),
+    // FEAT_LSUI and FEAT_OCCMO are not visible at EL0
+    (Armv9_6A, Armv9_6, "armv9.6-a", [Armv9_5A],
+        [FEAT_CMPBR, FEAT_FPRCVT],
+        [FEAT_SVE2p2, FEAT_LSFE, FEAT_SME_TMOP]),
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::EnumCount;
+
+    /// the features of a Neoverse N1 as reported by Linux
+    const NEOVERSE_N1: &[Feature] = &[
+        Feature::FEAT_AdvSIMD,
+        Feature::FEAT_AES,
+        Feature::FEAT_PMULL,
+        Feature::FEAT_SHA1,
+        Feature::FEAT_SHA256,
+        Feature::FEAT_LSE,
+        Feature::FEAT_RDM,
+        Feature::FEAT_DPB,
+        Feature::FEAT_FP16,
+        Feature::FEAT_DotProd,
+        Feature::FEAT_LRCPC,
+        Feature::FEAT_SSBS,
+    ];
+
+    fn set(features: &[Feature]) -> HashSet<Feature> {
+        features.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_neoverse_n1() {
+        let features = set(NEOVERSE_N1);
+
+        let report = ArchProfile::Armv8_2A.check(&features);
+        assert!(report.is_compliant());
+        assert_eq!(report.profile(), ArchProfile::Armv8_2A);
+        assert_eq!(report.highest_satisfied(), Some(ArchProfile::Armv8_2A));
+        assert!(report.optional_present().contains(&Feature::FEAT_DotProd));
+        assert!(report.optional_present().contains(&Feature::FEAT_SSBS));
+
+        // no FEAT_PAuth, FEAT_FCMA, and FEAT_JSCVT
+        let report = ArchProfile::Armv8_3A.check(&features);
+        assert!(!report.is_compliant());
+        assert_eq!(
+            set(report.missing()),
+            set(&[Feature::FEAT_FCMA, Feature::FEAT_JSCVT, Feature::FEAT_PAuth])
+        );
+    }
+
+    #[test]
+    fn test_mandatory_is_not_optional() {
+        let features = ArchProfile::Armv8_5A.mandatory();
+
+        let report = ArchProfile::Armv8_5A.check(&features);
+
+        assert!(report.is_compliant());
+        assert!(report.optional_present().is_empty());
+        assert_eq!(report.highest_satisfied(), Some(ArchProfile::Armv8_5A));
+    }
+
+    #[test]
+    fn test_armv9() {
+        let mut features = ArchProfile::Armv8_9A.mandatory();
+        assert_eq!(
+            set(ArchProfile::Armv9_0A.check(&features).missing()),
+            set(&[Feature::FEAT_SVE, Feature::FEAT_SVE2])
+        );
+        assert_eq!(
+            ArchProfile::Armv9_0A.check(&features).highest_satisfied(),
+            Some(ArchProfile::Armv8_9A)
+        );
+
+        features.extend([Feature::FEAT_SVE, Feature::FEAT_SVE2]);
+        let report = ArchProfile::Armv9_4A.check(&features);
+        assert!(report.is_compliant());
+        // no FEAT_CPA, FEAT_FAMINMAX, and FEAT_LUT
+        assert_eq!(report.highest_satisfied(), Some(ArchProfile::Armv9_4A));
+        assert_eq!(
+            set(ArchProfile::Armv9_5A.check(&features).missing()),
+            set(&[Feature::FEAT_CPA, Feature::FEAT_FAMINMAX, Feature::FEAT_LUT])
+        );
+
+        features.extend([Feature::FEAT_CPA, Feature::FEAT_FAMINMAX, Feature::FEAT_LUT]);
+        let report = ArchProfile::Armv9_4A.check(&features);
+        assert_eq!(report.highest_satisfied(), Some(ArchProfile::Armv9_5A));
+
+        features.extend([Feature::FEAT_CMPBR, Feature::FEAT_FPRCVT]);
+        let report = ArchProfile::Armv9_4A.check(&features);
+        assert_eq!(report.highest_satisfied(), Some(ArchProfile::Armv9_6A));
+    }
+
+    #[test]
+    fn test_every_level_adds_requirements() {
+        // otherwise, highest_satisfied climbs past the level for free.
+        // Armv9.1-A to Armv9.3-A add the requirements of the Armv8.x-A
+        // levels that they extend.
+        for profile in ArchProfile::iter() {
+            for parent in profile.description().extends {
+                assert!(
+                    parent.mandatory().len() < profile.mandatory().len(),
+                    "{profile} {parent}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_levels_are_cumulative() {
+        let profiles: Vec<ArchProfile> = ArchProfile::iter().collect();
+
+        for pair in profiles.windows(2) {
+            // Armv9.0-A extends Armv8.5-A
+            if pair[1] == ArchProfile::Armv9_0A {
+                continue;
+            }
+            assert!(
+                pair[0].mandatory().is_subset(&pair[1].mandatory()),
+                "{} {}",
+                pair[0],
+                pair[1]
+            );
+        }
+    }
+
+    #[test]
+    fn test_versions() {
+        assert_eq!(ARCH_PROFILES.len(), ArchProfile::COUNT);
+        for profile in ArchProfile::iter() {
+            assert_eq!(ArchProfile::from(profile.version()), profile);
+        }
+        assert_eq!(ArchProfile::Armv8_4A.to_string(), "armv8.4-a");
+        assert_eq!(ArchProfile::Armv9_0A.name(), "armv9-a");
+        assert!(ArchProfile::Armv8_0A
+            .check(&HashSet::new())
+            .highest_satisfied()
+            .is_none());
+    }
+
+    #[test]
+    fn test_parse() {
+        for profile in ArchProfile::iter() {
+            assert_eq!(profile.name().parse(), Ok(profile));
+        }
+        assert_eq!("armv8.0-a".parse(), Ok(ArchProfile::Armv8_0A));
+        assert_eq!(" Armv9.2-A\n".parse(), Ok(ArchProfile::Armv9_2A));
+        assert!("armv8.4".parse::<ArchProfile>().is_err());
+        assert!("armv7-a".parse::<ArchProfile>().is_err());
+    }
+}
@@ -1,68 +1,266 @@
 use crate::aarch64::read_register;
+use std::fmt;
 use std::ops::Index;
+use std::str::FromStr;
+use strum::{EnumCount, IntoEnumIterator};
+use strum_macros::{EnumCount, EnumIter};
 
 #[allow(non_camel_case_types)]
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Eq, PartialEq, Hash, Copy, Clone)]
-pub(crate) enum Register {
+#[allow(clippy::enum_variant_names)]
+#[derive(EnumIter, EnumCount, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone)]
+#[non_exhaustive]
+/// AArch64 registers that describe the features of a core
+pub enum Register {
+    /// AArch64 Debug Feature Register 0
     ID_AA64DFR0_EL1,
-    // ID_AA64DFR1_EL1,
+    /// AArch64 Debug Feature Register 1
+    ID_AA64DFR1_EL1,
+    /// AArch64 Instruction Set Attribute Register 0
     ID_AA64ISAR0_EL1,
+    /// AArch64 Instruction Set Attribute Register 1
     ID_AA64ISAR1_EL1,
+    /// AArch64 Memory Model Feature Register 0
     ID_AA64MMFR0_EL1,
+    /// AArch64 Memory Model Feature Register 1
     ID_AA64MMFR1_EL1,
+    /// AArch64 Memory Model Feature Register 2
     ID_AA64MMFR2_EL1,
+    /// AArch64 Processor Feature Register 0
     ID_AA64PFR0_EL1,
+    /// AArch64 Processor Feature Register 1
     ID_AA64PFR1_EL1,
+    /// AArch64 Instruction Set Attribute Register 3
     ID_AA64ISAR3_EL1,
     //ID_AA64ZFR0_EL1,
+    /// External Debug Device ID register 0
     EDDEVID,
+    /// Cache Type Register
     CTR_EL0,
+    /// SME Feature ID Register 0
     ID_AA64SMFR0_EL1,
+    /// Counter Identification Register
     CNTID,
+    /// AArch64 Instruction Set Attribute Register 2
     ID_AA64ISAR2_EL1,
+    /// Trace Device Architecture Register
     TRCDEVARCH,
+    /// SVE Feature ID Register 0
     ID_AA64ZFR0_EL1,
+    /// AArch64 Memory Model Feature Register 3
+    ID_AA64MMFR3_EL1,
+    /// AArch64 Memory Model Feature Register 4
+    ID_AA64MMFR4_EL1,
+    /// AArch64 Processor Feature Register 2
+    ID_AA64PFR2_EL1,
+    /// AArch64 Floating-point Feature Register 0
+    ID_AA64FPFR0_EL1,
+    /// Placeholder for features without a register
     NoRegister,
 }
 
+/// A snapshot of the values of all [`Register`]s of a core.
+///
+/// The values can come from the current core or from a dump of a
+/// different machine. Registers that could not be read are unknown,
+/// and features depending on them are never reported.
+///
+/// ```rust
+/// use aarch64_features::{features_from_snapshot, Feature, Register, RegisterSnapshot};
+///
+/// let mut snapshot = RegisterSnapshot::new();
+/// // Atomics, bits [23:20]
+/// snapshot.set(Register::ID_AA64ISAR0_EL1, 0b0010 << 20);
+///
+/// let features = features_from_snapshot(&snapshot);
+/// assert!(features.contains(&Feature::FEAT_LSE));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RegisterSnapshot {
+    values: [Option<u64>; Register::COUNT],
+}
+
+impl RegisterSnapshot {
+    /// Create a snapshot where every register is unknown
+    pub fn new() -> Self {
+        RegisterSnapshot {
+            values: [None; Register::COUNT],
+        }
+    }
+
+    /// Create a snapshot from the registers of the current core. The
+    /// registers are only read if the OS emulates `MRS` on them, e.g.,
+    /// Linux with `HWCAP_CPUID`. Otherwise, e.g., on macOS, Windows, or
+    /// other architectures, every register is unknown.
+    pub fn read() -> Self {
+        if !crate::check_availability() {
+            return RegisterSnapshot::new();
+        }
+
+        RegisterSnapshot::from(&RegistersInfo::new())
+    }
+
+    /// Set the value of a register
+    pub fn set(&mut self, register: Register, value: u64) {
+        if register != Register::NoRegister {
+            self.values[Self::index_of(register)] = Some(value);
+        }
+    }
+
+    /// Mark a register as unknown, e.g., because it could not be read
+    pub fn set_unknown(&mut self, register: Register) {
+        self.values[Self::index_of(register)] = None;
+    }
+
+    /// The value of a register, if it is known
+    pub fn get(&self, register: Register) -> Option<u64> {
+        self.values[Self::index_of(register)]
+    }
+
+    /// Iterate over all known registers and their values
+    pub fn iter(&self) -> impl Iterator<Item = (Register, u64)> + '_ {
+        Register::iter().filter_map(|register| self.get(register).map(|value| (register, value)))
+    }
+
+    #[allow(clippy::as_conversions)]
+    fn index_of(register: Register) -> usize {
+        register as usize
+    }
+}
+
+impl Default for RegisterSnapshot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One `REGISTER=0xVALUE` line per known register, which
+/// [`RegisterSnapshot::from_str`] parses
+impl fmt::Display for RegisterSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (register, value) in self.iter() {
+            writeln!(f, "{register:?}={value:#018x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a dump with one `REGISTER=VALUE` line per register, e.g.,
+/// `ID_AA64ISAR0_EL1=0x0000000000210000`. Values are hexadecimal with
+/// a `0x` prefix or decimal. Empty lines and lines starting with `#`
+/// are ignored. Missing registers are unknown.
+impl FromStr for RegisterSnapshot {
+    type Err = &'static str;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut snapshot = RegisterSnapshot::new();
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (name, value) = line.split_once('=').ok_or("expected REGISTER=VALUE")?;
+            let (name, value) = (name.trim(), value.trim());
+
+            let register = Register::iter()
+                .filter(|register| *register != Register::NoRegister)
+                .find(|register| format!("{register:?}") == name)
+                .ok_or("unknown register")?;
+
+            let value = match value.strip_prefix("0x") {
+                Some(hex) => u64::from_str_radix(hex, 16),
+                None => value.parse::<u64>(),
+            }
+            .map_err(|_| "invalid register value")?;
+
+            snapshot.set(register, value);
+        }
+
+        Ok(snapshot)
+    }
+}
+
+/// A map from the names of the known registers to their values as
+/// hexadecimal strings, e.g., `"ID_AA64ISAR0_EL1": "0x0000000000210000"`
+#[cfg(feature = "serde")]
+impl serde::Serialize for RegisterSnapshot {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.iter()
+                .map(|(register, value)| (format!("{register:?}"), format!("{value:#018x}"))),
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RegisterSnapshot {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let registers = std::collections::BTreeMap::<String, String>::deserialize(deserializer)?;
+
+        let text: String = registers
+            .iter()
+            .map(|(register, value)| format!("{register}={value}\n"))
+            .collect();
+
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<&RegistersInfo> for RegisterSnapshot {
+    fn from(info: &RegistersInfo) -> Self {
+        let mut snapshot = RegisterSnapshot::new();
+        for register in Register::iter() {
+            if let Some(value) = info[register] {
+                snapshot.set(register, value);
+            }
+        }
+        snapshot
+    }
+}
+
+/// The registers of the current core, `None` for registers that are not
+/// readable from userspace
 pub(crate) struct RegistersInfo {
-    id_aa64dfr0: u64,
-    //id_aa64dfr1: u64,
-    id_aa64isar0: u64,
-    id_aa64isar1: u64,
-    id_aa64mmfr0: u64,
-    id_aa64mmfr1: u64,
-    id_aa64mmfr2: u64,
-    id_aa64pfr0: u64,
-    id_aa64pfr1: u64,
-    id_aa64isar3_el1: u64,
-    //id_aa64zfr0: u64,
-    //zcr: u64,
-    ctr_el0: u64,
-    eddevid: u64,
-    id_aa64smfr0_el1: u64,
-    cntid: u64,
-    id_aa64isar2_el1: u64,
-    trcdevarch: u64,
-    id_aa64zfr0: u64,
+    id_aa64dfr0: Option<u64>,
+    id_aa64dfr1: Option<u64>,
+    id_aa64isar0: Option<u64>,
+    id_aa64isar1: Option<u64>,
+    id_aa64mmfr0: Option<u64>,
+    id_aa64mmfr1: Option<u64>,
+    id_aa64mmfr2: Option<u64>,
+    id_aa64mmfr3: Option<u64>,
+    id_aa64mmfr4: Option<u64>,
+    id_aa64pfr0: Option<u64>,
+    id_aa64pfr1: Option<u64>,
+    id_aa64pfr2: Option<u64>,
+    id_aa64isar3_el1: Option<u64>,
+    ctr_el0: Option<u64>,
+    eddevid: Option<u64>,
+    id_aa64smfr0_el1: Option<u64>,
+    cntid: Option<u64>,
+    id_aa64isar2_el1: Option<u64>,
+    trcdevarch: Option<u64>,
+    id_aa64zfr0: Option<u64>,
+    id_aa64fpfr0: Option<u64>,
 }
 
 impl RegistersInfo {
     pub(crate) fn new() -> RegistersInfo {
         RegistersInfo {
             id_aa64dfr0: read_register(Register::ID_AA64DFR0_EL1),
-            //id_aa64dfr1: CPUInfo::read_register(Register::ID_AA64DFR1_EL1),
+            id_aa64dfr1: read_register(Register::ID_AA64DFR1_EL1),
             id_aa64isar0: read_register(Register::ID_AA64ISAR0_EL1),
             id_aa64isar1: read_register(Register::ID_AA64ISAR1_EL1),
             id_aa64mmfr0: read_register(Register::ID_AA64MMFR0_EL1),
             id_aa64mmfr1: read_register(Register::ID_AA64MMFR1_EL1),
             id_aa64mmfr2: read_register(Register::ID_AA64MMFR2_EL1),
+            id_aa64mmfr3: read_register(Register::ID_AA64MMFR3_EL1),
+            id_aa64mmfr4: read_register(Register::ID_AA64MMFR4_EL1),
             id_aa64pfr0: read_register(Register::ID_AA64PFR0_EL1),
             id_aa64pfr1: read_register(Register::ID_AA64PFR1_EL1),
+            id_aa64pfr2: read_register(Register::ID_AA64PFR2_EL1),
             id_aa64isar3_el1: read_register(Register::ID_AA64ISAR3_EL1),
-            //id_aa64zfr0: CPUInfo::read_register(Register::ID_AA64ZFR0_EL1),
-            //zcr: u64,
             ctr_el0: read_register(Register::CTR_EL0),
             eddevid: read_register(Register::EDDEVID),
             id_aa64smfr0_el1: read_register(Register::ID_AA64SMFR0_EL1),
@@ -70,26 +268,29 @@ impl RegistersInfo {
             id_aa64isar2_el1: read_register(Register::ID_AA64ISAR2_EL1),
             trcdevarch: read_register(Register::TRCDEVARCH),
             id_aa64zfr0: read_register(Register::ID_AA64ZFR0_EL1),
+            id_aa64fpfr0: read_register(Register::ID_AA64FPFR0_EL1),
         }
     }
 }
 
 impl Index<Register> for RegistersInfo {
-    type Output = u64;
+    type Output = Option<u64>;
 
     fn index(&self, index: Register) -> &Self::Output {
         match index {
             Register::ID_AA64DFR0_EL1 => &self.id_aa64dfr0,
-            //Register::ID_AA64DFR1_EL1 => &self.id_aa64dfr1,
+            Register::ID_AA64DFR1_EL1 => &self.id_aa64dfr1,
             Register::ID_AA64ISAR0_EL1 => &self.id_aa64isar0,
             Register::ID_AA64ISAR1_EL1 => &self.id_aa64isar1,
             Register::ID_AA64MMFR0_EL1 => &self.id_aa64mmfr0,
             Register::ID_AA64MMFR1_EL1 => &self.id_aa64mmfr1,
             Register::ID_AA64MMFR2_EL1 => &self.id_aa64mmfr2,
+            Register::ID_AA64MMFR3_EL1 => &self.id_aa64mmfr3,
+            Register::ID_AA64MMFR4_EL1 => &self.id_aa64mmfr4,
             Register::ID_AA64PFR0_EL1 => &self.id_aa64pfr0,
             Register::ID_AA64PFR1_EL1 => &self.id_aa64pfr1,
+            Register::ID_AA64PFR2_EL1 => &self.id_aa64pfr2,
             Register::ID_AA64ISAR3_EL1 => &self.id_aa64isar3_el1,
-            //Register::ID_AA64ZFR0_EL1 => &self.id_aa64zfr0,
             Register::EDDEVID => &self.eddevid,
             Register::CTR_EL0 => &self.ctr_el0,
             Register::ID_AA64SMFR0_EL1 => &self.id_aa64smfr0_el1,
@@ -97,7 +298,86 @@ impl Index<Register> for RegistersInfo {
             Register::ID_AA64ISAR2_EL1 => &self.id_aa64isar2_el1,
             Register::TRCDEVARCH => &self.trcdevarch,
             Register::ID_AA64ZFR0_EL1 => &self.id_aa64zfr0,
-            Register::NoRegister => todo!(),
+            Register::ID_AA64FPFR0_EL1 => &self.id_aa64fpfr0,
+            Register::NoRegister => &None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_snapshot() {
+        let snapshot: RegisterSnapshot = "# Neoverse N1
+            ID_AA64ISAR0_EL1=0x0000100010211120
+
+            ID_AA64PFR0_EL1 = 1157706579479433489
+            "
+        .parse()
+        .unwrap();
+
+        assert_eq!(
+            snapshot.get(Register::ID_AA64ISAR0_EL1),
+            Some(0x0000100010211120)
+        );
+        assert_eq!(
+            snapshot.get(Register::ID_AA64PFR0_EL1),
+            Some(0x1011_0000_1001_1111)
+        );
+        assert_eq!(snapshot.get(Register::ID_AA64ISAR1_EL1), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut snapshot = RegisterSnapshot::new();
+        snapshot.set(Register::ID_AA64ZFR0_EL1, 0x0110_0110_0000_0011);
+        snapshot.set(Register::CTR_EL0, 0);
+
+        let text = snapshot.to_string();
+
+        assert_eq!(
+            text,
+            "CTR_EL0=0x0000000000000000\nID_AA64ZFR0_EL1=0x0110011000000011\n"
+        );
+        assert_eq!(text.parse::<RegisterSnapshot>(), Ok(snapshot));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("ID_AA64ISAR0_EL1".parse::<RegisterSnapshot>().is_err());
+        assert!("NoRegister=0".parse::<RegisterSnapshot>().is_err());
+        assert!("ID_AA64FOO_EL1=0".parse::<RegisterSnapshot>().is_err());
+        assert!("ID_AA64ISAR0_EL1=0xzz".parse::<RegisterSnapshot>().is_err());
+        assert_eq!("".parse::<RegisterSnapshot>(), Ok(RegisterSnapshot::new()));
+    }
+
+    #[test]
+    fn test_read() {
+        let snapshot = RegisterSnapshot::read();
+
+        if crate::check_availability() {
+            assert!(snapshot.get(Register::ID_AA64ISAR0_EL1).is_some());
+        } else {
+            // MRS would trap, e.g., on macOS or Windows
+            assert_eq!(snapshot, RegisterSnapshot::new());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut snapshot = RegisterSnapshot::new();
+        snapshot.set(Register::ID_AA64ISAR0_EL1, 0x0021_0000);
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+
+        assert_eq!(json, r#"{"ID_AA64ISAR0_EL1":"0x0000000000210000"}"#);
+        assert_eq!(
+            serde_json::from_str::<RegisterSnapshot>(&json).unwrap(),
+            snapshot
+        );
+        assert!(serde_json::from_str::<RegisterSnapshot>(r#"{"FOO":"0x0"}"#).is_err());
+    }
+}
@@ -1,64 +1,436 @@
-pub(super) fn has_sve() -> bool {
-    use std::arch::asm;
-    let tmp: u64;
-    unsafe {
-        asm!("mrs {tmp}, ID_AA64PFR0_EL1", tmp = out(reg) tmp);
-    };
-    // SVE, bits [35:32]
-    let shifted_bits = tmp >> 32;
-    let shifted_bits = shifted_bits & 0b1111;
-    shifted_bits == 0b0001
+use std::fmt;
+
+// linux/prctl.h, the same for PR_SVE_* and PR_SME_*
+const PR_VL_LEN_MASK: i32 = 0xffff;
+const PR_VL_INHERIT: i32 = 1 << 17;
+
+/// The architectural limits of the vector length in bytes
+const VL_MIN: u64 = 16;
+const VL_MAX: u64 = 256;
+
+/// SVE or the streaming SVE mode of SME
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VectorExtension {
+    Sve,
+    Sme,
 }
 
-pub(super) fn has_sme() -> bool {
-    use std::arch::asm;
-    let tmp: u64;
-    unsafe {
-        asm!("mrs {tmp}, ID_AA64PFR0_EL1", tmp = out(reg) tmp);
-    };
-    // SME, bits [27:24]
-    let shifted_bits = tmp >> 24;
-    let shifted_bits = shifted_bits & 0b1111;
-    shifted_bits == 0b0001
+/// The error of [`set_sve_vl`] and [`set_sme_vl`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetVectorLengthError {
+    /// The length in bits is not a multiple of 128 between 128 and 2048
+    InvalidLength(u64),
+    /// The core has no SVE or SME, or the OS cannot set the length
+    Unsupported,
+    /// The OS failed with the errno
+    Os(i32),
 }
 
-pub(super) fn is_streaming_sve_mode() -> bool {
-    use std::arch::asm;
-    let tmp: u64;
-    unsafe {
-        asm!("mrs {tmp}, SVCR", tmp = out(reg) tmp);
-    };
-    // SM, bit [0]
-    let shifted_bits = tmp & 0b0001;
-    shifted_bits == 0b1
+impl fmt::Display for SetVectorLengthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SetVectorLengthError::InvalidLength(bits) => {
+                write!(f, "invalid vector length: {bits} bits")
+            }
+            SetVectorLengthError::Unsupported => {
+                write!(f, "setting the vector length is not supported")
+            }
+            SetVectorLengthError::Os(errno) => {
+                write!(f, "cannot set the vector length: errno {errno}")
+            }
+        }
+    }
 }
 
-pub(super) fn get_za_size() -> Option<u64> {
-    if !is_streaming_sve_mode() {
-        return None;
+impl std::error::Error for SetVectorLengthError {}
+
+/// A vector length as reported by `prctl(PR_SVE_GET_VL)` or
+/// `prctl(PR_SME_GET_VL)` on Linux
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VectorLength {
+    bytes: u64,
+    inherit: bool,
+}
+
+impl VectorLength {
+    /// Decode the return value of `prctl(PR_SVE_GET_VL)` or
+    /// `prctl(PR_SME_GET_VL)`. Returns `None` for errors, i.e.,
+    /// negative values.
+    ///
+    /// ```rust
+    /// use aarch64_features::VectorLength;
+    ///
+    /// // 32 bytes with PR_SVE_VL_INHERIT
+    /// let vl = VectorLength::from_prctl(32 | 1 << 17).unwrap();
+    ///
+    /// assert_eq!(vl.bits(), 256);
+    /// assert!(vl.inherit());
+    /// assert_eq!(VectorLength::from_prctl(-22), None);
+    /// ```
+    pub fn from_prctl(ret: i32) -> Option<Self> {
+        if ret < 0 {
+            return None;
+        }
+
+        Some(VectorLength {
+            bytes: u64::try_from(ret & PR_VL_LEN_MASK).ok()?,
+            inherit: ret & PR_VL_INHERIT != 0,
+        })
+    }
+
+    /// The vector length in bits
+    pub fn bits(&self) -> u64 {
+        self.bytes * 8
     }
 
-    if let Some(sve_len) = get_sve_len() {
-        return Some((sve_len / 8) * (sve_len / 8));
+    /// The vector length in bytes
+    pub fn bytes(&self) -> u64 {
+        self.bytes
     }
 
-    None
+    /// Whether the vector length is kept across `execve`
+    pub fn inherit(&self) -> bool {
+        self.inherit
+    }
 }
 
-pub(super) fn get_sve_len() -> Option<u64> {
-    if !has_sve() {
-        return None;
+/// The geometry of the ZA tiles for one element size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ZaTiles {
+    count: u64,
+    dimension: u64,
+}
+
+impl ZaTiles {
+    /// The number of tiles, e.g., 4 for 32-bit elements
+    pub fn count(&self) -> u64 {
+        self.count
     }
 
-    use std::arch::asm;
+    /// The number of rows and columns of each tile
+    pub fn dimension(&self) -> u64 {
+        self.dimension
+    }
+}
+
+/// The SVE and SME vector lengths of the current thread, see
+/// [`vector_lengths`](crate::vector_lengths)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct VectorLengths {
+    sve: Option<VectorLength>,
+    streaming_sve: Option<VectorLength>,
+    sve_default: Option<u64>,
+    sme_default: Option<u64>,
+}
 
-    let tmp: u64;
-    unsafe {
-        asm!("mrs {tmp}, ZCR_EL3", tmp = out(reg) tmp);
-    };
+impl VectorLengths {
+    #[cfg_attr(
+        not(all(target_arch = "aarch64", target_os = "linux")),
+        allow(dead_code)
+    )]
+    pub(crate) fn new(
+        sve: Option<VectorLength>,
+        streaming_sve: Option<VectorLength>,
+        sve_default: Option<u64>,
+        sme_default: Option<u64>,
+    ) -> Self {
+        VectorLengths {
+            sve,
+            streaming_sve,
+            sve_default,
+            sme_default,
+        }
+    }
 
-    // LEN, bits [3:0]
+    /// The current SVE vector length. Returns `None` without SVE.
+    pub fn sve(&self) -> Option<VectorLength> {
+        self.sve
+    }
+
+    /// The current streaming SVE vector length of SME, i.e., `SVL`.
+    /// Returns `None` without SME.
+    pub fn streaming_sve(&self) -> Option<VectorLength> {
+        self.streaming_sve
+    }
+
+    /// The SVE vector length of new processes in bits from
+    /// `/proc/sys/abi/sve_default_vector_length`. The kernel default is
+    /// 512 bits, capped at the maximum of the hardware, and the
+    /// administrator may change it. The maximum is the last of
+    /// [`supported_sve_vls`](crate::supported_sve_vls).
+    pub fn sve_default(&self) -> Option<u64> {
+        self.sve_default
+    }
+
+    /// The streaming SVE vector length of new processes in bits from
+    /// `/proc/sys/abi/sme_default_vector_length`
+    pub fn sme_default(&self) -> Option<u64> {
+        self.sme_default
+    }
+
+    /// The size of the ZA storage in bytes, `SVL_B` x `SVL_B` for the
+    /// streaming vector length `SVL_B` in bytes
+    pub fn za_size(&self) -> Option<u64> {
+        self.streaming_sve.map(|svl| svl.bytes() * svl.bytes())
+    }
+
+    /// The ZA tiles for elements of 8, 16, 32, 64, or 128 bits. Returns
+    /// `None` without SME or for other element sizes.
+    ///
+    /// ```rust
+    /// use aarch64_features::vector_lengths;
+    ///
+    /// if let Some(tiles) = vector_lengths().za_tiles(32) {
+    ///     // ZA0.S to ZA3.S
+    ///     assert_eq!(tiles.count(), 4);
+    /// }
+    /// ```
+    pub fn za_tiles(&self, element_bits: u64) -> Option<ZaTiles> {
+        if !matches!(element_bits, 8 | 16 | 32 | 64 | 128) {
+            return None;
+        }
+
+        self.streaming_sve.map(|svl| ZaTiles {
+            count: element_bits / 8,
+            dimension: svl.bits() / element_bits,
+        })
+    }
+}
+
+/// All SVE vector lengths in bits that the current thread can use, in
+/// ascending order. Returns an empty list without SVE or if the OS
+/// cannot set the vector length.
+///
+/// On Linux, the lengths are probed with `prctl(PR_SVE_SET_VL)` from
+/// the longest down, the kernel picks the longest supported length up
+/// to the requested one. The probing runs on a short-lived thread, so
+/// the vector length and the SVE register state of the current thread
+/// are not changed.
+///
+/// ```rust
+/// use aarch64_features::{supported_sve_vls, sve_vector_length};
+///
+/// if let Some(bits) = sve_vector_length() {
+///     assert!(supported_sve_vls().contains(&bits));
+/// }
+/// ```
+pub fn supported_sve_vls() -> Vec<u64> {
+    crate::supported_vector_lengths(VectorExtension::Sve)
+}
+
+/// All streaming SVE vector lengths in bits that the current thread
+/// can use, in ascending order, see [`supported_sve_vls`]. Returns an
+/// empty list without SME.
+pub fn supported_sme_vls() -> Vec<u64> {
+    crate::supported_vector_lengths(VectorExtension::Sme)
+}
 
-    let len = tmp & 0b1111;
-    Some((len + 1) * 128)
+/// Set the SVE vector length of the current thread to `bits` with
+/// `prctl(PR_SVE_SET_VL)`. The OS picks the longest supported length up
+/// to `bits`, which is returned. With `inherit`, the length is kept
+/// across `execve`.
+///
+/// # Safety
+///
+/// Changing the vector length discards the SVE register state of the
+/// current thread beyond the NEON registers, and the sizes of the `Z`
+/// and `P` registers change. The caller must ensure that no code on the
+/// current thread depends on the old state or length, e.g., a function
+/// compiled with SVE further up the stack.
+///
+/// ```rust
+/// use aarch64_features::{set_sve_vl, SetVectorLengthError};
+///
+/// // SAFETY: an invalid length is rejected without changing anything
+/// let vl = unsafe { set_sve_vl(100, false) };
+///
+/// assert_eq!(vl, Err(SetVectorLengthError::InvalidLength(100)));
+/// ```
+pub unsafe fn set_sve_vl(bits: u64, inherit: bool) -> Result<VectorLength, SetVectorLengthError> {
+    let bytes = vector_length_bytes(bits)?;
+
+    crate::set_vector_length(VectorExtension::Sve, bytes, inherit)
+}
+
+/// Set the streaming SVE vector length of the current thread to `bits`
+/// with `prctl(PR_SME_SET_VL)`, see [`set_sve_vl`]. It disables ZA and
+/// the streaming mode.
+///
+/// # Safety
+///
+/// Changing the streaming vector length discards the ZA and streaming
+/// SVE state of the current thread. The caller must ensure that no code
+/// on the current thread is in streaming mode or has live ZA state, see
+/// [`set_sve_vl`].
+pub unsafe fn set_sme_vl(bits: u64, inherit: bool) -> Result<VectorLength, SetVectorLengthError> {
+    let bytes = vector_length_bytes(bits)?;
+
+    crate::set_vector_length(VectorExtension::Sme, bytes, inherit)
+}
+
+/// Check a vector length in bits and convert it to bytes
+fn vector_length_bytes(bits: u64) -> Result<u64, SetVectorLengthError> {
+    let bytes = bits / 8;
+
+    if !bits.is_multiple_of(128) || !(VL_MIN..=VL_MAX).contains(&bytes) {
+        return Err(SetVectorLengthError::InvalidLength(bits));
+    }
+
+    Ok(bytes)
+}
+
+/// Enumerate the supported vector lengths with `set`, which requests a
+/// length in bytes and returns the length that the OS picked. Returns
+/// the lengths in bits in ascending order.
+#[cfg_attr(
+    not(all(target_arch = "aarch64", target_os = "linux")),
+    allow(dead_code)
+)]
+pub(crate) fn probe_vector_lengths(mut set: impl FnMut(u64) -> Option<u64>) -> Vec<u64> {
+    let mut lengths = Vec::new();
+    let mut request = VL_MAX;
+
+    while request >= VL_MIN {
+        let Some(bytes) = set(request) else {
+            break;
+        };
+        // the OS never picks a longer length, but avoid looping forever
+        if bytes < VL_MIN || bytes > request {
+            break;
+        }
+
+        lengths.push(bytes * 8);
+        request = bytes - VL_MIN;
+    }
+
+    lengths.reverse();
+    lengths
+}
+
+/// Parse `/proc/sys/abi/sve_default_vector_length` or
+/// `/proc/sys/abi/sme_default_vector_length`, which are in bytes.
+/// Returns the vector length in bits.
+#[cfg_attr(
+    not(all(target_arch = "aarch64", target_os = "linux")),
+    allow(dead_code)
+)]
+pub(crate) fn parse_default_vector_length(text: &str) -> Option<u64> {
+    let bytes: u64 = text.trim().parse().ok()?;
+
+    (bytes > 0).then_some(bytes * 8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_prctl() {
+        assert_eq!(
+            VectorLength::from_prctl(16),
+            Some(VectorLength {
+                bytes: 16,
+                inherit: false
+            })
+        );
+        // PR_SVE_VL_INHERIT
+        assert_eq!(
+            VectorLength::from_prctl(256 | 1 << 17),
+            Some(VectorLength {
+                bytes: 256,
+                inherit: true
+            })
+        );
+        // PR_SVE_SET_VL_ONEXEC is not part of the length
+        assert_eq!(VectorLength::from_prctl(64 | 1 << 18).unwrap().bits(), 512);
+        // EINVAL without SVE
+        assert_eq!(VectorLength::from_prctl(-22), None);
+    }
+
+    #[test]
+    fn test_za() {
+        let svl = VectorLength::from_prctl(64);
+        let lengths = VectorLengths::new(None, svl, None, Some(512));
+
+        assert_eq!(lengths.za_size(), Some(64 * 64));
+        assert_eq!(
+            lengths.za_tiles(8),
+            Some(ZaTiles {
+                count: 1,
+                dimension: 64
+            })
+        );
+        assert_eq!(
+            lengths.za_tiles(32),
+            Some(ZaTiles {
+                count: 4,
+                dimension: 16
+            })
+        );
+        assert_eq!(lengths.za_tiles(128).unwrap().count(), 16);
+        assert_eq!(lengths.za_tiles(24), None);
+
+        assert_eq!(VectorLengths::default().za_size(), None);
+        assert_eq!(VectorLengths::default().za_tiles(32), None);
+    }
+
+    #[test]
+    fn test_vector_length_bytes() {
+        assert_eq!(vector_length_bytes(128), Ok(16));
+        assert_eq!(vector_length_bytes(2048), Ok(256));
+        assert_eq!(
+            vector_length_bytes(0),
+            Err(SetVectorLengthError::InvalidLength(0))
+        );
+        assert_eq!(
+            vector_length_bytes(192),
+            Err(SetVectorLengthError::InvalidLength(192))
+        );
+        assert_eq!(
+            vector_length_bytes(4096),
+            Err(SetVectorLengthError::InvalidLength(4096))
+        );
+    }
+
+    /// Mimic the kernel, which picks the longest supported length up to
+    /// the requested one or the shortest one
+    fn kernel(supported: &[u64]) -> impl FnMut(u64) -> Option<u64> + '_ {
+        |request| {
+            supported
+                .iter()
+                .copied()
+                .filter(|bytes| *bytes <= request)
+                .max()
+                .or_else(|| supported.iter().copied().min())
+        }
+    }
+
+    #[test]
+    fn test_probe_vector_lengths() {
+        // Neoverse V1
+        assert_eq!(probe_vector_lengths(kernel(&[16, 32])), vec![128, 256]);
+        // Neoverse N2
+        assert_eq!(probe_vector_lengths(kernel(&[16])), vec![128]);
+        // A64FX
+        assert_eq!(
+            probe_vector_lengths(kernel(&[16, 32, 64])),
+            vec![128, 256, 512]
+        );
+        // SME without 128 bits
+        assert_eq!(
+            probe_vector_lengths(kernel(&[32, 64, 256])),
+            vec![256, 512, 2048]
+        );
+        // without SVE
+        assert_eq!(probe_vector_lengths(|_| None), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn test_parse_default_vector_length() {
+        assert_eq!(parse_default_vector_length("64\n"), Some(512));
+        assert_eq!(parse_default_vector_length("16"), Some(128));
+        assert_eq!(parse_default_vector_length("0\n"), None);
+        assert_eq!(parse_default_vector_length("-1"), None);
+        assert_eq!(parse_default_vector_length(""), None);
+    }
 }
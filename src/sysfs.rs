@@ -0,0 +1,211 @@
+//! Reader for the per-CPU identification registers that the Linux
+//! kernel exports in sysfs, i.e.,
+//! `/sys/devices/system/cpu/cpu*/regs/identification/midr_el1` and
+//! `revidr_el1`.
+//!
+//! [`MidrEL1::new`](crate::midr::MidrEL1::new) reads `MIDR_EL1` on
+//! whatever core the thread is scheduled on. On big.LITTLE systems,
+//! sysfs reports the register of every core.
+//!
+//! ```rust,no_run
+//! use aarch64_features::sysfs::{read_midrs, SYSFS_CPU_ROOT};
+//!
+//! for (cpu, midr, core) in read_midrs(SYSFS_CPU_ROOT).unwrap() {
+//!     println!("cpu{cpu}: {midr:?} {core:?}");
+//! }
+//! ```
+
+use crate::cpu_type::Core;
+use crate::midr::{Midr, MidrEL1};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The default sysfs directory with one `cpu<N>` directory per CPU
+pub const SYSFS_CPU_ROOT: &str = "/sys/devices/system/cpu";
+
+/// Read `MIDR_EL1` of all CPUs below `root`, sorted by the CPU
+/// number. CPUs without identification registers, e.g., offline
+/// CPUs, are skipped.
+pub fn read_midrs(root: impl AsRef<Path>) -> io::Result<Vec<(usize, Midr, Option<Core>)>> {
+    let mut midrs = Vec::new();
+
+    for cpu in cpus(root.as_ref())? {
+        let Some(value) = read_register(root.as_ref(), cpu, "midr_el1")? else {
+            continue;
+        };
+
+        let midr = Midr::from(value);
+        let core = Core::try_from(MidrEL1::Known(midr.clone())).ok();
+
+        midrs.push((cpu, midr, core));
+    }
+
+    Ok(midrs)
+}
+
+/// Read `REVIDR_EL1` of all CPUs below `root`, sorted by the CPU
+/// number. The register is implementation defined and identifies
+/// revisions, e.g., errata fixes, that `MIDR_EL1` does not capture.
+pub fn read_revidrs(root: impl AsRef<Path>) -> io::Result<Vec<(usize, u64)>> {
+    let mut revidrs = Vec::new();
+
+    for cpu in cpus(root.as_ref())? {
+        if let Some(value) = read_register(root.as_ref(), cpu, "revidr_el1")? {
+            revidrs.push((cpu, value));
+        }
+    }
+
+    Ok(revidrs)
+}
+
+/// Read the numbers of all online CPUs from the `online` file below `root`
+pub fn online_cpus(root: impl AsRef<Path>) -> io::Result<Vec<usize>> {
+    let text = std::fs::read_to_string(root.as_ref().join("online"))?;
+
+    parse_cpu_list(&text)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid cpu list"))
+}
+
+/// Parse a cpu list of the kernel, e.g., `0-3,8,10-11`
+pub fn parse_cpu_list(text: &str) -> Option<Vec<usize>> {
+    let mut cpus = Vec::new();
+
+    for range in text.trim().split(',').filter(|range| !range.is_empty()) {
+        match range.split_once('-') {
+            Some((first, last)) => {
+                let first = first.parse::<usize>().ok()?;
+                let last = last.parse::<usize>().ok()?;
+                if first > last {
+                    return None;
+                }
+                cpus.extend(first..=last);
+            }
+            None => cpus.push(range.parse::<usize>().ok()?),
+        }
+    }
+
+    Some(cpus)
+}
+
+/// the numbers of all `cpu<N>` directories
+pub(crate) fn cpus(root: &Path) -> io::Result<Vec<usize>> {
+    let mut cpus = Vec::new();
+
+    for entry in std::fs::read_dir(root)? {
+        let name = entry?.file_name();
+        let Some(number) = name.to_str().and_then(|name| name.strip_prefix("cpu")) else {
+            continue;
+        };
+        if let Ok(cpu) = number.parse::<usize>() {
+            cpus.push(cpu);
+        }
+    }
+
+    cpus.sort_unstable();
+
+    Ok(cpus)
+}
+
+/// Returns `None` if the register file does not exist
+fn read_register(root: &Path, cpu: usize, register: &str) -> io::Result<Option<u64>> {
+    let path: PathBuf = root
+        .join(format!("cpu{cpu}"))
+        .join("regs/identification")
+        .join(register);
+
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    let text = text.trim();
+    let value = u64::from_str_radix(text.strip_prefix("0x").unwrap_or(text), 16)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    Ok(Some(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::Fixture;
+    use crate::midr::Implementer;
+
+    fn write_cpu(fixture: &Fixture, cpu: usize, midr: Option<&str>, revidr: Option<&str>) {
+        let mut files = Vec::new();
+        if let Some(midr) = midr {
+            files.push(("midr_el1", midr));
+        }
+        if let Some(revidr) = revidr {
+            files.push(("revidr_el1", revidr));
+        }
+        fixture.write(format!("cpu{cpu}/regs/identification"), &files);
+    }
+
+    #[test]
+    fn test_big_little() {
+        let fixture = Fixture::new("big_little");
+        // Cortex-A55
+        write_cpu(
+            &fixture,
+            0,
+            Some("0x00000000412fd050\n"),
+            Some("0x0000000000000000\n"),
+        );
+        // Neoverse N1
+        write_cpu(
+            &fixture,
+            10,
+            Some("0x00000000413fd0c1\n"),
+            Some("0x0000000000000100\n"),
+        );
+        write_cpu(&fixture, 2, Some("0x00000000413fd0c1\n"), None);
+        // offline
+        write_cpu(&fixture, 3, None, None);
+        std::fs::create_dir_all(fixture.root().join("cpufreq")).unwrap();
+        std::fs::write(fixture.root().join("online"), "0-3,10\n").unwrap();
+
+        let midrs = read_midrs(fixture.root()).unwrap();
+
+        assert_eq!(midrs.len(), 3);
+        assert_eq!(midrs[0].0, 0);
+        assert_eq!(midrs[1].0, 2);
+        assert_eq!(midrs[2].0, 10);
+        assert!(midrs[0].1.check_implementer(Implementer::Arm));
+        assert!(midrs[0].1.check_part_num(0xd05));
+        assert_eq!(midrs[0].2, None);
+        assert_eq!(midrs[1].2, Some(Core::NeoverseN1));
+        assert_eq!(midrs[2].2, Some(Core::NeoverseN1));
+
+        let revidrs = read_revidrs(fixture.root()).unwrap();
+        assert_eq!(revidrs, vec![(0, 0), (10, 0x100)]);
+    }
+
+    #[test]
+    fn test_invalid_register() {
+        let fixture = Fixture::new("invalid");
+        write_cpu(&fixture, 0, Some("garbage\n"), None);
+
+        let err = read_midrs(fixture.root()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_cpu_list() {
+        assert_eq!(parse_cpu_list("0-3,10\n"), Some(vec![0, 1, 2, 3, 10]));
+        assert_eq!(parse_cpu_list("0"), Some(vec![0]));
+        assert_eq!(parse_cpu_list("\n"), Some(vec![]));
+        assert_eq!(parse_cpu_list("3-1"), None);
+        assert_eq!(parse_cpu_list("a-b"), None);
+
+        let fixture = Fixture::new("online");
+        std::fs::write(fixture.root().join("online"), "0-1,4\n").unwrap();
+        assert_eq!(online_cpus(fixture.root()).unwrap(), vec![0, 1, 4]);
+    }
+
+    #[test]
+    fn test_missing_root() {
+        assert!(read_midrs("/nonexistent/aarch64_features").is_err());
+    }
+}
@@ -0,0 +1,285 @@
+use crate::Feature;
+use std::collections::HashSet;
+use std::fmt;
+
+/// The rustc target features of a set of features, see [`target_features`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TargetFeatures {
+    names: Vec<&'static str>,
+    unmapped: Vec<Feature>,
+}
+
+impl TargetFeatures {
+    /// The names of the enabled target features, e.g., `lse` or `rcpc`
+    pub fn names(&self) -> &[&'static str] {
+        &self.names
+    }
+
+    /// The features of the set without a rustc target feature, sorted
+    /// like [`Feature`]. This includes features of a target feature
+    /// that requires further features, e.g.,
+    /// [FEAT_AES](Feature::FEAT_AES) without
+    /// [FEAT_PMULL](Feature::FEAT_PMULL).
+    pub fn unmapped(&self) -> &[Feature] {
+        &self.unmapped
+    }
+
+    /// The argument for rustc, e.g., `-C target-feature=+lse,+rcpc`.
+    /// It is empty if no target feature is enabled.
+    pub fn rustflags(&self) -> String {
+        if self.names.is_empty() {
+            return String::new();
+        }
+
+        let features: Vec<String> = self.names.iter().map(|name| format!("+{name}")).collect();
+
+        format!("-C target-feature={}", features.join(","))
+    }
+}
+
+impl fmt::Display for TargetFeatures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.rustflags())
+    }
+}
+
+impl Feature {
+    /// The rustc target features that require this feature. A target
+    /// feature may require further features, e.g., `aes` requires
+    /// [FEAT_AES](Feature::FEAT_AES) and [FEAT_PMULL](Feature::FEAT_PMULL).
+    ///
+    /// ```rust
+    /// use aarch64_features::Feature;
+    ///
+    /// assert_eq!(Feature::FEAT_LSE.rustc_target_features(), vec!["lse"]);
+    /// assert_eq!(Feature::FEAT_PAuth.rustc_target_features(), vec!["paca", "pacg"]);
+    /// assert!(Feature::FEAT_VPIPT.rustc_target_features().is_empty());
+    /// ```
+    pub fn rustc_target_features(&self) -> Vec<&'static str> {
+        TARGET_FEATURES
+            .iter()
+            .filter(|desc| desc.requires.contains(self))
+            .map(|desc| desc.name)
+            .collect()
+    }
+}
+
+/// Map a set of features to rustc target features. A target feature is
+/// enabled if all its features are in the set.
+///
+/// ```rust
+/// use aarch64_features::{target_features, Feature};
+/// use std::collections::HashSet;
+///
+/// let features = HashSet::from([Feature::FEAT_LSE, Feature::FEAT_LRCPC, Feature::FEAT_VPIPT]);
+///
+/// let target = target_features(&features);
+///
+/// assert_eq!(target.rustflags(), "-C target-feature=+lse,+rcpc");
+/// assert_eq!(target.unmapped(), &[Feature::FEAT_VPIPT]);
+/// ```
+pub fn target_features(features: &HashSet<Feature>) -> TargetFeatures {
+    let enabled: Vec<&TargetFeatureDescription> = TARGET_FEATURES
+        .iter()
+        .filter(|desc| desc.requires.iter().all(|feat| features.contains(feat)))
+        .collect();
+
+    let mut unmapped: Vec<Feature> = features
+        .iter()
+        .filter(|feat| !enabled.iter().any(|desc| desc.requires.contains(feat)))
+        .copied()
+        .collect();
+    unmapped.sort();
+
+    TargetFeatures {
+        names: enabled.iter().map(|desc| desc.name).collect(),
+        unmapped,
+    }
+}
+
+struct TargetFeatureDescription {
+    name: &'static str,
+    requires: &'static [Feature],
+}
+
+macro_rules! declare_target_features {
+    ($(
+        ($name:literal, [$($feature:ident),+]),
+    )+) => {
+        /// The aarch64 target features of rustc and the features they require
+        const TARGET_FEATURES: &[TargetFeatureDescription] = &[
+            $(
+                TargetFeatureDescription {
+                    name: $name,
+                    requires: &[$(Feature::$feature),+],
+                }
+            ),+
+        ];
+
+        /// Whether the feature is enabled at compile time, e.g., with
+        /// `-C target-feature=+lse`. The result is a constant for a
+        /// constant feature. Other architectures share some of the
+        /// names, e.g., `aes` on x86_64, so it is always false there.
+        #[inline]
+        pub(crate) fn statically_enabled(feature: Feature) -> bool {
+            cfg!(target_arch = "aarch64") && ($(
+                (cfg!(target_feature = $name) && [$(Feature::$feature),+].contains(&feature))
+            )||+)
+        }
+    }
+}
+
+#[rustfmt::skip]
+declare_target_features!(
+    // Armv8.0
+    ("neon",          [FEAT_AdvSIMD]),
+    ("aes",           [FEAT_AES, FEAT_PMULL]),
+    ("sha2",          [FEAT_SHA1, FEAT_SHA256]),
+    ("sb",            [FEAT_SB]),
+    ("ssbs",          [FEAT_SSBS]),
+    // Armv8.1
+    ("lse",           [FEAT_LSE]),
+    ("rdm",           [FEAT_RDM]),
+    ("lor",           [FEAT_LOR]),
+    ("pan",           [FEAT_PAN]),
+    ("vh",            [FEAT_VHE]),
+    // Armv8.2
+    ("sha3",          [FEAT_SHA3, FEAT_SHA512]),
+    ("sm4",           [FEAT_SM3, FEAT_SM4]),
+    ("fp16",          [FEAT_FP16]),
+    ("fhm",           [FEAT_FHM]),
+    ("dotprod",       [FEAT_DotProd]),
+    ("dpb",           [FEAT_DPB]),
+    ("dpb2",          [FEAT_DPB2]),
+    ("ras",           [FEAT_RAS]),
+    ("spe",           [FEAT_SPE]),
+    ("sve",           [FEAT_SVE]),
+    ("f32mm",         [FEAT_F32MM]),
+    ("f64mm",         [FEAT_F64MM]),
+    ("bf16",          [FEAT_BF16]),
+    ("i8mm",          [FEAT_I8MM]),
+    // Armv8.3
+    ("fcma",          [FEAT_FCMA]),
+    ("jsconv",        [FEAT_JSCVT]),
+    ("rcpc",          [FEAT_LRCPC]),
+    ("paca",          [FEAT_PAuth]),
+    // FEAT_PAuth includes PACGA, the generic authentication of pacg
+    ("pacg",          [FEAT_PAuth]),
+    // Armv8.4
+    ("dit",           [FEAT_DIT]),
+    ("flagm",         [FEAT_FlagM]),
+    ("rcpc2",         [FEAT_LRCPC2]),
+    ("lse2",          [FEAT_LSE2]),
+    // Armv8.5
+    ("flagm2",        [FEAT_FlagM2]),
+    ("frintts",       [FEAT_FRINTTS]),
+    ("bti",           [FEAT_BTI]),
+    ("rand",          [FEAT_RNG]),
+    ("mte",           [FEAT_MTE, FEAT_MTE2]),
+    // Armv8.6
+    ("ecv",           [FEAT_ECV]),
+    // Armv8.7
+    ("wfxt",          [FEAT_WFxT]),
+    // Armv8.8
+    ("mops",          [FEAT_MOPS]),
+    ("hbc",           [FEAT_HBC]),
+    // Armv8.9
+    ("cssc",          [FEAT_CSSC]),
+    ("rcpc3",         [FEAT_LRCPC3]),
+    // Armv9.0
+    ("sve2",          [FEAT_SVE2]),
+    ("sve2-aes",      [FEAT_SVE_AES, FEAT_SVE_PMULL128]),
+    ("sve2-bitperm",  [FEAT_SVE_BitPerm]),
+    ("sve2-sha3",     [FEAT_SVE_SHA3]),
+    ("sve2-sm4",      [FEAT_SVE_SM4]),
+    // Armv9.2
+    ("sme",           [FEAT_SME]),
+    ("sme-fa64",      [FEAT_SME_FA64]),
+    ("sme-f64f64",    [FEAT_SME_F64F64]),
+    ("sme-i16i64",    [FEAT_SME_I16I64]),
+    // Armv9.3
+    ("sme2",          [FEAT_SME2]),
+    // Armv9.4
+    ("sve2p1",        [FEAT_SVE2p1]),
+    ("lse128",        [FEAT_LSE128]),
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_neoverse_n1() {
+        let features = HashSet::from([
+            Feature::FEAT_AdvSIMD,
+            Feature::FEAT_AES,
+            Feature::FEAT_PMULL,
+            Feature::FEAT_SHA1,
+            Feature::FEAT_SHA256,
+            Feature::FEAT_LSE,
+            Feature::FEAT_RDM,
+            Feature::FEAT_FP16,
+            Feature::FEAT_DotProd,
+            Feature::FEAT_LRCPC,
+            Feature::FEAT_DPB,
+            Feature::FEAT_SSBS,
+        ]);
+
+        let target = target_features(&features);
+
+        assert_eq!(
+            target.to_string(),
+            "-C target-feature=+neon,+aes,+sha2,+ssbs,+lse,+rdm,+fp16,+dotprod,+dpb,+rcpc"
+        );
+        assert!(target.unmapped().is_empty());
+    }
+
+    #[test]
+    fn test_partial_target_feature() {
+        let features = HashSet::from([Feature::FEAT_AES, Feature::FEAT_SHA3]);
+
+        let target = target_features(&features);
+
+        assert!(target.names().is_empty());
+        assert_eq!(target.rustflags(), "");
+        assert_eq!(target.unmapped().len(), 2);
+    }
+
+    #[test]
+    fn test_unique_names() {
+        let names: HashSet<&str> = TARGET_FEATURES.iter().map(|desc| desc.name).collect();
+
+        assert_eq!(names.len(), TARGET_FEATURES.len());
+        for name in names {
+            assert!(
+                name.chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_statically_enabled() {
+        if cfg!(target_arch = "aarch64") {
+            assert_eq!(
+                statically_enabled(Feature::FEAT_AdvSIMD),
+                cfg!(target_feature = "neon")
+            );
+        } else {
+            // e.g., `aes` and `sha2` are also x86_64 target features
+            for feature in Feature::iter() {
+                assert!(!statically_enabled(feature), "{feature:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        let target = target_features(&HashSet::new());
+
+        assert_eq!(target, TargetFeatures::default());
+        assert_eq!(target.rustflags(), "");
+    }
+}
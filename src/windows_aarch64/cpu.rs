@@ -1,4 +1,6 @@
-use crate::Feature;
+use crate::sve_sme::VectorExtension;
+use crate::{Feature, SetVectorLengthError, VectorLength, VectorLengths};
+use std::collections::HashSet;
 
 /// default implementation
 pub(crate) fn check_availability() -> bool {
@@ -8,3 +10,23 @@ pub(crate) fn check_availability() -> bool {
 pub(crate) fn is_exposed_to_userspace(_feat: Feature) -> bool {
     false
 }
+
+pub(crate) fn fallback_features() -> Option<HashSet<Feature>> {
+    None
+}
+
+pub(crate) fn current_vector_lengths() -> VectorLengths {
+    VectorLengths::default()
+}
+
+pub(crate) fn supported_vector_lengths(_extension: VectorExtension) -> Vec<u64> {
+    Vec::new()
+}
+
+pub(crate) fn set_vector_length(
+    _extension: VectorExtension,
+    _bytes: u64,
+    _inherit: bool,
+) -> Result<VectorLength, SetVectorLengthError> {
+    Err(SetVectorLengthError::Unsupported)
+}